@@ -1,19 +1,353 @@
+use darling::util::Override;
 use darling::{FromDeriveInput, FromField};
 use proc_macro::TokenStream;
 use quote::quote;
 
 #[derive(FromDeriveInput)]
-#[darling(attributes(branded), supports(struct_newtype))]
+#[darling(attributes(branded), forward_attrs(repr), supports(struct_newtype))]
 pub(crate) struct BrandedTypeOptions {
     ident: syn::Ident,
+    generics: syn::Generics,
     data: darling::ast::Data<(), BrandedFieldOptions>,
+    attrs: Vec<syn::Attribute>,
 
+    serde: Option<Override<SerdeOptions>>,
+    uuid: Option<Override<UuidOptions>>,
+    display: Option<DisplayOptions>,
+    render: Option<syn::Path>,
+    generator: Option<GeneratorOptions>,
+    on_generate: Option<syn::Path>,
+    sqlx: Option<Override<SqlxOptions>>,
     #[darling(default)]
-    serde: bool,
+    defmt: bool,
     #[darling(default)]
-    uuid: bool,
+    polars: bool,
+    #[darling(default)]
+    influxdb: bool,
+    #[darling(default)]
+    tonic: bool,
+    #[darling(default)]
+    okapi: bool,
+    #[darling(default)]
+    aide: bool,
+    cynic: Option<syn::Path>,
+    example: Option<String>,
+    schema_format: Option<String>,
+    #[darling(default)]
+    prometheus: bool,
+    #[darling(default)]
+    mysql: bool,
+    #[darling(default)]
+    gel: bool,
+    #[darling(default)]
+    zbus: bool,
+    #[darling(default)]
+    rustler: bool,
+    #[darling(default)]
+    bitset: bool,
+    #[darling(default)]
+    compact_str: bool,
+    #[darling(default)]
+    context: bool,
+    shard: Option<ShardOptions>,
+    newtype_uuid: Option<syn::Path>,
+    #[darling(default)]
+    new_into: bool,
+    #[darling(default)]
+    const_new: bool,
+    constructor: Option<syn::Ident>,
+    constructor_vis: Option<syn::Visibility>,
+    #[darling(default)]
+    no_constructor: bool,
+    #[darling(default)]
+    inner_mut: bool,
+    #[darling(default)]
+    structural: bool,
+    #[darling(default)]
+    cast: bool,
+    #[darling(default)]
+    secret: bool,
+    #[darling(default)]
+    bytemuck: bool,
+    brand: Option<syn::LitStr>,
+    column: Option<syn::LitStr>,
+    validate: Option<syn::Path>,
+    pattern: Option<syn::LitStr>,
+    len: Option<syn::LitStr>,
+    range: Option<syn::LitStr>,
+    format: Option<StringFormat>,
+    #[darling(default)]
+    from: bool,
+    #[darling(default)]
+    try_from: bool,
+    builder: Option<Override<BuilderOptions>>,
+    #[darling(default)]
+    partial_eq_inner: bool,
+    sentry: Option<Override<SentryOptions>>,
+    alias_of: Option<syn::Path>,
+    #[darling(default)]
+    convert_from: darling::util::PathList,
+    #[darling(default)]
+    implements: darling::util::PathList,
+    #[darling(default)]
+    skip: SkipOptions,
+    selftest: Option<Override<SelftestOptions>>,
+    path: Option<syn::LitStr>,
+    checksum: Option<ChecksumAlgorithm>,
+    external: Option<ExternalOptions>,
+}
+
+/// The versioned external representations of a brand, as
+/// `external(prefix = "user", v1 = "user:{}", v2 = "typeid")`.
+pub(crate) struct ExternalOptions {
+    prefix: Option<syn::LitStr>,
+    /// The formats by version, from the latest.
+    versions: Vec<(u32, syn::LitStr)>,
+}
+
+impl darling::FromMeta for ExternalOptions {
+    fn from_list(items: &[darling::ast::NestedMeta]) -> darling::Result<Self> {
+        let mut prefix = None;
+        let mut versions: Vec<(u32, syn::LitStr)> = Vec::new();
+        for item in items {
+            let darling::ast::NestedMeta::Meta(syn::Meta::NameValue(meta)) = item else {
+                return Err(
+                    darling::Error::unsupported_format("expected `name = \"...\"`").with_span(item)
+                );
+            };
+            let name = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+            let value = syn::LitStr::from_expr(&meta.value)?;
+            if name == "prefix" {
+                prefix = Some(value);
+                continue;
+            }
+            let version = name
+                .strip_prefix('v')
+                .and_then(|version| version.parse::<u32>().ok())
+                .ok_or_else(|| darling::Error::unknown_field(&name).with_span(&meta.path))?;
+            if versions.iter().any(|(existing, _)| *existing == version) {
+                return Err(darling::Error::duplicate_field(&name).with_span(&meta.path));
+            }
+            versions.push((version, value));
+        }
+        if versions.is_empty() {
+            return Err(darling::Error::custom(
+                "expected at least one version, such as `v1 = \"{}\"`",
+            ));
+        }
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+        Ok(Self {
+            prefix,
+            versions,
+        })
+    }
+}
+
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct SqlxOptions {
+    #[darling(default)]
+    postgres: bool,
+    type_name: Option<String>,
+    table: Option<String>,
+    column: Option<String>,
+    from_row: Option<Override<String>>,
+    int128: Option<Int128Strategy>,
+    only: Option<syn::LitStr>,
+}
+
+#[derive(darling::FromMeta, Clone, Copy)]
+pub(crate) enum Int128Strategy {
+    #[darling(rename = "bytes")]
+    Bytes,
+    #[darling(rename = "text")]
+    Text,
+}
+
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct SerdeOptions {
+    #[darling(default)]
+    default_generate: bool,
+    #[darling(default)]
+    as_string: bool,
+    prefix: Option<syn::LitStr>,
+    accept_legacy: Option<syn::LitStr>,
+    on_legacy: Option<syn::Path>,
+    style: Option<SerdeStyle>,
+}
+
+#[derive(darling::FromMeta, Clone, Copy, PartialEq)]
+pub(crate) enum SerdeStyle {
+    #[darling(rename = "bare")]
+    Bare,
+    #[darling(rename = "wrapped")]
+    Wrapped,
+}
+
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct UuidOptions {
+    #[darling(default)]
+    v7: bool,
+    v5: Option<syn::LitStr>,
+    format: Option<UuidFormat>,
+}
+
+#[derive(darling::FromMeta, Clone, Copy)]
+pub(crate) enum UuidFormat {
+    #[darling(rename = "hyphenated")]
+    Hyphenated,
+    #[darling(rename = "simple")]
+    Simple,
+    #[darling(rename = "urn")]
+    Urn,
+    #[darling(rename = "braced")]
+    Braced,
+}
+
+#[derive(darling::FromMeta, Clone, Copy)]
+pub(crate) enum StringFormat {
+    #[darling(rename = "email")]
+    Email,
+    #[darling(rename = "url")]
+    Url,
+    #[darling(rename = "hostname")]
+    Hostname,
+    #[darling(rename = "slug")]
+    Slug,
+    #[darling(rename = "uuid_string")]
+    UuidString,
+}
+
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct DisplayOptions {
+    zero_pad: Option<usize>,
+}
+
+/// The generator of a brand, either as `generator = "path::to::fn"` or with nested parameters as
+/// `generator(function = "path::to::fn", default)`.
+pub(crate) struct GeneratorOptions {
+    function: syn::Path,
+    default: bool,
+}
+
+#[derive(darling::FromMeta)]
+struct GeneratorParams {
+    function: syn::Path,
+    #[darling(default)]
+    default: bool,
+}
+
+impl darling::FromMeta for GeneratorOptions {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self {
+            function: syn::Path::from_string(value)?,
+            default: false,
+        })
+    }
+
+    fn from_list(items: &[darling::ast::NestedMeta]) -> darling::Result<Self> {
+        let params = GeneratorParams::from_list(items)?;
+        Ok(Self {
+            function: params.function,
+            default: params.default,
+        })
+    }
+}
+
+#[derive(darling::FromMeta)]
+pub(crate) struct ShardOptions {
+    modulo: u16,
+}
+
+/// The conversions generated with `builder(...)`, all of them when none are given.
+#[derive(darling::FromMeta)]
+pub(crate) struct BuilderOptions {
+    #[darling(default)]
+    inner: bool,
+    #[darling(default)]
+    parse: bool,
+}
+
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct SentryOptions {
     #[darling(default)]
-    sqlx: bool,
+    pii: bool,
+}
+
+/// The sources of samples of `selftest(...)`, in addition to the default value and UUIDs.
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct SelftestOptions {
+    samples: Option<syn::Path>,
+}
+
+/// The generated trait impls that are suppressed with `skip(...)`.
+#[derive(darling::FromMeta, Default)]
+pub(crate) struct SkipOptions {
+    #[darling(default, rename = "Clone")]
+    clone: bool,
+    #[darling(default, rename = "Copy")]
+    copy: bool,
+    #[darling(default, rename = "Debug")]
+    debug: bool,
+    #[darling(default, rename = "Default")]
+    default: bool,
+    #[darling(default, rename = "Display")]
+    display: bool,
+    #[darling(default, rename = "FromStr")]
+    from_str: bool,
+    #[darling(default, rename = "PartialEq")]
+    partial_eq: bool,
+    #[darling(default, rename = "Eq")]
+    eq: bool,
+    #[darling(default, rename = "PartialOrd")]
+    partial_ord: bool,
+    #[darling(default, rename = "Ord")]
+    ord: bool,
+    #[darling(default, rename = "Hash")]
+    hash: bool,
+    #[darling(default, rename = "AsRef")]
+    as_ref: bool,
+    #[darling(default, rename = "Borrow")]
+    borrow: bool,
+}
+
+impl SkipOptions {
+    /// Get the names of the `__impl_std!` impls of the derived traits that are not skipped.
+    fn std_impls(&self) -> Vec<proc_macro2::TokenStream> {
+        [
+            (self.clone, quote!(Clone)),
+            (self.copy, quote!(Copy)),
+            (self.partial_eq, quote!(PartialEq)),
+            (self.eq, quote!(Eq)),
+            (self.partial_ord, quote!(PartialOrd)),
+            (self.ord, quote!(Ord)),
+            (self.hash, quote!(Hash)),
+        ]
+        .into_iter()
+        .filter(|(skip, _)| !skip)
+        .map(|(_, name)| name)
+        .collect()
+    }
+
+    /// Also skip the impls whose supertraits are skipped, since they could never apply.
+    fn with_dependents(self) -> Self {
+        let eq = self.eq || self.partial_eq;
+        let partial_ord = self.partial_ord || self.partial_eq;
+        Self {
+            copy: self.copy || self.clone,
+            eq,
+            partial_ord,
+            ord: self.ord || eq || partial_ord,
+            ..self
+        }
+    }
+}
+
+#[derive(darling::FromMeta, Clone, Copy)]
+pub(crate) enum ChecksumAlgorithm {
+    #[darling(rename = "luhn")]
+    Luhn,
+    #[darling(rename = "crc")]
+    Crc,
 }
 
 #[derive(FromField)]
@@ -23,20 +357,79 @@ pub(crate) struct BrandedFieldOptions {
 
 #[proc_macro_derive(Branded, attributes(branded))]
 pub fn branded_derive(input: TokenStream) -> TokenStream {
-    let input = syn::parse_macro_input!(input);
+    let input: syn::DeriveInput = syn::parse_macro_input!(input);
+    // Structs with more than one field are composite keys, which support a separate set of options.
+    if matches!(&input.data, syn::Data::Struct(data) if data.fields.len() > 1) {
+        let expanded = CompositeKeyOptions::from_derive_input(&input)
+            .map_err(darling::Error::write_errors)
+            .and_then(|options| {
+                expand_composite_key_derive(options).map_err(syn::Error::into_compile_error)
+            });
+        return match expanded {
+            Ok(expanded) | Err(expanded) => expanded.into(),
+        };
+    }
     let options = match BrandedTypeOptions::from_derive_input(&input) {
         Ok(options) => options,
         Err(err) => return err.write_errors().into(),
     };
-    let expanded = match expand_branded_derive(options) {
+    let spans = OptionSpans::new(&input.attrs, input.ident.span());
+    let expanded = match expand_branded_derive(options, &spans) {
         Ok(expanded) => expanded,
         Err(err) => return err.to_compile_error().into(),
     };
     expanded.into()
 }
 
+/// The spans of the options in the `#[branded(...)]` attributes of a brand, so that errors about
+/// an option point at the option instead of the whole brand.
+pub(crate) struct OptionSpans {
+    /// The spans by option name, with nested options named as `outer(inner)`.
+    spans: Vec<(String, proc_macro2::Span)>,
+    /// The span of errors about options that are not present.
+    fallback: proc_macro2::Span,
+}
+
+impl OptionSpans {
+    pub(crate) fn new(attrs: &[syn::Attribute], fallback: proc_macro2::Span) -> Self {
+        type Metas = syn::punctuated::Punctuated<syn::Meta, syn::Token![,]>;
+        let name = |path: &syn::Path| quote!(#path).to_string().replace(' ', "");
+        let mut spans = Vec::new();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("branded")) {
+            let Ok(metas) = attr.parse_args_with(Metas::parse_terminated) else {
+                continue;
+            };
+            for meta in metas {
+                let outer = name(meta.path());
+                if let syn::Meta::List(list) = &meta {
+                    for inner in list.parse_args_with(Metas::parse_terminated).into_iter().flatten()
+                    {
+                        let inner_name = format!("{outer}({})", name(inner.path()));
+                        spans.push((inner_name, syn::spanned::Spanned::span(&inner)));
+                    }
+                }
+                spans.push((outer, syn::spanned::Spanned::span(&meta)));
+            }
+        }
+        Self {
+            spans,
+            fallback,
+        }
+    }
+
+    /// Get an error at the first of `options` that is present, or at the brand if none is.
+    pub(crate) fn error(&self, options: &[&str], message: impl std::fmt::Display) -> syn::Error {
+        let span = options
+            .iter()
+            .find_map(|option| self.spans.iter().find(|(name, _)| name == option))
+            .map_or(self.fallback, |(_, span)| *span);
+        syn::Error::new(span, message)
+    }
+}
+
 pub(crate) fn expand_branded_derive(
     options: BrandedTypeOptions,
+    spans: &OptionSpans,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let mut tokens = proc_macro2::TokenStream::new();
     let struct_name = &options.ident;
@@ -55,244 +448,3943 @@ pub(crate) fn expand_branded_derive(
             "derive(Branded) can only be used on structs",
         ))?;
     let ty = field.ty;
+    let generics = &options.generics;
+    let brand = match &options.brand {
+        Some(brand) if brand.value().is_empty() => {
+            return Err(syn::Error::new(brand.span(), "brand name must not be empty"));
+        }
+        Some(brand) => brand.clone(),
+        None => syn::LitStr::new(&struct_name.to_string(), struct_name.span()),
+    };
+    let column = match &options.column {
+        Some(column) if column.value().is_empty() => {
+            return Err(syn::Error::new(column.span(), "column name must not be empty"));
+        }
+        Some(column) => column.clone(),
+        None => syn::LitStr::new(&to_snake_case(&brand.value()), brand.span()),
+    };
+    // Options that take nested parameters are normalized, so that the bare word form is equivalent
+    // to passing no parameters.
+    let serde = options.serde.map(Override::unwrap_or_default);
+    let sqlx = options.sqlx.map(Override::unwrap_or_default);
+    let uuid = options.uuid.map(Override::unwrap_or_default);
+    let sentry = options.sentry.map(Override::unwrap_or_default);
+    // Options that need a certain kind of inner type are checked against inner types that are
+    // obviously of another kind, so that the error points at the field instead of at the
+    // generated code.
+    if uuid.is_some() && (is_primitive_type(&ty) || is_string_type(&ty) || is_str_ref_type(&ty)) {
+        return Err(syn::Error::new_spanned(
+            &ty,
+            "the `uuid` option requires a `uuid::Uuid` inner type",
+        ));
+    }
+    let string_option = [
+        ("len", options.len.is_some()),
+        ("pattern", options.pattern.is_some()),
+        ("format", options.format.is_some()),
+    ]
+    .into_iter()
+    .find_map(|(option, present)| present.then_some(option));
+    if let Some(option) = string_option.filter(|_| is_primitive_type(&ty)) {
+        return Err(syn::Error::new_spanned(
+            &ty,
+            format!("the `{option}` option requires a string inner type"),
+        ));
+    }
+    let mut skip = options.skip.with_dependents();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let constructor_doc_comment = format!("Construct a new `{struct_name}` value.");
+    // With `new_into`, the constructor accepts anything convertible into the inner type, at the
+    // cost of integer literals no longer being inferred as the inner type.
+    // With `const_new`, the constructor can be used in const and static items.
+    // With `no_constructor`, no constructor is generated at all, so validated brands can provide
+    // their own.
+    // With `structural`, the constructor is always const, so that constants can be matched on.
+    let const_new = options.const_new || options.structural;
+    let constructor_name = options
+        .constructor
+        .clone()
+        .unwrap_or_else(|| syn::Ident::new("new", proc_macro2::Span::call_site()));
+    let constructor_vis = options.constructor_vis.clone().unwrap_or_else(|| syn::parse_quote!(pub));
+    let validation_checks = expand_validation_checks(
+        &ty,
+        options.validate.as_ref(),
+        options.pattern.as_ref(),
+        options.len.as_ref(),
+        options.range.as_ref(),
+        options.format,
+    )?;
+    let validate = !validation_checks.is_empty();
+    let (constructor_contract, try_new_contract) = expand_verify_contracts(&validation_checks);
+    let constraints = expand_constraints(
+        options.len.as_ref(),
+        options.range.as_ref(),
+        options.pattern.as_ref(),
+        options.format,
+        serde.as_ref().and_then(|serde| serde.prefix.as_ref()),
+        options.validate.is_some(),
+    )?;
+    let constructor = match (options.new_into, const_new) {
+        _ if options.no_constructor => {
+            if options.new_into
+                || const_new
+                || options.constructor.is_some()
+                || options.constructor_vis.is_some()
+            {
+                return Err(spans.error(
+                    &["no_constructor"],
+                    "`no_constructor` cannot be combined with the `new_into`, `const_new`, \
+                     `structural`, `constructor` and `constructor_vis` options",
+                ));
+            }
+            quote!()
+        }
+        (true, true) => {
+            return Err(spans.error(
+                &["new_into"],
+                "the `new_into` option cannot be combined with `const_new` and `structural`",
+            ));
+        }
+        (true, false) => quote! {
+            #[doc = #constructor_doc_comment]
+            #constructor_vis fn #constructor_name(inner: impl ::core::convert::Into<#ty>) -> Self {
+                Self(inner.into())
+            }
+        },
+        (false, true) => quote! {
+            #[doc = #constructor_doc_comment]
+            #constructor_vis const fn #constructor_name(inner: #ty) -> Self { Self(inner) }
+        },
+        (false, false) => quote! {
+            #[doc = #constructor_doc_comment]
+            #constructor_contract
+            #constructor_vis fn #constructor_name(inner: #ty) -> Self { Self(inner) }
+        },
+    };
+    // Validated brands also get an explicitly unchecked constructor for trusted values, with the
+    // visibility of the constructor, so that it remains available if the constructor is renamed.
+    let new_unchecked =
+        (validate && !options.no_constructor && constructor_name != "new_unchecked").then(|| {
+            let doc_comment = format!(
+                "Construct a new `{struct_name}` value without validating it, for values that are \
+                 known to pass validation."
+            );
+            if const_new {
+                quote! {
+                    #[doc = #doc_comment]
+                    #constructor_vis const fn new_unchecked(inner: #ty) -> Self { Self(inner) }
+                }
+            } else {
+                quote! {
+                    #[doc = #doc_comment]
+                    #constructor_contract
+                    #constructor_vis fn new_unchecked(inner: #ty) -> Self { Self(inner) }
+                }
+            }
+        });
+    let try_new = validate.then(|| {
+        let doc_comment =
+            format!("Construct a new `{struct_name}` value, if it passes validation.");
+        quote! {
+            #[doc = #doc_comment]
+            #try_new_contract
+            pub fn try_new(inner: #ty) -> ::core::result::Result<Self, ::branded::Error> {
+                Self::validate(&inner)?;
+                ::core::result::Result::Ok(Self(inner))
+            }
+        }
+    });
+    // String brands without a validator function check string input in place, and other brands
+    // parse it as the inner type first.
+    let is_valid_str = (is_string_type(&ty) || is_str_ref_type(&ty))
+        && options.validate.is_none()
+        && options.range.is_none();
+    let validate_fn = validate.then(|| {
+        let validate_doc_comment =
+            format!("Check whether a value passes the validation of `{struct_name}`.");
+        let is_valid_doc_comment = format!(
+            "Check whether a string is a valid `{struct_name}` inner value, without constructing it."
+        );
+        let is_valid = if is_valid_str {
+            quote! {
+                #[doc = #is_valid_doc_comment]
+                pub fn is_valid(input: &str) -> bool {
+                    let inner = input;
+                    #(#validation_checks)&&*
+                }
+            }
+        } else {
+            quote! {
+                #[doc = #is_valid_doc_comment]
+                pub fn is_valid(input: &str) -> bool
+                where
+                    for<'__branded> #ty: ::core::str::FromStr,
+                {
+                    input.parse::<#ty>().is_ok_and(|inner| Self::validate(&inner).is_ok())
+                }
+            }
+        };
+        // Validators take a reference to the inner type, such as `&String`, which clippy would
+        // otherwise flag in the signature.
+        quote! {
+            #[doc = #validate_doc_comment]
+            #[allow(clippy::ptr_arg)]
+            pub fn validate(inner: &#ty) -> ::core::result::Result<(), ::branded::Error> {
+                #(
+                    if !#validation_checks {
+                        return ::core::result::Result::Err(::branded::Error::new(<Self as Branded>::BRAND, ::branded::ErrorKind::Validation));
+                    }
+                )*
+                ::core::result::Result::Ok(())
+            }
+
+            #is_valid
+        }
+    });
+    // Mapping a validated brand validates the result, so normalization cannot break its invariants.
+    let map = if validate {
+        quote! {
+            /// Transform the inner value, such as to normalize it, and validate the result.
+            pub fn map(self, f: impl ::core::ops::FnOnce(#ty) -> #ty) -> ::core::result::Result<Self, ::branded::Error> {
+                Self::try_new(f(self.0))
+            }
+
+            /// Transform the inner value with a fallible function, and validate the result.
+            pub fn try_map<E: ::core::convert::From<::branded::Error>>(
+                self,
+                f: impl ::core::ops::FnOnce(#ty) -> ::core::result::Result<#ty, E>,
+            ) -> ::core::result::Result<Self, E> {
+                ::core::result::Result::Ok(Self::try_new(f(self.0)?)?)
+            }
+        }
+    } else {
+        quote! {
+            /// Transform the inner value, such as to normalize it.
+            pub fn map(self, f: impl ::core::ops::FnOnce(#ty) -> #ty) -> Self { Self(f(self.0)) }
+
+            /// Transform the inner value with a fallible function.
+            pub fn try_map<E>(
+                self,
+                f: impl ::core::ops::FnOnce(#ty) -> ::core::result::Result<#ty, E>,
+            ) -> ::core::result::Result<Self, E> {
+                f(self.0).map(Self)
+            }
+        }
+    };
+    // Brands with a const constructor can be constructed from literals parsed at compile time by
+    // the `brand!` macro, if the parsing of their inner type is known.
+    let from_literal = (const_new && !validate)
+        .then(|| expand_literal_parser(&ty))
+        .flatten()
+        .map(|parse| {
+            quote! {
+                #[doc(hidden)]
+                #constructor_vis const fn __from_literal(literal: &'static str) -> Self { Self(#parse) }
+            }
+        });
+    // Structural brands leave `PartialEq` and `Eq` to `#[derive]`, since only derived equality
+    // allows constants to be used as match patterns, and provide a const accessor for const generic
+    // arguments.
+    if options.structural {
+        if !is_integer_type(&ty) {
+            return Err(syn::Error::new_spanned(
+                &ty,
+                "the `structural` option is only supported for integer inner types",
+            ));
+        }
+        skip.partial_eq = true;
+        skip.eq = true;
+    }
+    let get = options.structural.then(|| {
+        quote! {
+            /// Get the inner value in a const context, such as a const generic argument.
+            pub const fn get(self) -> #ty { self.0 }
+        }
+    });
+    // Generic construction is only allowed where the brand has a public unchecked constructor.
+    let public_constructor = !options.no_constructor
+        && options
+            .constructor_vis
+            .as_ref()
+            .is_none_or(|vis| matches!(vis, syn::Visibility::Public(_)));
+    if public_constructor && !validate {
+        tokens.extend(quote! {
+            impl #impl_generics ::branded::FromInner for #struct_name #ty_generics #where_clause {
+                fn from_inner(inner: #ty) -> Self { Self(inner) }
+            }
+        });
+    }
+    let inner_mut = options.inner_mut.then(|| {
+        quote! {
+            /// Get a mutable reference to the inner value.
+            pub fn inner_mut(&mut self) -> &mut #ty { &mut self.0 }
+        }
+    });
     tokens.extend(quote! {
-        impl Branded for #struct_name {
+        impl #impl_generics Branded for #struct_name #ty_generics #where_clause {
             type Inner = #ty;
+            const BRAND: &'static str = #brand;
+            const COLUMN: &'static str = #column;
+            #constraints
             fn inner(&self) -> &#ty { &self.0 }
             fn into_inner(self) -> #ty { self.0 }
         }
-        impl #struct_name {
-            #[doc = #constructor_doc_comment]
-            pub fn new(inner: #ty) -> Self { Self(inner) }
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #constructor
+            #new_unchecked
+            #try_new
+            #validate_fn
+            #map
+            #from_literal
+            #get
+            #inner_mut
+        }
+    });
+
+    // The impls that do not depend on options are generated by a single invocation of the
+    // `__impl_std!` macro of the runtime crate, which keeps the expansion of each brand small.
+    let mut std_impls = skip.std_impls();
+    std_impls.push(quote!(WriteTo));
+    if options.compact_str {
+        tokens.extend(expand_compact_str_impl(struct_name, generics));
+    }
+    match &options.render {
+        _ if skip.debug => {}
+        Some(render) => tokens.extend(expand_debug_impl(struct_name, generics, render)),
+        None => std_impls.push(quote!(Debug)),
+    }
+    let generate_default = options.generator.as_ref().is_some_and(|generator| generator.default);
+    if generate_default && skip.default {
+        return Err(spans.error(
+            &["generator(default)", "skip(Default)"],
+            "`generator(default)` and `skip(Default)` cannot be combined",
+        ));
+    }
+    // The default inner value may not pass validation, so validated brands only implement
+    // Default with a generator.
+    let has_default = generate_default || !(skip.default || validate);
+    if generate_default {
+        tokens.extend(expand_default_impl(struct_name, generics));
+    } else if has_default {
+        std_impls.push(quote!(Default));
+    }
+    // Brands of `String` can also be used as `&str`.
+    let str_ty: syn::Type = syn::parse_quote!(str);
+    let targets = if is_string_type(&ty) {
+        vec![&ty, &str_ty]
+    } else {
+        vec![&ty]
+    };
+    for target in targets {
+        if !skip.as_ref {
+            std_impls.push(quote!(AsRef(#target)));
+        }
+        if !skip.borrow {
+            std_impls.push(quote!(Borrow(#target)));
+        }
+    }
+
+    let zero_pad = options.display.as_ref().and_then(|display| display.zero_pad);
+    if zero_pad.is_some() && skip.display {
+        return Err(spans.error(
+            &["display(zero_pad)", "skip(Display)"],
+            "`display(zero_pad = ...)` and `skip(Display)` cannot be combined",
+        ));
+    }
+    if zero_pad.is_some() && (options.path.is_some() || options.checksum.is_some()) {
+        return Err(spans.error(
+            &["display(zero_pad)"],
+            "`display(zero_pad = ...)` cannot be combined with the `path` and `checksum` options",
+        ));
+    }
+    if zero_pad.is_some() && uuid.as_ref().is_some_and(|uuid| uuid.format.is_some()) {
+        return Err(spans.error(
+            &["display(zero_pad)", "uuid(format)"],
+            "`display(zero_pad = ...)` and `uuid(format = \"...\")` cannot be combined",
+        ));
+    }
+    if let Some(render) = &options.render {
+        let conflict =
+            if options.path.is_some() || options.checksum.is_some() || options.external.is_some() {
+                Some("the `path`, `checksum`, and `external` options")
+            } else if zero_pad.is_some() {
+                Some("`display(zero_pad = ...)`")
+            } else if uuid.as_ref().is_some_and(|uuid| uuid.format.is_some()) {
+                Some("`uuid(format = \"...\")`")
+            } else {
+                None
+            };
+        if let Some(conflict) = conflict {
+            return Err(syn::Error::new_spanned(
+                render,
+                format!("the `render` option cannot be combined with {conflict}"),
+            ));
+        }
+    }
+    if (skip.display || skip.from_str)
+        && (options.path.is_some() || options.checksum.is_some() || options.external.is_some())
+    {
+        return Err(spans.error(
+            &["skip(Display)", "skip(FromStr)", "skip"],
+            "`skip(Display)` and `skip(FromStr)` cannot be combined with the `path`, `checksum`, and \
+             `external` options",
+        ));
+    }
+    if validate && options.from {
+        return Err(spans.error(
+            &["from"],
+            "validated brands cannot use the `from` option, since `From` cannot fail",
+        ));
+    }
+    if validate && options.inner_mut {
+        return Err(spans.error(
+            &["inner_mut"],
+            "validated brands cannot use the `inner_mut` option, since mutating the inner value \
+             skips validation",
+        ));
+    }
+    // These options construct brands from inner values that cannot be rejected.
+    let unvalidated_option = [
+        ("alias_of", options.alias_of.is_some()),
+        ("newtype_uuid", options.newtype_uuid.is_some()),
+        ("bitset", options.bitset),
+        ("cast", options.cast),
+        ("bytemuck", options.bytemuck),
+    ]
+    .into_iter()
+    .find_map(|(option, enabled)| enabled.then_some(option));
+    if let Some(option) = unvalidated_option.filter(|_| validate) {
+        return Err(spans.error(
+            &[option],
+            format!(
+                "validated brands cannot use the `{option}` option, since it constructs brands \
+                 without validation"
+            ),
+        ));
+    }
+    // Path-structured brands format their components with the path template instead of
+    // delegating to the inner type.
+    if let Some(external) = &options.external {
+        let conflict = if options.path.is_some() || options.checksum.is_some() {
+            Some("the `path` and `checksum` options")
+        } else if zero_pad.is_some() {
+            Some("`display(zero_pad = ...)`")
+        } else if uuid.as_ref().is_some_and(|uuid| uuid.format.is_some()) {
+            Some("`uuid(format = \"...\")`")
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            return Err(spans.error(
+                &["external"],
+                format!("the `external` option cannot be combined with {conflict}"),
+            ));
+        }
+        tokens.extend(expand_external_impl(struct_name, generics, external, validate)?);
+    } else {
+        match (&options.path, options.checksum) {
+            (Some(template), None) => {
+                tokens.extend(expand_path_impl(struct_name, generics, template, &ty, validate)?)
+            }
+            (None, Some(algorithm)) => {
+                tokens.extend(expand_checksum_impl(struct_name, generics, algorithm, validate))
+            }
+            (None, None) => {
+                let uuid_format = uuid.as_ref().and_then(|uuid| uuid.format);
+                let render = options.render.as_ref();
+                if !skip.display {
+                    if uuid_format.is_none() && zero_pad.is_none() && render.is_none() {
+                        std_impls.push(quote!(Display));
+                    } else {
+                        tokens.extend(expand_display_impl(
+                            struct_name,
+                            generics,
+                            uuid_format,
+                            zero_pad,
+                            render,
+                        ));
+                    }
+                }
+                match zero_pad {
+                    _ if skip.from_str => {}
+                    Some(width) => {
+                        tokens.extend(expand_from_str_impl(struct_name, generics, width, validate))
+                    }
+                    None if validate => std_impls.push(quote!(FromStr(try_new))),
+                    None => std_impls.push(quote!(FromStr)),
+                }
+            }
+            (Some(template), Some(_)) => {
+                return Err(syn::Error::new(
+                    template.span(),
+                    "the `path` and `checksum` options cannot be combined",
+                ));
+            }
+        }
+    }
+
+    // cynic scalars and D-Bus values are (de)serialized with serde, so the cynic and zbus options
+    // imply the serde impls.
+    let (default_generate, as_string) = match &serde {
+        Some(serde) => (serde.default_generate, serde.as_string),
+        None => (false, false),
+    };
+    let serde_prefix = serde.as_ref().and_then(|serde| serde.prefix.as_ref());
+    if let Some(prefix) = serde_prefix {
+        if prefix.value().is_empty() {
+            return Err(syn::Error::new(prefix.span(), "serde prefix must not be empty"));
+        }
+        if default_generate || as_string {
+            return Err(spans.error(
+                &["serde(prefix)"],
+                "`serde(prefix = \"...\")` cannot be combined with `serde(default_generate)` and \
+                 `serde(as_string)`",
+            ));
+        }
+    }
+    let legacy_prefix = serde.as_ref().and_then(|serde| serde.accept_legacy.as_ref());
+    let on_legacy = serde.as_ref().and_then(|serde| serde.on_legacy.as_ref());
+    match (serde_prefix, legacy_prefix) {
+        (None, Some(_)) => {
+            return Err(spans.error(
+                &["serde(accept_legacy)"],
+                "`serde(accept_legacy = \"...\")` requires `serde(prefix = \"...\")`",
+            ));
+        }
+        (Some(prefix), Some(legacy)) if prefix.value() == legacy.value() => {
+            return Err(syn::Error::new(
+                legacy.span(),
+                "the legacy prefix must differ from the serde prefix",
+            ));
+        }
+        _ => {}
+    }
+    if on_legacy.is_some() && legacy_prefix.is_none() {
+        return Err(spans.error(
+            &["serde(on_legacy)"],
+            "`serde(on_legacy = \"...\")` requires `serde(accept_legacy = \"...\")`",
+        ));
+    }
+    let wrapped = serde.as_ref().and_then(|serde| serde.style) == Some(SerdeStyle::Wrapped);
+    if wrapped && (default_generate || as_string || serde_prefix.is_some()) {
+        return Err(spans.error(
+            &["serde(style)"],
+            "`serde(style = \"wrapped\")` cannot be combined with `serde(default_generate)`, \
+             `serde(as_string)`, and `serde(prefix = \"...\")`",
+        ));
+    }
+    if default_generate && uuid.is_none() && options.generator.is_none() {
+        return Err(spans.error(
+            &["serde(default_generate)"],
+            "`serde(default_generate)` requires a generator, such as the `uuid` or `generator` option",
+        ));
+    }
+    if default_generate && as_string {
+        return Err(spans.error(
+            &["serde(as_string)", "serde(default_generate)"],
+            "`serde(default_generate)` and `serde(as_string)` cannot be combined",
+        ));
+    }
+    if wrapped {
+        tokens.extend(expand_serde_wrapped_impl(struct_name, generics, validate));
+    } else if let Some(prefix) = serde_prefix {
+        tokens.extend(expand_serde_prefixed_impl(
+            struct_name,
+            generics,
+            prefix,
+            legacy_prefix.map(|legacy| (legacy, on_legacy)),
+            zero_pad.is_some(),
+            validate,
+        ));
+    } else if serde.is_some() || options.cynic.is_some() || options.zbus {
+        // A custom generator takes precedence over the UUID constructors.
+        let generator = match (&options.generator, &uuid) {
+            _ if !default_generate => None,
+            (Some(_), _) => Some(quote!(Self::generate())),
+            (None, uuid) => uuid.as_ref().map(|uuid| expand_uuid_generator(uuid.v7)),
+        };
+        tokens.extend(expand_serde_impl(
+            struct_name,
+            generics,
+            generator,
+            as_string,
+            zero_pad.is_some(),
+            validate,
+            cow_str_lifetime(&ty),
+        ));
+    }
+
+    if let Some(sqlx) = &sqlx {
+        if sqlx.type_name.is_some() && !sqlx.postgres {
+            return Err(spans.error(
+                &["sqlx(type_name)"],
+                "`sqlx(type_name = \"...\")` requires `sqlx(postgres)`",
+            ));
+        }
+        let databases = sqlx.only.as_ref().map(parse_sqlx_databases).transpose()?;
+        if sqlx.postgres
+            && databases
+                .as_ref()
+                .is_some_and(|databases| !databases.contains(&syn::parse_quote!(::sqlx::Postgres)))
+        {
+            return Err(spans.error(
+                &["sqlx(only)", "sqlx(postgres)"],
+                "`sqlx(postgres)` requires `postgres` in `sqlx(only = \"...\")`",
+            ));
+        }
+        match sqlx.int128 {
+            Some(_) if sqlx.postgres => {
+                return Err(spans.error(
+                    &["sqlx(int128)"],
+                    "`sqlx(int128 = \"...\")` and `sqlx(postgres)` cannot be combined",
+                ));
+            }
+            Some(strategy) => tokens.extend(expand_sqlx_int128_impl(
+                struct_name,
+                generics,
+                strategy,
+                databases.as_deref(),
+                validate,
+            )),
+            None => tokens.extend(expand_sqlx_impl(
+                struct_name,
+                generics,
+                &ty,
+                sqlx.postgres,
+                sqlx.type_name.as_deref(),
+                databases.as_deref(),
+                validate,
+            )),
+        }
+        if let Some(table) = &sqlx.table {
+            let column = sqlx.column.as_deref().unwrap_or("id");
+            tokens.extend(expand_sqlx_table_impl(struct_name, generics, table, column));
+        }
+        if let Some(from_row) = &sqlx.from_row {
+            let column = match from_row {
+                Override::Inherit => None,
+                Override::Explicit(column) => Some(column.as_str()),
+            };
+            tokens.extend(expand_sqlx_from_row_impl(struct_name, generics, column));
+        }
+    }
+
+    let on_generate = options.on_generate.as_ref();
+    if on_generate.is_some() && uuid.is_none() && options.generator.is_none() {
+        return Err(spans.error(
+            &["on_generate"],
+            "the `on_generate` option requires a generator, such as the `uuid` or `generator` option",
+        ));
+    }
+    if let Some(uuid) = &uuid {
+        let namespace = uuid.v5.as_ref().map(parse_uuid_namespace).transpose()?;
+        tokens.extend(expand_uuid_impl(struct_name, generics, uuid.v7, namespace, on_generate));
+    }
+
+    if options.defmt {
+        tokens.extend(expand_defmt_impl(struct_name, generics));
+    }
+
+    if options.polars {
+        tokens.extend(expand_polars_impl(struct_name, generics, validate));
+    }
+
+    if options.influxdb {
+        tokens.extend(expand_influxdb_impl(struct_name, generics));
+    }
+
+    if options.tonic {
+        tokens.extend(expand_tonic_impl(struct_name, generics, validate));
+    }
+
+    if (options.example.is_some() || options.schema_format.is_some())
+        && !(options.okapi || options.aide)
+    {
+        return Err(spans.error(
+            &["example", "schema_format"],
+            "the `example` and `schema_format` options require a schema option, `okapi` or `aide`",
+        ));
+    }
+
+    if options.okapi {
+        let schemars = syn::parse_quote!(::okapi::schemars);
+        tokens.extend(expand_json_schema_impl(
+            struct_name,
+            generics,
+            schemars,
+            options.example.as_deref(),
+            options.schema_format.as_deref(),
+        ));
+    }
+
+    if options.aide {
+        let schemars = syn::parse_quote!(::schemars);
+        tokens.extend(expand_json_schema_impl(
+            struct_name,
+            generics,
+            schemars,
+            options.example.as_deref(),
+            options.schema_format.as_deref(),
+        ));
+    }
+
+    if let Some(scalar) = &options.cynic {
+        tokens.extend(expand_cynic_impl(struct_name, generics, scalar)?);
+    }
+
+    if options.prometheus {
+        tokens.extend(expand_prometheus_impl(struct_name, generics));
+    }
+
+    if options.mysql {
+        tokens.extend(expand_mysql_impl(struct_name, generics, validate));
+    }
+
+    if options.gel {
+        tokens.extend(expand_gel_impl(struct_name, generics, validate));
+    }
+
+    if options.zbus {
+        tokens.extend(expand_zbus_impl(struct_name, generics));
+    }
+
+    // Derive macros cannot change the representation of the struct, so the options that rely on
+    // the layout of the brand check that it is declared `#[repr(transparent)]`.
+    if (options.cast || options.bytemuck) && !is_repr_transparent(&options.attrs) {
+        let option = if options.cast {
+            "cast"
+        } else {
+            "bytemuck"
+        };
+        return Err(spans.error(
+            &[option],
+            format!("the `{option}` option requires the brand to be `#[repr(transparent)]`"),
+        ));
+    }
+
+    if options.cast {
+        tokens.extend(expand_cast_impl(struct_name, generics));
+    }
+
+    if options.bytemuck {
+        tokens.extend(expand_bytemuck_impl(struct_name, generics, &ty));
+    }
+
+    if options.rustler {
+        tokens.extend(expand_rustler_impl(struct_name, generics, &ty, validate));
+    }
+
+    if let Some(kind) = &options.newtype_uuid {
+        tokens.extend(expand_newtype_uuid_impl(struct_name, generics, kind));
+    }
+
+    if options.bitset {
+        tokens.extend(expand_bitset_impl(struct_name, generics));
+    }
+
+    if let Some(sentry) = &sentry {
+        tokens.extend(expand_sentry_impl(struct_name, generics, sentry.pii));
+    }
+
+    if let Some(alias_of) = &options.alias_of {
+        tokens.extend(expand_alias_of_impl(struct_name, generics, alias_of));
+    }
+    for source in options.convert_from.iter() {
+        tokens.extend(expand_convert_from_impl(struct_name, generics, source, validate));
+    }
+
+    if options.from && options.try_from {
+        if is_generic_type(&ty, generics) {
+            return Err(spans.error(
+                &["try_from"],
+                "the `from` and `try_from` options cannot be combined on a brand that is generic \
+                 over its inner type",
+            ));
+        }
+        // `From` of a string implies the `TryFrom` of the same string through the blanket impl of
+        // core, which would overlap the generated one.
+        if is_string_type(&ty) || is_str_ref_type(&ty) {
+            return Err(spans.error(
+                &["try_from"],
+                "the `from` and `try_from` options cannot be combined on a brand of `String` or \
+                 `&str`, since `From` already implies `TryFrom`",
+            ));
+        }
+    }
+    if options.from {
+        tokens.extend(expand_from_impl(struct_name, generics, &ty));
+    }
+
+    if options.context {
+        tokens.extend(expand_context_impl(struct_name, generics));
+    }
+
+    if let Some(generator) = &options.generator {
+        tokens.extend(expand_generator_impl(
+            struct_name,
+            generics,
+            &ty,
+            &generator.function,
+            on_generate,
+        ));
+    }
+
+    if let Some(shard) = &options.shard {
+        if shard.modulo == 0 {
+            return Err(spans.error(&["shard(modulo)"], "`shard(modulo = ...)` must be at least 1"));
+        }
+        tokens.extend(expand_shard_impl(struct_name, generics, shard.modulo));
+    }
+
+    if options.try_from {
+        tokens.extend(expand_try_from_impl(struct_name, generics, true, true));
+    }
+
+    // The builder conversions are spelled out, instead of normalized, since the bare option
+    // generates all of them.
+    let builder = options.builder.as_ref().map(|builder| match builder {
+        Override::Inherit => (true, true),
+        Override::Explicit(builder) => (builder.inner, builder.parse),
+    });
+    if let Some((inner, parse)) = builder {
+        if inner && options.from {
+            return Err(spans.error(
+                &["builder(inner)", "builder"],
+                "`builder(inner)` and `from` cannot be combined, since both convert from the inner type",
+            ));
+        }
+        if parse && options.try_from {
+            return Err(spans.error(
+                &["builder(parse)", "builder"],
+                "`builder(parse)` and `try_from` cannot be combined, since both parse strings",
+            ));
+        }
+        if inner && validate && is_generic_type(&ty, generics) {
+            return Err(spans.error(
+                &["builder(inner)", "builder"],
+                "`builder(inner)` is not supported on validated brands that are generic over their \
+                 inner type",
+            ));
+        }
+        if inner {
+            tokens.extend(expand_builder_inner_impl(struct_name, generics, &ty, validate));
+        }
+        if parse {
+            // Brands of `String` and `&str` already convert from them with `builder(inner)`.
+            let include_str = !(inner && is_str_ref_type(&ty));
+            let include_string = !(inner && is_string_type(&ty));
+            tokens.extend(expand_try_from_impl(struct_name, generics, include_str, include_string));
+        }
+    }
+
+    if options.partial_eq_inner {
+        tokens.extend(expand_partial_eq_inner_impl(struct_name, generics, &ty));
+    }
+
+    if !options.secret {
+        std_impls.push(quote!(PathSegment));
+    }
+
+    tokens.extend(expand_implements_impl(struct_name, generics, &options.implements));
+
+    // The traits implemented by inner types the derive knows, which includes `uuid::Uuid`, are
+    // generated unconditionally, and impls of the traits they do not implement are not generated at
+    // all. Other inner types are bounded on implementing each trait, which for concrete types needs
+    // a higher-ranked bound, so that the impls still compile if the bound does not hold.
+    let missing_traits = missing_inner_traits(&ty, uuid.is_some());
+    let std_impls = std_impls.into_iter().filter(|tokens| {
+        let name = tokens.clone().into_iter().next().map(|token| token.to_string());
+        missing_traits.is_none_or(|missing| !name.is_some_and(|name| missing.contains(&&*name)))
+    });
+    let inner_bound = if missing_traits.is_some() || is_generic_type(&ty, generics) {
+        quote!(#ty)
+    } else {
+        quote!(for<'__branded> <Self as ::branded::Branded>::Inner)
+    };
+    let predicates = where_predicates(generics);
+    tokens.extend(quote! {
+        ::branded::__impl_std! {
+            {#struct_name [#impl_generics] [#ty_generics] [#(#predicates,)*] [#inner_bound]}
+            #(#std_impls)*
+        }
+    });
+
+    // The selftest module is emitted outside of the anonymous const below, since tests nested in
+    // it would not be collected by the test harness.
+    let selftest = options.selftest.map(Override::unwrap_or_default);
+    let selftest = if let Some(selftest) = &selftest {
+        // Checks over fewer than two distinct values cannot fail, so the samples must come from
+        // a function or from the UUID constructors.
+        if selftest.samples.is_none() && uuid.is_none() {
+            return Err(spans.error(
+                &["selftest"],
+                "`selftest` needs samples, pass `selftest(samples = \"path::to::fn\")` with a \
+                 function returning sample values of the brand",
+            ));
+        }
+        expand_selftest(
+            struct_name,
+            generics,
+            serde.is_some(),
+            uuid.is_some(),
+            has_default,
+            selftest.samples.as_ref(),
+        )?
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // The impls are wrapped in an anonymous const so that brands can be marked as deprecated
+    // without every generated impl warning about the use of a deprecated type. The const also
+    // scopes an import of the `Branded` trait, which the impls refer to by name, so that the derive
+    // works without the trait in scope and when the name is shadowed at the definition site.
+    Ok(quote! {
+        #[allow(deprecated)]
+        const _: () = {
+            use ::branded::Branded;
+            #tokens
+        };
+        #selftest
+    })
+}
+
+/// Options of the `Branded` derive on structs with more than one field, which are composite keys
+/// made up of other brands.
+#[derive(FromDeriveInput)]
+#[darling(attributes(branded), supports(struct_tuple, struct_named))]
+pub(crate) struct CompositeKeyOptions {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: darling::ast::Data<(), CompositeKeyFieldOptions>,
+
+    #[darling(default)]
+    serde: bool,
+    #[darling(default)]
+    const_new: bool,
+    constructor_vis: Option<syn::Visibility>,
+    #[darling(default)]
+    skip: SkipOptions,
+}
+
+#[derive(FromField)]
+pub(crate) struct CompositeKeyFieldOptions {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+}
+
+/// Derive a composite key, with a constructor, an accessor per component, and impls that compare,
+/// hash, and serialize the components as a tuple.
+pub(crate) fn expand_composite_key_derive(
+    options: CompositeKeyOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &options.ident;
+    let generics = &options.generics;
+    let skip = options.skip.with_dependents();
+    let fields = options.data.take_struct().expect("composite keys are structs");
+    let named = fields.style == darling::ast::Style::Struct;
+    let fields = fields.fields;
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    // Named fields keep their names, and tuple fields are named after their brand, such as
+    // `tenant_id` for a `TenantId` component.
+    let names = fields
+        .iter()
+        .map(|field| match (&field.ident, &field.ty) {
+            (Some(ident), _) => Ok(ident.clone()),
+            (None, syn::Type::Path(path)) if path.qself.is_none() => {
+                let segment = path.path.segments.last().expect("paths are not empty");
+                Ok(syn::Ident::new(
+                    &to_snake_case(&segment.ident.to_string()),
+                    segment.ident.span(),
+                ))
+            }
+            (None, ty) => Err(syn::Error::new_spanned(
+                ty,
+                "components of tuple composite keys must be named types, use named fields instead",
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            return Err(syn::Error::new_spanned(
+                types[i],
+                format!("duplicate component `{name}`, use named fields instead"),
+            ));
+        }
+    }
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(i)),
+        })
+        .collect::<Vec<_>>();
+    let construct = if named {
+        quote!(Self { #(#members: #names),* })
+    } else {
+        quote!(Self(#(#names),*))
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let constructor_vis = options.constructor_vis.clone().unwrap_or_else(|| syn::parse_quote!(pub));
+    let constness = options.const_new.then(|| quote!(const));
+    let constructor_doc_comment =
+        format!("Construct a new `{struct_name}` key from its components.");
+    let accessor_doc_comments =
+        names.iter().map(|name| format!("Get the `{name}` component of the key."));
+    let mut tokens = quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #[doc = #constructor_doc_comment]
+            #constructor_vis #constness fn new(#(#names: #types),*) -> Self { #construct }
+
+            #(
+                #[doc = #accessor_doc_comments]
+                pub fn #names(&self) -> &#types { &self.#members }
+            )*
+
+            /// Convert the key into its components.
+            pub fn into_parts(self) -> (#(#types,)*) { (#(self.#members,)*) }
+        }
+    };
+
+    let mut impl_trait = |skipped: bool, trait_path: proc_macro2::TokenStream, body| {
+        if !skipped {
+            tokens.extend(quote! {
+                impl #impl_generics #trait_path for #struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    #(for<'__branded> #types: #trait_path,)*
+                {
+                    #body
+                }
+            });
+        }
+    };
+    impl_trait(
+        skip.clone,
+        quote!(::core::clone::Clone),
+        quote! {
+            fn clone(&self) -> Self {
+                #(let #names = ::core::clone::Clone::clone(&self.#members);)*
+                #construct
+            }
+        },
+    );
+    impl_trait(skip.copy, quote!(::core::marker::Copy), quote!());
+    impl_trait(
+        skip.partial_eq,
+        quote!(::core::cmp::PartialEq),
+        quote! {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#members == other.#members)*
+            }
+        },
+    );
+    impl_trait(skip.eq, quote!(::core::cmp::Eq), quote!());
+    impl_trait(
+        skip.partial_ord,
+        quote!(::core::cmp::PartialOrd),
+        quote! {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::cmp::PartialOrd::partial_cmp(&(#(&self.#members,)*), &(#(&other.#members,)*))
+            }
+        },
+    );
+    impl_trait(
+        skip.ord,
+        quote!(::core::cmp::Ord),
+        quote! {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                ::core::cmp::Ord::cmp(&(#(&self.#members,)*), &(#(&other.#members,)*))
+            }
+        },
+    );
+    impl_trait(
+        skip.hash,
+        quote!(::core::hash::Hash),
+        quote! {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                #(::core::hash::Hash::hash(&self.#members, state);)*
+            }
+        },
+    );
+    impl_trait(
+        skip.default,
+        quote!(::core::default::Default),
+        quote! {
+            fn default() -> Self {
+                #(let #names = ::core::default::Default::default();)*
+                #construct
+            }
+        },
+    );
+    let debug = if named {
+        quote! {
+            f.debug_struct(::core::stringify!(#struct_name))
+                #(.field(::core::stringify!(#members), &self.#members))*
+                .finish()
+        }
+    } else {
+        quote!(f.debug_tuple(::core::stringify!(#struct_name)) #(.field(&self.#members))* .finish())
+    };
+    impl_trait(
+        skip.debug,
+        quote!(::core::fmt::Debug),
+        quote! {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #debug
+            }
+        },
+    );
+
+    if options.serde {
+        let mut de_generics = generics.clone();
+        de_generics.params.insert(0, syn::parse_quote!('__de));
+        let (de_impl_generics, _, _) = de_generics.split_for_impl();
+        tokens.extend(quote! {
+            impl #impl_generics ::serde::Serialize for #struct_name #ty_generics
+            where
+                #(#predicates,)*
+                #(for<'__branded> #types: ::serde::Serialize,)*
+            {
+                fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+                    ::serde::Serialize::serialize(&(#(&self.#members,)*), serializer)
+                }
+            }
+
+            impl #de_impl_generics ::serde::Deserialize<'__de> for #struct_name #ty_generics
+            where
+                #(#predicates,)*
+                #(#types: ::serde::Deserialize<'__de>,)*
+            {
+                fn deserialize<D: ::serde::Deserializer<'__de>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
+                    let (#(#names,)*) = <(#(#types,)*) as ::serde::Deserialize<'__de>>::deserialize(deserializer)?;
+                    ::core::result::Result::Ok(#construct)
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        const _: () = {
+            #tokens
+        };
+    })
+}
+
+/// Derive a Display implementation for a brand with display options, if the inner type conforms to
+/// Display.
+///
+/// The Display implementation prints the inner value, and prefixes it with the branded type name
+/// when the alternate flag is set, so `{:#}` renders as `UserId:1234`.
+///
+/// Brands of UUIDs with a `format` print the inner UUID in that format, and brands with a
+/// `zero_pad` width print the inner value padded with leading zeros to that width. Brands with a
+/// `render` function print the inner value with it instead, even if it does not conform to Display.
+pub(crate) fn expand_display_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    uuid_format: Option<UuidFormat>,
+    zero_pad: Option<usize>,
+    render: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    let display_trait: syn::Path = syn::parse_quote!(::core::fmt::Display);
+    let inner = match uuid_format {
+        None => quote!(self.inner()),
+        Some(UuidFormat::Hyphenated) => quote!(&::uuid::Uuid::hyphenated(*self.inner())),
+        Some(UuidFormat::Simple) => quote!(&::uuid::Uuid::simple(*self.inner())),
+        Some(UuidFormat::Urn) => quote!(&::uuid::Uuid::urn(*self.inner())),
+        Some(UuidFormat::Braced) => quote!(&::uuid::Uuid::braced(*self.inner())),
+    };
+    let write_inner = match (render, zero_pad) {
+        (Some(render), _) => quote!(#render(self.inner(), f)),
+        (None, Some(width)) => quote!(::core::write!(f, "{:01$}", #inner, #width)),
+        (None, None) => quote!(::core::fmt::Display::fmt(#inner, f)),
+    };
+    let inner_bound =
+        render.is_none().then(|| quote!(for<'__branded> <Self as Branded>::Inner: #display_trait,));
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #display_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            #inner_bound
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if f.alternate() {
+                    f.write_str(<Self as Branded>::BRAND)?;
+                    f.write_str(":")?;
+                }
+                #write_inner
+            }
+        }
+    }
+}
+
+/// Derive the `to_compact_string` method of the `compact_str` option, which formats the brand with
+/// its Display implementation into an inline `CompactString`.
+pub(crate) fn expand_compact_str_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let display_trait: syn::Path = syn::parse_quote!(::core::fmt::Display);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> Self: #display_trait,
+        {
+            /// Format the value into a `CompactString`, which stores short values inline.
+            pub fn to_compact_string(&self) -> ::branded::compact_str::CompactString {
+                let mut buf = ::branded::compact_str::CompactString::default();
+                self.write_to(&mut buf)
+                    .expect("a Display implementation returned an error unexpectedly");
+                buf
+            }
+        }
+    }
+}
+
+/// Derive a FromStr implementation for a brand with a `zero_pad` width, if the inner type conforms
+/// to FromStr.
+///
+/// Errors of the inner type are reported as a `branded::Error` carrying the brand name and input.
+/// Inputs shorter than the width are rejected.
+pub(crate) fn expand_from_str_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    zero_pad: usize,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let from_str_trait: syn::Path = syn::parse_quote!(::core::str::FromStr);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let check_width = quote! {
+        if input.len() < #zero_pad {
+            return ::core::result::Result::Err(::branded::Error::new(<Self as Branded>::BRAND, ::branded::ErrorKind::Length)
+                .with_input(input));
+        }
+    };
+    let construct = expand_construct(validate, quote!(|error| error.with_input(input)));
+    quote! {
+        impl #impl_generics #from_str_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #from_str_trait,
+        {
+            type Err = ::branded::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                #check_width
+                input
+                    .parse::<<Self as Branded>::Inner>()
+                    .map_err(|_| {
+                        ::branded::Error::new(<Self as Branded>::BRAND, ::branded::ErrorKind::Parse)
+                            .with_input(input)
+                    })
+                    #construct
+            }
+        }
+    }
+}
+
+/// Derive a Debug implementation for a brand with a `render` function, which prints the inner value
+/// with it instead, even if it does not conform to Debug.
+pub(crate) fn expand_debug_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    render: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let debug_trait: syn::Path = syn::parse_quote!(::core::fmt::Debug);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #debug_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(::core::stringify!(#brand_struct_name))
+                    .field(&::branded::__private::Rendered(self.inner(), #render))
+                    .finish()
+            }
+        }
+    }
+}
+
+/// Derive a Default implementation for a brand with `generator(default)`, which is a newly
+/// generated value.
+pub(crate) fn expand_default_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let path: syn::Path = syn::parse_quote!(::core::default::Default);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #path for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            fn default() -> Self {
+                Self::generate()
+            }
+        }
+    }
+}
+
+/// Derive a Serde implementation for the branded type if asked for.
+///
+/// With a generator, a missing or null value in a human-readable format deserializes to a newly
+/// generated value instead. With `as_string`, the value is (de)serialized as a string with
+/// the Display and FromStr implementations of the inner type, or of the branded type itself if it
+/// formats the inner value with `zero_pad`.
+///
+/// Brands of `Cow<'a, str>` borrow the string from the input where possible, since the
+/// implementation of `Cow` always allocates. `borrow` is the lifetime of the `Cow`.
+pub(crate) fn expand_serde_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    generator: Option<proc_macro2::TokenStream>,
+    as_string: bool,
+    zero_pad: bool,
+    validate: bool,
+    borrow: Option<&syn::Lifetime>,
+) -> proc_macro2::TokenStream {
+    if as_string {
+        return expand_serde_as_string_impl(brand_struct_name, generics, zero_pad, validate);
+    }
+    let serialize_trait: syn::Path = syn::parse_quote!(::serde::Serialize);
+    let deserialize_trait: syn::Path = syn::parse_quote!(::serde::Deserialize);
+    let mut de_generics = generics.clone();
+    match borrow {
+        Some(lifetime) => de_generics.params.insert(0, syn::parse_quote!('__de: #lifetime)),
+        None => de_generics.params.insert(0, syn::parse_quote!('__de)),
+    }
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let construct = expand_construct(validate, quote!(::serde::de::Error::custom));
+    let (deserialize, inner_bound) = match (generator, borrow) {
+        (Some(generator), _) => (
+            quote! {
+                ::branded::serde::deserialize_or_generate(deserializer, || #generator.into_inner())
+                    #construct
+            },
+            quote!(for<'__branded> <Self as Branded>::Inner: #deserialize_trait<'__de>,),
+        ),
+        (None, Some(_)) => (
+            quote! {
+                ::branded::serde::deserialize_cow_str(deserializer)
+                    #construct
+            },
+            quote!(),
+        ),
+        (None, None) => (
+            quote! {
+                <Self as Branded>::Inner::deserialize(deserializer)
+                    #construct
+            },
+            quote!(for<'__branded> <Self as Branded>::Inner: #deserialize_trait<'__de>,),
+        ),
+    };
+    quote! {
+        impl #impl_generics #serialize_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #serialize_trait,
+        {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                self.inner().serialize(serializer)
+            }
+        }
+
+        impl #de_impl_generics #deserialize_trait<'__de> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            #inner_bound
+        {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'__de>,
+            {
+                #deserialize
+            }
+        }
+    }
+}
+
+/// Derive a Serde implementation (de)serializing the branded type as a string.
+pub(crate) fn expand_serde_as_string_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    zero_pad: bool,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let serialize_trait: syn::Path = syn::parse_quote!(::serde::Serialize);
+    let deserialize_trait: syn::Path = syn::parse_quote!(::serde::Deserialize);
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('__de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    // Zero-padded brands are written and parsed with their own Display and FromStr implementations,
+    // so that the string form has the same fixed width. Their FromStr implementation already
+    // validates the value.
+    let (value, string_ty, from_string) = if zero_pad {
+        (quote!(self), quote!(Self), quote!())
+    } else {
+        let construct = expand_construct(validate, quote!(::serde::de::Error::custom));
+        (quote!(self.inner()), quote!(<Self as Branded>::Inner), construct)
+    };
+    quote! {
+        impl #impl_generics #serialize_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #string_ty: ::core::fmt::Display,
+        {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                ::branded::serde::serialize_str(#value, serializer)
+            }
+        }
+
+        impl #de_impl_generics #deserialize_trait<'__de> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #string_ty: ::core::str::FromStr,
+            for<'__branded> <#string_ty as ::core::str::FromStr>::Err: ::core::fmt::Display,
+        {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'__de>,
+            {
+                ::branded::serde::deserialize_from_str::<D, #string_ty>(deserializer) #from_string
+            }
+        }
+    }
+}
+
+/// Derive a Serde implementation (de)serializing the branded type as a string after a prefix, such
+/// as `cus_42`.
+///
+/// Strings without the prefix are rejected with a `WrongPrefix` error, and the rest of the string
+/// is parsed and validated like the string form of `serde(as_string)`. With a `legacy` prefix,
+/// strings starting with it are accepted too, and passed to its hook once parsed.
+pub(crate) fn expand_serde_prefixed_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    prefix: &syn::LitStr,
+    legacy: Option<(&syn::LitStr, Option<&syn::Path>)>,
+    zero_pad: bool,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let serialize_trait: syn::Path = syn::parse_quote!(::serde::Serialize);
+    let deserialize_trait: syn::Path = syn::parse_quote!(::serde::Deserialize);
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('__de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    // Zero-padded brands are written and parsed with their own Display and FromStr
+    // implementations, as with `serde(as_string)`.
+    let (value, string_ty, parse) = if zero_pad {
+        (quote!(self), quote!(Self), quote!(input.parse::<Self>()))
+    } else {
+        let construct = expand_construct(validate, quote!(|error| error.with_input(input)));
+        let parse = quote! {
+            input
+                .parse::<<Self as Branded>::Inner>()
+                .map_err(|_| {
+                    ::branded::Error::new(<Self as Branded>::BRAND, ::branded::ErrorKind::Parse)
+                        .with_input(input)
+                })
+                #construct
+        };
+        (quote!(self.inner()), quote!(<Self as Branded>::Inner), parse)
+    };
+    let deserialize = match legacy {
+        Some((legacy, on_legacy)) => {
+            let on_legacy = match on_legacy {
+                Some(on_legacy) => quote!(|value: &Self| #on_legacy(value)),
+                None => quote!(|_: &Self| {}),
+            };
+            quote! {
+                ::branded::serde::deserialize_prefixed_or_legacy(
+                    deserializer,
+                    <Self as Branded>::BRAND,
+                    #prefix,
+                    #legacy,
+                    |input| #parse,
+                    #on_legacy,
+                )
+            }
+        }
+        None => quote! {
+            ::branded::serde::deserialize_prefixed(
+                deserializer,
+                <Self as Branded>::BRAND,
+                #prefix,
+                |input| #parse,
+            )
+        },
+    };
+    quote! {
+        impl #impl_generics #serialize_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #string_ty: ::core::fmt::Display,
+        {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                ::branded::serde::serialize_prefixed(#prefix, #value, serializer)
+            }
+        }
+
+        impl #de_impl_generics #deserialize_trait<'__de> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #string_ty: ::core::str::FromStr,
+        {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'__de>,
+            {
+                #deserialize
+            }
+        }
+    }
+}
+
+/// Derive a Serde implementation (de)serializing the branded type as a struct of its brand name and
+/// inner value, such as `{"type": "UserId", "value": 42}`.
+///
+/// Structs naming another brand are rejected, and the inner value is validated.
+pub(crate) fn expand_serde_wrapped_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let serialize_trait: syn::Path = syn::parse_quote!(::serde::Serialize);
+    let deserialize_trait: syn::Path = syn::parse_quote!(::serde::Deserialize);
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('__de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let construct = expand_construct(validate, quote!(::serde::de::Error::custom));
+    quote! {
+        impl #impl_generics #serialize_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #serialize_trait,
+        {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                ::branded::serde::serialize_wrapped(<Self as Branded>::BRAND, self.inner(), serializer)
+            }
+        }
+
+        impl #de_impl_generics #deserialize_trait<'__de> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #deserialize_trait<'__de>,
+        {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'__de>,
+            {
+                ::branded::serde::deserialize_wrapped::<D, <Self as Branded>::Inner>(
+                    deserializer,
+                    <Self as Branded>::BRAND,
+                )
+                #construct
+            }
+        }
+    }
+}
+
+/// Derive a sqlx Type, Encode, and Decode implementation for the branded type if asked for.
+///
+/// With `postgres`, the Type implementation is specific to Postgres, which also allows arrays of
+/// the brand and, with a `type_name`, mapping the brand to a custom Postgres type.
+///
+/// With `databases`, the impls are generated for each of the given databases instead of for any
+/// database, so that they cannot conflict with impls for the inner type on other databases.
+pub(crate) fn expand_sqlx_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    postgres: bool,
+    type_name: Option<&str>,
+    databases: Option<&[syn::Path]>,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let type_trait: syn::Path = syn::parse_quote!(::sqlx::Type);
+    let encode_trait: syn::Path = syn::parse_quote!(::sqlx::Encode);
+    let decode_trait: syn::Path = syn::parse_quote!(::sqlx::Decode);
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let construct = expand_construct(validate, quote!(::core::convert::Into::into));
+    let type_impl = if postgres {
+        let (impl_generics, _, _) = generics.split_for_impl();
+        let array_trait: syn::Path = syn::parse_quote!(::sqlx::postgres::PgHasArrayType);
+        let (type_info, array_type_info) = match type_name {
+            Some(type_name) => {
+                let array_type_name = format!("_{type_name}");
+                (
+                    quote!(::sqlx::postgres::PgTypeInfo::with_name(#type_name)),
+                    quote!(::sqlx::postgres::PgTypeInfo::with_name(#array_type_name)),
+                )
+            }
+            None => (
+                quote!(<<Self as Branded>::Inner as #type_trait<::sqlx::Postgres>>::type_info()),
+                quote!(<<Self as Branded>::Inner as #array_trait>::array_type_info()),
+            ),
+        };
+        quote! {
+            impl #impl_generics #type_trait<::sqlx::Postgres> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #type_trait<::sqlx::Postgres>,
+            {
+                fn type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    #type_info
+                }
+
+                fn compatible(ty: &::sqlx::postgres::PgTypeInfo) -> bool {
+                    *ty == #type_info || <<Self as Branded>::Inner as #type_trait<::sqlx::Postgres>>::compatible(ty)
+                }
+            }
+
+            impl #impl_generics #array_trait for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #array_trait,
+            {
+                fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    #array_type_info
+                }
+            }
+        }
+    } else {
+        expand_sqlx_targets(generics, databases, None, |impl_generics, db| {
+            quote! {
+                impl #impl_generics #type_trait<#db> for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    for<'__branded> <Self as Branded>::Inner: #type_trait<#db>,
+                    #db: ::sqlx::Database,
+                {
+                    fn type_info() -> <#db as ::sqlx::Database>::TypeInfo {
+                        <<Self as Branded>::Inner as #type_trait<#db>>::type_info()
+                    }
+                }
+            }
+        })
+    };
+    let decode_impls = expand_sqlx_targets(
+        generics,
+        databases,
+        Some(quote!('__de)),
+        |impl_generics, db| {
+            quote! {
+                impl #impl_generics #decode_trait<'__de, #db> for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    for<'__branded, '__value> #ty: #decode_trait<'__value, #db>,
+                    #db: ::sqlx::Database,
+                {
+                    fn decode(value: <#db as ::sqlx::Database>::ValueRef<'_>) -> ::core::result::Result<Self, ::sqlx::error::BoxDynError> {
+                        <#ty as #decode_trait<'_, #db>>::decode(value) #construct
+                    }
+                }
+            }
+        },
+    );
+    let encode_impls = expand_sqlx_targets(
+        generics,
+        databases,
+        Some(quote!('__en)),
+        |impl_generics, db| {
+            quote! {
+                impl #impl_generics #encode_trait<'__en, #db> for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    for<'__branded> Self: Branded,
+                    <Self as Branded>::Inner: for<'__value> #encode_trait<'__value, #db>,
+                    #db: ::sqlx::Database,
+                {
+                    fn encode_by_ref(&self, buf: &mut <#db as ::sqlx::Database>::ArgumentBuffer<'_>) -> ::core::result::Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                        #encode_trait::<#db>::encode_by_ref(self.inner(), buf)
+                    }
+                }
+            }
+        },
+    );
+    quote! {
+        #type_impl
+        #decode_impls
+        #encode_impls
+    }
+}
+
+/// Derive a sqlx Type, Encode, and Decode implementation for a branded 128-bit integer, stored with
+/// the given strategy since databases have no native 128-bit integer type.
+pub(crate) fn expand_sqlx_int128_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    strategy: Int128Strategy,
+    databases: Option<&[syn::Path]>,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let (storage_ty, decoded_ty, to_storage, from_storage) = match strategy {
+        Int128Strategy::Bytes => (
+            quote!(::branded::sqlx::Vec<u8>),
+            quote!(&'__de [u8]),
+            quote!(::branded::sqlx::encode_int128_bytes(*self.inner())),
+            quote!(::branded::sqlx::decode_int128_bytes),
+        ),
+        Int128Strategy::Text => (
+            quote!(::branded::sqlx::String),
+            quote!(&'__de str),
+            quote!(::branded::sqlx::encode_int128_text(*self.inner())),
+            quote!(::branded::sqlx::decode_int128_text),
+        ),
+    };
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let construct = expand_construct(validate, quote!(::core::convert::Into::into));
+    let type_impls = expand_sqlx_targets(generics, databases, None, |impl_generics, db| {
+        quote! {
+            impl #impl_generics ::sqlx::Type<#db> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: ::branded::sqlx::Int128,
+                #storage_ty: ::sqlx::Type<#db>,
+                #db: ::sqlx::Database,
+            {
+                fn type_info() -> <#db as ::sqlx::Database>::TypeInfo {
+                    <#storage_ty as ::sqlx::Type<#db>>::type_info()
+                }
+
+                fn compatible(ty: &<#db as ::sqlx::Database>::TypeInfo) -> bool {
+                    <#storage_ty as ::sqlx::Type<#db>>::compatible(ty)
+                }
+            }
+        }
+    });
+    let decode_impls = expand_sqlx_targets(
+        generics,
+        databases,
+        Some(quote!('__de)),
+        |impl_generics, db| {
+            quote! {
+                impl #impl_generics ::sqlx::Decode<'__de, #db> for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    for<'__branded> <Self as Branded>::Inner: ::branded::sqlx::Int128,
+                    #decoded_ty: ::sqlx::Decode<'__de, #db>,
+                    #db: ::sqlx::Database,
+                {
+                    fn decode(value: <#db as ::sqlx::Database>::ValueRef<'__de>) -> ::core::result::Result<Self, ::sqlx::error::BoxDynError> {
+                        let value = <#decoded_ty as ::sqlx::Decode<'__de, #db>>::decode(value)?;
+                        #from_storage(value) #construct
+                    }
+                }
+            }
+        },
+    );
+    let encode_impls = expand_sqlx_targets(
+        generics,
+        databases,
+        Some(quote!('__en)),
+        |impl_generics, db| {
+            quote! {
+                impl #impl_generics ::sqlx::Encode<'__en, #db> for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                    for<'__branded> <Self as Branded>::Inner: ::branded::sqlx::Int128,
+                    #storage_ty: ::sqlx::Encode<'__en, #db>,
+                    #db: ::sqlx::Database,
+                {
+                    fn encode_by_ref(&self, buf: &mut <#db as ::sqlx::Database>::ArgumentBuffer<'__en>) -> ::core::result::Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                        <#storage_ty as ::sqlx::Encode<'__en, #db>>::encode(#to_storage, buf)
+                    }
+                }
+            }
+        },
+    );
+    quote! {
+        #type_impls
+        #decode_impls
+        #encode_impls
+    }
+}
+
+/// Expand a sqlx impl for each of the given databases, or once for any database `DB` if no
+/// databases are given, with an optional leading lifetime parameter.
+fn expand_sqlx_targets(
+    generics: &syn::Generics,
+    databases: Option<&[syn::Path]>,
+    lifetime: Option<proc_macro2::TokenStream>,
+    expand: impl Fn(&syn::ImplGenerics, proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut target_generics = generics.clone();
+    if let Some(lifetime) = lifetime {
+        target_generics.params.insert(0, syn::parse_quote!(#lifetime));
+    }
+    match databases {
+        Some(databases) => {
+            let (impl_generics, _, _) = target_generics.split_for_impl();
+            databases.iter().map(|database| expand(&impl_generics, quote!(#database))).collect()
+        }
+        None => {
+            target_generics.params.push(syn::parse_quote!(DB));
+            let (impl_generics, _, _) = target_generics.split_for_impl();
+            expand(&impl_generics, quote!(DB))
+        }
+    }
+}
+
+/// Parse the namespace of `uuid(v5 = "...")`, which is either one of the well-known namespaces of
+/// RFC 9562 or a UUID, into an expression of the namespace UUID.
+fn parse_uuid_namespace(namespace: &syn::LitStr) -> syn::Result<proc_macro2::TokenStream> {
+    match namespace.value().as_str() {
+        "dns" => Ok(quote!(::uuid::Uuid::NAMESPACE_DNS)),
+        "url" => Ok(quote!(::uuid::Uuid::NAMESPACE_URL)),
+        "oid" => Ok(quote!(::uuid::Uuid::NAMESPACE_OID)),
+        "x500" => Ok(quote!(::uuid::Uuid::NAMESPACE_X500)),
+        uuid => {
+            // Both the hyphenated and the simple format are accepted.
+            let digits = match uuid.len() {
+                36 if [8, 13, 18, 23].iter().all(|&i| uuid.as_bytes()[i] == b'-') => {
+                    uuid.replace('-', "")
+                }
+                _ => uuid.to_owned(),
+            };
+            let value = (digits.len() == 32 && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+                .then(|| u128::from_str_radix(&digits, 16).expect("the digits are hexadecimal"))
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        namespace,
+                        "expected `dns`, `url`, `oid`, `x500`, or a UUID as the v5 namespace",
+                    )
+                })?;
+            let value = proc_macro2::Literal::u128_suffixed(value);
+            Ok(quote!(::uuid::Uuid::from_u128(#value)))
+        }
+    }
+}
+
+/// Parse the comma-separated databases of `sqlx(only = "...")` into their sqlx types.
+fn parse_sqlx_databases(only: &syn::LitStr) -> syn::Result<Vec<syn::Path>> {
+    only.value()
+        .split(',')
+        .map(|database| match database.trim() {
+            "postgres" => Ok(syn::parse_quote!(::sqlx::Postgres)),
+            "mysql" => Ok(syn::parse_quote!(::sqlx::MySql)),
+            "sqlite" => Ok(syn::parse_quote!(::sqlx::Sqlite)),
+            database => Err(syn::Error::new_spanned(
+                only,
+                format!(
+                    "unknown sqlx database `{database}`, expected `postgres`, `mysql` or `sqlite`"
+                ),
+            )),
+        })
+        .collect()
+}
+
+/// Generate keyed query methods for a branded type stored in the given table and column.
+///
+/// The methods are available on every database the brand and pool support, and delegate to the
+/// query helpers in `branded::sqlx`.
+pub(crate) fn expand_sqlx_table_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    table: &str,
+    column: &str,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let bounds = quote! {
+        DB: ::sqlx::Database,
+        Self: for<'q> ::sqlx::Encode<'q, DB> + ::sqlx::Type<DB> + ::core::marker::Sync,
+        for<'q> DB::Arguments<'q>: ::sqlx::IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: ::sqlx::Executor<'c, Database = DB>,
+    };
+    quote! {
+        impl #impl_generics #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            #[doc = ::core::concat!("Check whether a row with this ID exists in the `", #table, "` table.")]
+            pub async fn exists<DB>(
+                &self,
+                pool: &::sqlx::Pool<DB>,
+            ) -> ::core::result::Result<bool, ::sqlx::Error>
+            where
+                #bounds
+            {
+                ::branded::sqlx::exists(pool, #table, #column, self).await
+            }
+
+            #[doc = ::core::concat!("Delete the rows with this ID from the `", #table, "` table.")]
+            pub async fn delete<DB>(
+                &self,
+                pool: &::sqlx::Pool<DB>,
+            ) -> ::core::result::Result<DB::QueryResult, ::sqlx::Error>
+            where
+                #bounds
+            {
+                ::branded::sqlx::delete(pool, #table, #column, self).await
+            }
+        }
+    }
+}
+
+/// Derive a sqlx FromRow implementation reading the brand from a single column.
+///
+/// The brand is read from the named column, or from the first column if no name is given.
+pub(crate) fn expand_sqlx_from_row_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    column: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let index = match column {
+        Some(column) => quote!(#column),
+        None => quote!(0usize),
+    };
+    let index_ty = match column {
+        Some(_) => quote!(&'static str),
+        None => quote!(usize),
+    };
+    let mut row_generics = generics.clone();
+    row_generics.params.insert(0, syn::parse_quote!('r));
+    row_generics.params.push(syn::parse_quote!(R));
+    let (row_impl_generics, _, _) = row_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #row_impl_generics ::sqlx::FromRow<'r, R> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            R: ::sqlx::Row,
+            #index_ty: ::sqlx::ColumnIndex<R>,
+            Self: ::sqlx::Decode<'r, R::Database> + ::sqlx::Type<R::Database>,
+        {
+            fn from_row(row: &'r R) -> ::core::result::Result<Self, ::sqlx::Error> {
+                row.try_get(#index)
+            }
+        }
+    }
+}
+
+/// Derive the UUID constructors for the branded type if asked for.
+pub(crate) fn expand_uuid_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    v7: bool,
+    namespace: Option<proc_macro2::TokenStream>,
+    on_generate: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let new_v4 = expand_generated_value(quote!(Self(::uuid::Uuid::new_v4())), on_generate);
+    let new_v7 = expand_generated_value(quote!(Self(::uuid::Uuid::now_v7())), on_generate);
+    let v7_constructor = v7.then(|| {
+        quote! {
+            /// Get a new time-ordered UUID v7.
+            pub fn new_v7() -> Self { #new_v7 }
+
+            /// Get the time bucket of the given granularity that the UUID v7 was created in.
+            pub fn bucket(&self, granularity: ::core::time::Duration) -> ::branded::time_bucket::TimeBucket {
+                ::branded::time_bucket::TimeBucket::of_uuid_bytes(self.inner().as_bytes(), granularity)
+            }
+
+            /// Get the range from the smallest to the largest UUID v7 in the time bucket.
+            pub fn range_for_bucket(
+                bucket: ::branded::time_bucket::TimeBucket,
+            ) -> ::core::ops::RangeInclusive<Self> {
+                let min = ::uuid::Uuid::from_bytes(bucket.min_uuid_bytes());
+                let max = ::uuid::Uuid::from_bytes(bucket.max_uuid_bytes());
+                Self(min)..=Self(max)
+            }
+        }
+    });
+    let v5_constructor = namespace.map(|namespace| {
+        quote! {
+            /// The namespace of the name-based UUID v5 values of this brand.
+            pub const NAMESPACE: ::uuid::Uuid = #namespace;
+
+            /// Derive the deterministic UUID v5 of `name` in the namespace of this brand.
+            pub fn derive_from(name: impl ::core::convert::AsRef<[u8]>) -> Self {
+                Self(::uuid::Uuid::new_v5(&Self::NAMESPACE, name.as_ref()))
+            }
+        }
+    });
+    quote! {
+        impl #impl_generics #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> Self: Branded<Inner = ::uuid::Uuid>,
+        {
+            /// Get the nil UUID.
+            pub fn nil() -> Self { Self(::uuid::Uuid::nil()) }
+
+            /// Get a new random UUID v4.
+            pub fn new_v4() -> Self { #new_v4 }
+
+            ::branded::__uuid_for_test!();
+
+            #v7_constructor
+            #v5_constructor
+        }
+    }
+}
+
+/// Get an expression generating a new value of a UUID brand, preferring v7 if enabled.
+pub(crate) fn expand_uuid_generator(v7: bool) -> proc_macro2::TokenStream {
+    if v7 {
+        quote!(Self::new_v7())
+    } else {
+        quote!(Self::new_v4())
+    }
+}
+
+/// Derive the newtype-uuid bridges for a UUID brand if asked for.
+///
+/// This implements `GenericUuid` for the branded type, and conversions in both directions between
+/// the branded type and `TypedUuid` of the given kind.
+pub(crate) fn expand_newtype_uuid_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    kind: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let from_trait: syn::Path = syn::parse_quote!(::core::convert::From);
+    let generic_uuid_trait: syn::Path = syn::parse_quote!(::newtype_uuid::GenericUuid);
+    let typed_uuid: syn::Type = syn::parse_quote!(::newtype_uuid::TypedUuid<#kind>);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #generic_uuid_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> Self: Branded<Inner = ::uuid::Uuid>,
+        {
+            fn from_untyped_uuid(uuid: ::uuid::Uuid) -> Self {
+                Self(uuid)
+            }
+
+            fn into_untyped_uuid(self) -> ::uuid::Uuid {
+                self.into_inner()
+            }
+
+            fn as_untyped_uuid(&self) -> &::uuid::Uuid {
+                self.inner()
+            }
+        }
+
+        impl #impl_generics #from_trait<#typed_uuid> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> Self: Branded<Inner = ::uuid::Uuid>,
+        {
+            fn from(value: #typed_uuid) -> Self {
+                Self(#generic_uuid_trait::into_untyped_uuid(value))
+            }
+        }
+
+        impl #impl_generics #from_trait<#brand_struct_name #ty_generics> for #typed_uuid
+        where
+            #(#predicates,)*
+            for<'__branded> #brand_struct_name #ty_generics: Branded<Inner = ::uuid::Uuid>,
+        {
+            fn from(value: #brand_struct_name #ty_generics) -> Self {
+                #generic_uuid_trait::from_untyped_uuid(value.into_inner())
+            }
+        }
+    }
+}
+
+/// Derive a defmt Format implementation for the branded type if asked for.
+///
+/// Like the Debug implementation, this formats the inner value wrapped in the branded type name.
+pub(crate) fn expand_defmt_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let format_trait: syn::Path = syn::parse_quote!(::defmt::Format);
+    let format_string = format!("{brand_struct_name}({{}})");
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #format_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #format_trait,
+        {
+            fn format(&self, f: ::defmt::Formatter<'_>) {
+                ::defmt::write!(f, #format_string, self.inner())
+            }
+        }
+    }
+}
+
+/// Derive conversions between the branded type and polars columns and values if asked for.
+///
+/// The conversions of columns are inherent functions, since the orphan rules prevent implementing
+/// polars traits for `Vec<Brand>`. Converting a column moves the values into a column of the inner
+/// type, which reuses the allocation. Validated brands validate every value that is read.
+pub(crate) fn expand_polars_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let polars_inner_trait: syn::Path = syn::parse_quote!(::branded::polars::PolarsInner);
+    let validation_error: syn::Path = syn::parse_quote!(::branded::polars::validation_error);
+    let from_series = if validate {
+        quote! {
+            values
+                .into_iter()
+                .map(Self::try_new)
+                .collect::<::core::result::Result<_, _>>()
+                .map_err(#validation_error)
+        }
+    } else {
+        quote!(::core::result::Result::Ok(values.into_iter().map(Self).collect()))
+    };
+    let construct = expand_construct(validate, quote!(#validation_error));
+    let into_series_doc_comment =
+        format!("Build a polars `Series` from a column of `{brand_struct_name}` values.");
+    let try_from_series_doc_comment =
+        format!("Read a column of `{brand_struct_name}` values from a polars `Series`.");
+    let mut any_value_generics = generics.clone();
+    any_value_generics.params.insert(0, syn::parse_quote!('__any_value));
+    let (any_value_impl_generics, _, _) = any_value_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #polars_inner_trait,
+        {
+            #[doc = #into_series_doc_comment]
+            pub fn into_series(
+                name: impl ::core::convert::Into<::polars::prelude::PlSmallStr>,
+                values: ::std::vec::Vec<Self>,
+            ) -> ::polars::prelude::Series {
+                let values = values.into_iter().map(Branded::into_inner).collect();
+                <<Self as Branded>::Inner as #polars_inner_trait>::into_series(name.into(), values)
+            }
+
+            #[doc = #try_from_series_doc_comment]
+            pub fn try_from_series(
+                series: &::polars::prelude::Series,
+            ) -> ::polars::prelude::PolarsResult<::std::vec::Vec<Self>> {
+                let values = <<Self as Branded>::Inner as #polars_inner_trait>::from_series(series)?;
+                #from_series
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#brand_struct_name #ty_generics>
+            for ::polars::prelude::AnyValue<'static>
+        where
+            #(#predicates,)*
+            for<'__branded> <#brand_struct_name #ty_generics as Branded>::Inner: #polars_inner_trait,
+        {
+            fn from(value: #brand_struct_name #ty_generics) -> Self {
+                #polars_inner_trait::into_any_value(value.into_inner())
+            }
+        }
+
+        impl #any_value_impl_generics ::core::convert::TryFrom<::polars::prelude::AnyValue<'__any_value>>
+            for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #polars_inner_trait,
+        {
+            type Error = ::polars::prelude::PolarsError;
+
+            fn try_from(value: ::polars::prelude::AnyValue<'__any_value>) -> ::core::result::Result<Self, Self::Error> {
+                <<Self as Branded>::Inner as #polars_inner_trait>::from_any_value(value)#construct
+            }
+        }
+    }
+}
+
+/// Derive a conversion into an InfluxDB value for the branded type if asked for.
+pub(crate) fn expand_influxdb_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let influx_value_trait: syn::Path = syn::parse_quote!(::branded::influxdb::InfluxValue);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics ::core::convert::From<#brand_struct_name #ty_generics> for ::influxdb::Type
+        where
+            #(#predicates,)*
+            for<'__branded> <#brand_struct_name #ty_generics as Branded>::Inner: #influx_value_trait,
+        {
+            fn from(value: #brand_struct_name #ty_generics) -> Self {
+                #influx_value_trait::into_influx_value(value.into_inner())
+            }
+        }
+    }
+}
+
+/// Derive conversions between the branded type and ASCII gRPC metadata values if asked for.
+pub(crate) fn expand_tonic_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let construct = expand_construct(validate, quote!(::branded::tonic::validation_status));
+    let from_str_trait: syn::Path = syn::parse_quote!(::core::str::FromStr);
+    let display_trait: syn::Path = syn::parse_quote!(::core::fmt::Display);
+    let metadata_value: syn::Type =
+        syn::parse_quote!(::tonic::metadata::MetadataValue<::tonic::metadata::Ascii>);
+    let mut ref_generics = generics.clone();
+    ref_generics.params.insert(0, syn::parse_quote!('__metadata));
+    let (ref_impl_generics, _, _) = ref_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #ref_impl_generics ::core::convert::TryFrom<&'__metadata #metadata_value>
+            for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #from_str_trait,
+            for<'__branded> <<Self as Branded>::Inner as #from_str_trait>::Err: #display_trait,
+        {
+            type Error = ::tonic::Status;
+
+            fn try_from(value: &'__metadata #metadata_value) -> ::core::result::Result<Self, Self::Error> {
+                ::branded::tonic::parse_metadata_value(<Self as Branded>::BRAND, value)#construct
+            }
+        }
+
+        impl #ref_impl_generics ::core::convert::TryFrom<&'__metadata #brand_struct_name #ty_generics>
+            for #metadata_value
+        where
+            #(#predicates,)*
+            for<'__branded> <#brand_struct_name #ty_generics as Branded>::Inner: #display_trait,
+        {
+            type Error = ::tonic::metadata::errors::InvalidMetadataValue;
+
+            fn try_from(value: &'__metadata #brand_struct_name #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                ::branded::tonic::to_metadata_value(value.inner())
+            }
+        }
+    }
+}
+
+/// Derive a schemars JsonSchema implementation for the branded type if asked for.
+///
+/// The schema is the schema of the inner type, so that branded parameters and fields are
+/// documented as the value they serialize as. The okapi and aide options use this with the
+/// schemars crate they depend on, given as `schemars`.
+///
+/// The `example` and `schema_format` options are added to the schema of the inner type. Such a
+/// schema is always inlined, since it no longer matches the schema registered for the inner type.
+/// The example is a string if the inner schema allows strings, and is parsed as JSON otherwise.
+pub(crate) fn expand_json_schema_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    schemars: syn::Path,
+    example: Option<&str>,
+    format: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let json_schema_trait: syn::Path = syn::parse_quote!(#schemars::JsonSchema);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let inner_schema = quote! {
+        <<Self as Branded>::Inner as #json_schema_trait>::json_schema(generator)
+    };
+    let (is_referenceable, json_schema) = if example.is_none() && format.is_none() {
+        (quote!(<<Self as Branded>::Inner as #json_schema_trait>::is_referenceable()), inner_schema)
+    } else {
+        let format = format.map(|format| {
+            quote! { schema.format = ::core::option::Option::Some(#format.into()); }
+        });
+        let example = example.map(|example| {
+            quote! {
+                let example = if schema.has_type(#schemars::schema::InstanceType::String) {
+                    #schemars::_serde_json::Value::String(#example.into())
+                } else {
+                    #schemars::_serde_json::from_str(#example)
+                        .unwrap_or_else(|_| #schemars::_serde_json::Value::String(#example.into()))
+                };
+                schema.metadata().examples.push(example);
+            }
+        });
+        let json_schema = quote! {
+            let mut schema = #inner_schema.into_object();
+            #format
+            #example
+            #schemars::schema::Schema::Object(schema)
+        };
+        (quote!(false), json_schema)
+    };
+    quote! {
+        impl #impl_generics #json_schema_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #json_schema_trait,
+        {
+            fn schema_name() -> ::std::string::String {
+                <<Self as Branded>::Inner as #json_schema_trait>::schema_name()
+            }
+
+            fn schema_id() -> ::std::borrow::Cow<'static, str> {
+                <<Self as Branded>::Inner as #json_schema_trait>::schema_id()
+            }
+
+            fn is_referenceable() -> bool {
+                #is_referenceable
+            }
+
+            fn json_schema(
+                generator: &mut #schemars::gen::SchemaGenerator,
+            ) -> #schemars::schema::Schema {
+                #json_schema
+            }
+        }
+    }
+}
+
+/// Derive the cynic scalar traits for the branded type if asked for.
+///
+/// The option names the scalar marker type in the cynic schema module, such as `schema::ID`, and
+/// the variable trait is implemented from the `variable` module next to it, like `impl_scalar!`.
+pub(crate) fn expand_cynic_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    scalar: &syn::Path,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut schema_module = scalar.clone();
+    if schema_module.segments.pop().is_none() || schema_module.segments.is_empty() {
+        return Err(syn::Error::new_spanned(
+            scalar,
+            "expected the path to a scalar in a cynic schema module, such as `schema::ID`",
+        ));
+    }
+    schema_module.segments.pop_punct();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    Ok(quote! {
+        impl #impl_generics ::cynic::schema::IsScalar<#scalar> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            type SchemaType = #scalar;
+        }
+
+        impl #impl_generics #schema_module::variable::Variable for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            const TYPE: ::cynic::variables::VariableType = ::cynic::variables::VariableType::Named(
+                <#scalar as ::cynic::schema::NamedType>::NAME,
+            );
+        }
+
+        ::cynic::impl_coercions!(
+            #brand_struct_name #ty_generics [#impl_generics] [where #(#predicates,)*],
+            #scalar
+        );
+    })
+}
+
+/// Derive a prometheus-client EncodeLabelValue implementation for the branded type if asked for.
+pub(crate) fn expand_prometheus_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let encode_trait: syn::Path =
+        syn::parse_quote!(::prometheus_client::encoding::EncodeLabelValue);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #encode_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #encode_trait,
+        {
+            fn encode(
+                &self,
+                encoder: &mut ::prometheus_client::encoding::LabelValueEncoder,
+            ) -> ::core::result::Result<(), ::core::fmt::Error> {
+                #encode_trait::encode(self.inner(), encoder)
+            }
+        }
+    }
+}
+
+/// Derive conversions between the branded type and MySQL values if asked for.
+///
+/// The conversion from a value goes through `branded::mysql::Intermediate`, which wraps the
+/// intermediate result of the inner type, since `FromValue` requires an intermediate type that can
+/// be converted into the brand. Validated brands are their own intermediate type instead, so that
+/// values that do not pass validation fail to convert.
+pub(crate) fn expand_mysql_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let from_value_trait: syn::Path = syn::parse_quote!(::branded::mysql::FromValue);
+    let value_type: syn::Path = syn::parse_quote!(::branded::mysql::Value);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let from_value = if validate {
+        quote! {
+            impl #impl_generics #from_value_trait for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #from_value_trait,
+            {
+                type Intermediate = Self;
+            }
+
+            impl #impl_generics ::core::convert::TryFrom<#value_type> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #from_value_trait,
+            {
+                type Error = ::branded::mysql::FromValueError;
+
+                fn try_from(value: #value_type) -> ::core::result::Result<Self, Self::Error> {
+                    let inner = <<Self as Branded>::Inner as #from_value_trait>::from_value_opt(value.clone())?;
+                    Self::try_new(inner).map_err(|_| ::branded::mysql::FromValueError(value))
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #from_value_trait for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #from_value_trait,
+            {
+                type Intermediate = ::branded::mysql::Intermediate<Self>;
+            }
+
+            impl #impl_generics ::core::convert::From<::branded::mysql::Intermediate<Self>> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> <Self as Branded>::Inner: #from_value_trait,
+            {
+                fn from(intermediate: ::branded::mysql::Intermediate<Self>) -> Self {
+                    Self(intermediate.0.into())
+                }
+            }
+        }
+    };
+    quote! {
+        #from_value
+
+        impl #impl_generics ::core::convert::From<#brand_struct_name #ty_generics> for #value_type
+        where
+            #(#predicates,)*
+            for<'__branded> <#brand_struct_name #ty_generics as Branded>::Inner: ::core::convert::Into<#value_type>,
+        {
+            fn from(value: #brand_struct_name #ty_generics) -> Self {
+                value.into_inner().into()
+            }
+        }
+    }
+}
+
+/// Derive the Gel Queryable and ScalarArg implementations for the branded type if asked for.
+///
+/// Validated brands fail to decode values that do not pass validation.
+pub(crate) fn expand_gel_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let construct =
+        expand_construct(validate, quote!(::branded::gel::protocol::errors::decode_error));
+    let queryable_trait: syn::Path =
+        syn::parse_quote!(::branded::gel::protocol::queryable::Queryable);
+    let scalar_arg_trait: syn::Path =
+        syn::parse_quote!(::branded::gel::protocol::query_arg::ScalarArg);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #queryable_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #queryable_trait,
+        {
+            type Args = <<Self as Branded>::Inner as #queryable_trait>::Args;
+
+            fn decode(
+                decoder: &::branded::gel::protocol::queryable::Decoder,
+                args: &Self::Args,
+                buf: &[u8],
+            ) -> ::core::result::Result<Self, ::branded::gel::protocol::errors::DecodeError> {
+                <<Self as Branded>::Inner as #queryable_trait>::decode(decoder, args, buf)#construct
+            }
+
+            fn decode_optional(
+                decoder: &::branded::gel::protocol::queryable::Decoder,
+                args: &Self::Args,
+                buf: ::core::option::Option<&[u8]>,
+            ) -> ::core::result::Result<Self, ::branded::gel::protocol::errors::DecodeError> {
+                <<Self as Branded>::Inner as #queryable_trait>::decode_optional(decoder, args, buf)#construct
+            }
+
+            fn check_descriptor(
+                ctx: &::branded::gel::protocol::queryable::DescriptorContext,
+                type_pos: ::branded::gel::protocol::descriptors::TypePos,
+            ) -> ::core::result::Result<Self::Args, ::branded::gel::protocol::queryable::DescriptorMismatch> {
+                <<Self as Branded>::Inner as #queryable_trait>::check_descriptor(ctx, type_pos)
+            }
+        }
+
+        impl #impl_generics #scalar_arg_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #scalar_arg_trait,
+        {
+            fn encode(
+                &self,
+                encoder: &mut ::branded::gel::protocol::query_arg::Encoder,
+            ) -> ::core::result::Result<(), ::branded::gel::errors::Error> {
+                #scalar_arg_trait::encode(self.inner(), encoder)
+            }
+
+            fn check_descriptor(
+                ctx: &::branded::gel::protocol::query_arg::DescriptorContext,
+                pos: ::branded::gel::protocol::descriptors::TypePos,
+            ) -> ::core::result::Result<(), ::branded::gel::errors::Error> {
+                <<Self as Branded>::Inner as #scalar_arg_trait>::check_descriptor(ctx, pos)
+            }
+
+            fn to_value(
+                &self,
+            ) -> ::core::result::Result<::branded::gel::protocol::value::Value, ::branded::gel::errors::Error> {
+                #scalar_arg_trait::to_value(self.inner())
+            }
+        }
+    }
+}
+
+/// Derive the zvariant Type implementation for the branded type if asked for.
+///
+/// The signature is that of the inner type, matching its transparent serde representation.
+pub(crate) fn expand_zbus_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let type_trait: syn::Path = syn::parse_quote!(::branded::zbus::zvariant::Type);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #type_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #type_trait,
+        {
+            const SIGNATURE: &'static ::branded::zbus::zvariant::Signature =
+                <<Self as Branded>::Inner as #type_trait>::SIGNATURE;
+        }
+    }
+}
+
+/// Derive the rustler Encoder and Decoder implementations for the branded type if asked for.
+///
+/// Validated brands fail to decode terms that do not pass validation with `BadArg`.
+pub(crate) fn expand_rustler_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let construct = expand_construct(validate, quote!(|_| ::rustler::Error::BadArg));
+    let encoder_trait: syn::Path = syn::parse_quote!(::rustler::Encoder);
+    let decoder_trait: syn::Path = syn::parse_quote!(::rustler::Decoder);
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('__term));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #encoder_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #encoder_trait,
+        {
+            fn encode<'__term>(&self, env: ::rustler::Env<'__term>) -> ::rustler::Term<'__term> {
+                #encoder_trait::encode(self.inner(), env)
+            }
+        }
+
+        impl #de_impl_generics #decoder_trait<'__term> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #ty: #decoder_trait<'__term>,
+            Self: '__term,
+        {
+            fn decode(term: ::rustler::Term<'__term>) -> ::rustler::NifResult<Self> {
+                <#ty as #decoder_trait<'__term>>::decode(term)#construct
+            }
+        }
+    }
+}
+
+/// Implement TransparentBrand for a `#[repr(transparent)]` branded type if asked for, so that
+/// collections of the brand can be cast to and from collections of the inner type.
+pub(crate) fn expand_cast_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        // SAFETY: The brand is `#[repr(transparent)]` over its inner type.
+        unsafe impl #impl_generics ::branded::cast::TransparentBrand for #brand_struct_name #ty_generics
+            #where_clause
+        {
+        }
+    }
+}
+
+/// Implement bytemuck's Zeroable, Pod, and TransparentWrapper traits for a `#[repr(transparent)]`
+/// branded type if asked for, when the inner type is Zeroable and Pod.
+pub(crate) fn expand_bytemuck_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let zeroable_trait: syn::Path = syn::parse_quote!(::bytemuck::Zeroable);
+    let pod_trait: syn::Path = syn::parse_quote!(::bytemuck::Pod);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        // SAFETY: The brand is `#[repr(transparent)]` over its inner type, so it is valid for the
+        // same bit patterns.
+        unsafe impl #impl_generics #zeroable_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #zeroable_trait,
+        {
+        }
+
+        // SAFETY: As above, and the brand is `Copy` when the inner type is.
+        unsafe impl #impl_generics #pod_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #pod_trait,
+        {
+        }
+
+        // SAFETY: The brand is `#[repr(transparent)]` over its inner type.
+        unsafe impl #impl_generics ::bytemuck::TransparentWrapper<#ty> for #brand_struct_name #ty_generics
+            #where_clause
+        {
+        }
+    }
+}
+
+/// Derive the DenseId implementation for the branded type if asked for, so that it can be stored
+/// in a `branded::bitset::BrandedBitSet`.
+pub(crate) fn expand_bitset_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let dense_inner_trait: syn::Path = syn::parse_quote!(::branded::bitset::DenseInner);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics ::branded::bitset::DenseId for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #dense_inner_trait,
+        {
+            fn to_index(&self) -> u64 {
+                #dense_inner_trait::to_index(*self.inner())
+            }
+
+            fn from_index(index: u64) -> Self {
+                Self(#dense_inner_trait::from_index(index))
+            }
+        }
+    }
+}
+
+/// Derive the Sentry identifier implementation for the branded type if asked for.
+///
+/// The key is the snake_case name of the brand. The value is the Display output of the inner
+/// value, or `[redacted]` if the brand is marked as personal data.
+pub(crate) fn expand_sentry_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    pii: bool,
+) -> proc_macro2::TokenStream {
+    let sentry_id_trait: syn::Path = syn::parse_quote!(::branded::sentry::SentryId);
+    let key = to_snake_case(&brand_struct_name.to_string());
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let (bound, value) = if pii {
+        (quote!(), quote!(f.write_str(::branded::sentry::REDACTED)))
+    } else {
+        (
+            quote!(for<'__branded> <Self as Branded>::Inner: ::core::fmt::Display,),
+            quote!(::core::fmt::Display::fmt(self.inner(), f)),
+        )
+    };
+    quote! {
+        impl #impl_generics #sentry_id_trait for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            #bound
+        {
+            const KEY: &'static str = #key;
+
+            fn fmt_sentry_value(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #value
+            }
+        }
+    }
+}
+
+/// Derive conversions in both directions between the branded type and the brand it is an alias of.
+///
+/// Both brands must have the same inner type. This allows renaming a brand incrementally, by
+/// marking the old brand as `#[deprecated]` and converting at the boundaries that have not been
+/// migrated yet. The old brand is constructed through `FromInner`, so that its constructor may be
+/// renamed, and the conversion into it is not generated if it is validated.
+pub(crate) fn expand_alias_of_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    alias_of: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let from_trait: syn::Path = syn::parse_quote!(::core::convert::From);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #from_trait<#alias_of> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            for<'__branded> #alias_of: Branded<Inner = <Self as Branded>::Inner>,
+        {
+            fn from(value: #alias_of) -> Self {
+                Self(value.into_inner())
+            }
+        }
+
+        impl #impl_generics #from_trait<#brand_struct_name #ty_generics> for #alias_of
+        where
+            #(#predicates,)*
+            for<'__branded> #alias_of: ::branded::FromInner<Inner = <#brand_struct_name #ty_generics as Branded>::Inner>,
+        {
+            fn from(value: #brand_struct_name #ty_generics) -> Self {
+                <#alias_of as ::branded::FromInner>::from_inner(value.into_inner())
+            }
+        }
+    }
+}
+
+/// Derive a conversion into the branded type from another brand with the same inner type.
+///
+/// Unlike `alias_of`, the conversion only goes one way, so each bridge between brands is declared
+/// explicitly. Validated brands get a `TryFrom` conversion through `try_new` instead.
+pub(crate) fn expand_convert_from_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    source: &syn::Path,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let bound = quote! {
+        for<'__branded> #source: Branded<Inner = <Self as Branded>::Inner>
+    };
+    if validate {
+        return quote! {
+            impl #impl_generics ::core::convert::TryFrom<#source> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                #bound,
+            {
+                type Error = ::branded::Error;
+
+                fn try_from(value: #source) -> ::core::result::Result<Self, Self::Error> {
+                    Self::try_new(value.into_inner())
+                }
+            }
+        };
+    }
+    quote! {
+        impl #impl_generics ::core::convert::From<#source> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+            #bound,
+        {
+            fn from(value: #source) -> Self {
+                Self(value.into_inner())
+            }
+        }
+    }
+}
+
+/// Derive conversions in both directions between the branded type and its inner type if asked for.
+///
+/// The orphan rules forbid the conversion out of a brand that is generic over its inner type, so
+/// only the conversion into the brand is generated for those.
+pub(crate) fn expand_from_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let from_trait: syn::Path = syn::parse_quote!(::core::convert::From);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let from_brand_impl = (!is_generic_type(ty, generics)).then(|| {
+        quote! {
+            impl #impl_generics #from_trait<#brand_struct_name #ty_generics> for #ty
+            where
+                #(#predicates,)*
+            {
+                fn from(value: #brand_struct_name #ty_generics) -> Self {
+                    value.into_inner()
+                }
+            }
         }
     });
+    quote! {
+        impl #impl_generics #from_trait<#ty> for #brand_struct_name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            fn from(inner: #ty) -> Self {
+                Self(inner)
+            }
+        }
 
-    tokens.extend(expand_clone_copy_impl(struct_name));
-    tokens.extend(expand_debug_display_impl(struct_name));
-    tokens.extend(expand_default_impl(struct_name));
-    tokens.extend(expand_ord_impl(struct_name));
-    tokens.extend(expand_hash_impl(struct_name));
-
-    if options.serde {
-        tokens.extend(expand_serde_impl(struct_name));
+        #from_brand_impl
     }
+}
 
-    if options.sqlx {
-        tokens.extend(expand_sqlx_impl(struct_name));
+/// Derive a constructor generating a new value with a custom generator function if asked for.
+///
+/// With the `testing` feature, the generator of a non-generic brand can be overridden for
+/// deterministic values.
+pub(crate) fn expand_generator_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    function: &syn::Path,
+    on_generate: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let generated = expand_generated_value(quote!(Self(#function())), on_generate);
+    let overridden = expand_generated_value(quote!(Self(generator())), on_generate);
+    // Statics cannot refer to the generic parameters of the brand, so generic brands have no
+    // override.
+    if !cfg!(feature = "testing") || !generics.params.is_empty() {
+        return quote! {
+            impl #impl_generics #brand_struct_name #ty_generics #where_clause {
+                /// Generate a new value with the generator of the brand.
+                pub fn generate() -> Self {
+                    #generated
+                }
+            }
+        };
     }
+    quote! {
+        static GENERATOR_OVERRIDE: ::branded::__private::GeneratorOverride<#ty> =
+            ::branded::__private::GeneratorOverride::new();
+
+        impl #brand_struct_name {
+            /// Generate a new value with the generator of the brand.
+            pub fn generate() -> Self {
+                if let ::core::option::Option::Some(generator) = GENERATOR_OVERRIDE.get() {
+                    return #overridden;
+                }
+                #generated
+            }
 
-    if options.uuid {
-        tokens.extend(expand_uuid_impl(struct_name));
+            /// Override the generator of the brand for the whole process, or restore it with
+            /// `None`.
+            pub fn override_generator(generator: ::core::option::Option<fn() -> #ty>) {
+                GENERATOR_OVERRIDE.set(generator);
+            }
+        }
     }
+}
 
-    Ok(tokens)
+/// Get an expression evaluating to a newly generated value, passing it to the `on_generate` hook
+/// first if there is one.
+pub(crate) fn expand_generated_value(
+    value: proc_macro2::TokenStream,
+    on_generate: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    match on_generate {
+        Some(on_generate) => quote! {{
+            let value = #value;
+            #on_generate(&value);
+            value
+        }},
+        None => value,
+    }
 }
 
-/// Derive a Clone implementation for the branded type if the inner type is Clone.
-pub(crate) fn expand_clone_copy_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let copy_trait: syn::Path = syn::parse_quote!(::std::marker::Copy);
-    let clone_trait: syn::Path = syn::parse_quote!(::std::clone::Clone);
+/// Derive a method returning the error context of a value of the branded type if asked for.
+pub(crate) fn expand_context_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let display_trait: syn::Path = syn::parse_quote!(::core::fmt::Display);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
     quote! {
-        impl #clone_trait for #brand_struct_name
+        impl #impl_generics #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #clone_trait,
+            #(#predicates,)*
+            for<'__branded> Self: #display_trait,
         {
-            fn clone(&self) -> Self {
-                Self::new(self.inner().clone())
+            /// Get the brand name and value, to attach to errors caused by this value.
+            pub fn context(&self) -> ::branded::context::BrandContext {
+                let mut value = ::branded::__private::String::new();
+                self.write_to(&mut value)
+                    .expect("a Display implementation returned an error unexpectedly");
+                ::branded::context::BrandContext::new(<Self as Branded>::BRAND, value)
             }
         }
-        impl #copy_trait for #brand_struct_name
+    }
+}
+
+/// Derive a method mapping the branded type to a shard in `0..modulo` if asked for.
+pub(crate) fn expand_shard_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    modulo: u16,
+) -> proc_macro2::TokenStream {
+    let shard_key_trait: syn::Path = syn::parse_quote!(::branded::shard::ShardKey);
+    let doc_comment = format!("Get the shard of the value, in `0..{modulo}`.");
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    quote! {
+        impl #impl_generics #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #copy_trait,
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #shard_key_trait,
         {
+            /// The number of shards of the brand.
+            pub const SHARDS: u16 = #modulo;
+
+            #[doc = #doc_comment]
+            pub fn shard(&self) -> u16 {
+                ::branded::shard::shard(self.inner(), #modulo)
+            }
         }
     }
 }
 
-/// Derive a Display and Debug implementation for the branded type if the inner type conforms to
-/// either trait.
+/// Derive comparisons of the branded type with its inner type if asked for.
 ///
-/// For the Debug implementation, this generates a Debug implementation that prints a tuple of the
-/// inner type contained in the branded type name.
-pub(crate) fn expand_debug_display_impl(
+/// Brands of `String` can also be compared with `str` and `&str`.
+pub(crate) fn expand_partial_eq_inner_impl(
     brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
 ) -> proc_macro2::TokenStream {
-    let display_trait: syn::Path = syn::parse_quote!(::std::fmt::Display);
-    let debug_trait: syn::Path = syn::parse_quote!(::std::fmt::Debug);
-    quote! {
-        impl #display_trait for #brand_struct_name
-        where
-            for<'__branded> <Self as Branded>::Inner: #display_trait,
-        {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                ::std::fmt::Display::fmt(&self.inner(), f)
+    let partial_eq_trait: syn::Path = syn::parse_quote!(::core::cmp::PartialEq);
+    let mut ref_generics = generics.clone();
+    ref_generics.params.insert(0, syn::parse_quote!('__str));
+    let (ref_impl_generics, _, _) = ref_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let str_impls = is_string_type(ty).then(|| {
+        quote! {
+            impl #impl_generics #partial_eq_trait<str> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+            {
+                fn eq(&self, other: &str) -> bool {
+                    self.inner() == other
+                }
+            }
+
+            impl #ref_impl_generics #partial_eq_trait<&'__str str> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+            {
+                fn eq(&self, other: &&'__str str) -> bool {
+                    self.inner() == *other
+                }
             }
         }
-        impl #debug_trait for #brand_struct_name
+    });
+    quote! {
+        impl #impl_generics #partial_eq_trait<#ty> for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #debug_trait,
+            #(#predicates,)*
+            for<'__branded> #ty: #partial_eq_trait,
         {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                f.debug_tuple(stringify!(#brand_struct_name)).field(self.inner()).finish()
+            fn eq(&self, other: &#ty) -> bool {
+                self.inner() == other
             }
         }
+
+        #str_impls
     }
 }
 
-/// Derive a Default implementation for the branded type if the inner type conforms to Default.
-pub(crate) fn expand_default_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let path: syn::Path = syn::parse_quote!(::std::default::Default);
+/// Derive fallible conversions from strings to the branded type if asked for.
+///
+/// Both conversions parse the string with the FromStr implementation of the branded type, so they
+/// apply the same parsing and fail with the same error. Either conversion is left out when the
+/// brand already converts from that string type, since it would overlap the blanket `TryFrom`
+/// impl of core.
+pub(crate) fn expand_try_from_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    include_str: bool,
+    include_string: bool,
+) -> proc_macro2::TokenStream {
+    let try_from_trait: syn::Path = syn::parse_quote!(::core::convert::TryFrom);
+    let from_str_trait: syn::Path = syn::parse_quote!(::core::str::FromStr);
+    let mut ref_generics = generics.clone();
+    ref_generics.params.insert(0, syn::parse_quote!('__str));
+    let (ref_impl_generics, _, _) = ref_generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let string_impl = include_string.then(|| {
+        quote! {
+            impl #impl_generics #try_from_trait<::branded::__private::String> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> Self: #from_str_trait,
+            {
+                type Error = <Self as #from_str_trait>::Err;
+
+                fn try_from(value: ::branded::__private::String) -> ::core::result::Result<Self, Self::Error> {
+                    value.parse()
+                }
+            }
+        }
+    });
+    let str_impl = include_str.then(|| {
+        quote! {
+            impl #ref_impl_generics #try_from_trait<&'__str str> for #brand_struct_name #ty_generics
+            where
+                #(#predicates,)*
+                for<'__branded> Self: #from_str_trait,
+            {
+                type Error = <Self as #from_str_trait>::Err;
+
+                fn try_from(value: &'__str str) -> ::core::result::Result<Self, Self::Error> {
+                    value.parse()
+                }
+            }
+        }
+    });
     quote! {
-        impl #path for #brand_struct_name
-        where
-            for<'__branded> <Self as Branded>::Inner: #path,
-        {
-            fn default() -> Self {
-                Self::new(<Self as Branded>::Inner::default())
+        #str_impl
+        #string_impl
+    }
+}
+
+/// Derive a conversion from the inner type for builder setters, which is `From` for plain brands
+/// and `TryFrom` through `try_new` for validated brands.
+pub(crate) fn expand_builder_inner_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if validate {
+        quote! {
+            impl #impl_generics ::core::convert::TryFrom<#ty> for #brand_struct_name #ty_generics #where_clause {
+                type Error = ::branded::Error;
+
+                fn try_from(inner: #ty) -> ::core::result::Result<Self, Self::Error> {
+                    Self::try_new(inner)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics ::core::convert::From<#ty> for #brand_struct_name #ty_generics #where_clause {
+                fn from(inner: #ty) -> Self {
+                    Self(inner)
+                }
             }
         }
     }
 }
 
-/// Derive a PartialEq, Eq, Ord, and PartialOrd implementation for the branded type if the inner
-/// type conforms to any of those traits.
-pub(crate) fn expand_ord_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let eq_trait: syn::Path = syn::parse_quote!(::std::cmp::Eq);
-    let partial_eq_trait: syn::Path = syn::parse_quote!(::std::cmp::PartialEq);
-    let ord_trait: syn::Path = syn::parse_quote!(::std::cmp::Ord);
-    let partial_ord_trait: syn::Path = syn::parse_quote!(::std::cmp::PartialOrd);
-    quote! {
-        impl #partial_eq_trait for #brand_struct_name
-        where
-            for<'__branded> <Self as Branded>::Inner: #partial_eq_trait,
-        {
-            fn eq(&self, other: &Self) -> bool {
-                self.inner().eq(other.inner())
+/// Implement the given user-defined marker traits for the branded type.
+pub(crate) fn expand_implements_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    marker_traits: &[syn::Path],
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    marker_traits
+        .iter()
+        .map(|marker_trait| {
+            quote! {
+                impl #impl_generics #marker_trait for #brand_struct_name #ty_generics
+                where
+                    #(#predicates,)*
+                {
+                }
+            }
+        })
+        .collect()
+}
+
+/// A segment of a path template.
+enum PathSegment {
+    /// A literal segment that must appear verbatim.
+    Literal(String),
+    /// A component, named after its type.
+    Component(syn::Ident),
+}
+
+/// Parse a path template such as `orgs/{OrgId}/projects/{ProjectId}` into its segments.
+fn parse_path_template(template: &syn::LitStr) -> syn::Result<Vec<PathSegment>> {
+    let value = template.value();
+    let mut segments = Vec::new();
+    let mut rest = value.as_str();
+    while !rest.is_empty() {
+        match rest.find(['{', '}']) {
+            Some(0) if rest.starts_with('{') => {
+                let end = rest.find('}').ok_or_else(|| {
+                    syn::Error::new(template.span(), "unclosed `{` in path template")
+                })?;
+                let name = &rest[1..end];
+                let ident = syn::parse_str::<syn::Ident>(name).map_err(|_| {
+                    syn::Error::new(
+                        template.span(),
+                        format!("`{{{name}}}` in path template must name the component type"),
+                    )
+                })?;
+                if let Some(PathSegment::Component(previous)) = segments.last() {
+                    return Err(syn::Error::new(
+                        template.span(),
+                        format!("components `{{{previous}}}` and `{{{ident}}}` must be separated by a literal segment"),
+                    ));
+                }
+                segments.push(PathSegment::Component(ident));
+                rest = &rest[end + 1..];
+            }
+            Some(0) => {
+                return Err(syn::Error::new(template.span(), "unmatched `}` in path template"));
+            }
+            Some(end) => {
+                segments.push(PathSegment::Literal(rest[..end].to_owned()));
+                rest = &rest[end..];
+            }
+            None => {
+                segments.push(PathSegment::Literal(rest.to_owned()));
+                rest = "";
             }
         }
-        impl #eq_trait for #brand_struct_name
-        where
-            for<'__branded> <Self as Branded>::Inner: #eq_trait,
-        {
+    }
+    Ok(segments)
+}
+
+/// Derive Display, FromStr, and component accessors for a path-structured branded type.
+///
+/// The inner type must be a tuple with one element per component in the template, in order, and
+/// each element type must be named like its component. Display writes the literal segments and
+/// the components, and FromStr parses each component up to the literal segment following it.
+pub(crate) fn expand_path_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    template: &syn::LitStr,
+    inner_ty: &syn::Type,
+    validate: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let segments = parse_path_template(template)?;
+    let components = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            PathSegment::Component(ident) => Some(ident),
+            PathSegment::Literal(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let element_types = match inner_ty {
+        syn::Type::Tuple(tuple) if tuple.elems.len() == components.len() => {
+            tuple.elems.iter().collect::<Vec<_>>()
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                inner_ty,
+                format!(
+                    "path-structured brands must wrap a tuple of their {} components",
+                    components.len()
+                ),
+            ))
+        }
+    };
+    for (component, element_ty) in components.iter().zip(&element_types) {
+        let element_name = match element_ty {
+            syn::Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+            _ => None,
+        };
+        if element_name != Some(*component) {
+            return Err(syn::Error::new_spanned(
+                element_ty,
+                format!("expected the `{{{component}}}` component of the path template to be a `{component}`"),
+            ));
+        }
+    }
+
+    let indices = (0..components.len()).map(syn::Index::from).collect::<Vec<_>>();
+    let accessors = components
+        .iter()
+        .map(|component| quote::format_ident!("{}", to_snake_case(&component.to_string())))
+        .collect::<Vec<_>>();
+    let accessor_doc_comments = components
+        .iter()
+        .map(|component| format!("Get the `{component}` component of the path."))
+        .collect::<Vec<_>>();
+
+    let mut component_index = 0;
+    let mut display_statements = Vec::new();
+    let mut parse_statements = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            PathSegment::Literal(literal) => {
+                display_statements.push(quote! { f.write_str(#literal)?; });
+                parse_statements.push(quote! {
+                    let input = ::branded::path::strip_literal(brand, input, #literal)?;
+                });
+            }
+            PathSegment::Component(_) => {
+                let index = &indices[component_index];
+                let element_ty = element_types[component_index];
+                let variable = quote::format_ident!("__component_{}", component_index);
+                let terminator = match segments.get(i + 1) {
+                    Some(PathSegment::Literal(literal)) => {
+                        quote!(::core::option::Option::Some(#literal))
+                    }
+                    _ => quote!(::core::option::Option::None),
+                };
+                display_statements.push(quote! { ::core::write!(f, "{}", self.0.#index)?; });
+                parse_statements.push(quote! {
+                    let (#variable, input) = ::branded::path::split_component(brand, input, #terminator)?;
+                    let #variable = #variable.parse::<#element_ty>().map_err(|_| {
+                        ::branded::Error::new(brand, ::branded::ErrorKind::Parse)
+                    })?;
+                });
+                component_index += 1;
+            }
         }
-        impl #ord_trait for #brand_struct_name
+    }
+    let variables = (0..components.len())
+        .map(|i| quote::format_ident!("__component_{}", i))
+        .collect::<Vec<_>>();
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let construct = expand_construct(validate, quote!(::core::convert::identity));
+    let predicates = where_predicates(generics);
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #ord_trait,
+            #(#predicates,)*
         {
-            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
-                self.0.cmp(&other.0)
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if f.alternate() {
+                    f.write_str(<Self as Branded>::BRAND)?;
+                    f.write_str(":")?;
+                }
+                #(#display_statements)*
+                ::core::result::Result::Ok(())
             }
         }
-        impl #partial_ord_trait for #brand_struct_name
+
+        impl #impl_generics ::core::str::FromStr for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #partial_ord_trait,
+            #(#predicates,)*
         {
-            fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
-                self.0.partial_cmp(&other.0)
+            type Err = ::branded::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                let brand = <Self as Branded>::BRAND;
+                let parse = |input: &str| -> ::core::result::Result<Self, Self::Err> {
+                    #(#parse_statements)*
+                    ::branded::path::finish(brand, input)?;
+                    ::core::result::Result::Ok((#(#variables,)*)) #construct
+                };
+                parse(input).map_err(|error| error.with_input(input))
             }
         }
-    }
-}
 
-/// Derive a Hash implementation for the branded type if the inner type conforms to Hash.
-pub(crate) fn expand_hash_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let hash_trait: syn::Path = syn::parse_quote!(::std::hash::Hash);
-    quote! {
-        impl #hash_trait for #brand_struct_name
+        impl #impl_generics #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #hash_trait,
+            #(#predicates,)*
         {
-            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
-                self.inner().hash(state);
-            }
+            #(
+                #[doc = #accessor_doc_comments]
+                pub fn #accessors(&self) -> &#element_types {
+                    &self.0.#indices
+                }
+            )*
         }
-    }
+    })
 }
 
-/// Derive a Serde implementation for the branded type if asked for.
-pub(crate) fn expand_serde_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let serialize_trait: syn::Path = syn::parse_quote!(::serde::Serialize);
-    let deserialize_trait: syn::Path = syn::parse_quote!(::serde::Deserialize);
+/// Derive Display and FromStr implementations with a check character for the branded type.
+///
+/// Display writes the inner value followed by its check character, and FromStr validates the
+/// check character before parsing the rest of the input as the inner type.
+pub(crate) fn expand_checksum_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    algorithm: ChecksumAlgorithm,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let algorithm: syn::Path = match algorithm {
+        ChecksumAlgorithm::Luhn => syn::parse_quote!(::branded::checksum::Luhn),
+        ChecksumAlgorithm::Crc => syn::parse_quote!(::branded::checksum::Crc),
+    };
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+    let construct = expand_construct(validate, quote!(|error| error.with_input(input)));
     quote! {
-        impl #serialize_trait for #brand_struct_name
+        impl #impl_generics ::core::fmt::Display for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #serialize_trait,
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: ::core::fmt::Display,
         {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: ::serde::Serializer,
-            {
-                self.inner().serialize(serializer)
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if f.alternate() {
+                    f.write_str(<Self as Branded>::BRAND)?;
+                    f.write_str(":")?;
+                }
+                ::branded::checksum::write_with_check::<#algorithm>(f, self.inner())
             }
         }
 
-        impl<'de> #deserialize_trait<'de> for #brand_struct_name
+        impl #impl_generics ::core::str::FromStr for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #deserialize_trait<'de>,
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: ::core::str::FromStr,
         {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: ::serde::Deserializer<'de>,
-            {
-                <Self as Branded>::Inner::deserialize(deserializer)
-                    .map(Self::new)
+            type Err = ::branded::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                let brand = <Self as Branded>::BRAND;
+                let body = ::branded::checksum::split_check::<#algorithm>(brand, input)
+                    .map_err(|error| error.with_input(input))?;
+                body.parse::<<Self as Branded>::Inner>()
+                    .map_err(|_| ::branded::Error::new(brand, ::branded::ErrorKind::Parse).with_input(input))
+                    #construct
             }
         }
     }
 }
 
-/// Derive a sqlx Type, Encode, and Decode implementation for the branded type if asked for.
-pub(crate) fn expand_sqlx_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let type_trait: syn::Path = syn::parse_quote!(::sqlx::Type);
-    let encode_trait: syn::Path = syn::parse_quote!(::sqlx::Encode);
-    let decode_trait: syn::Path = syn::parse_quote!(::sqlx::Decode);
-    quote! {
-        impl<DB> #type_trait<DB> for #brand_struct_name
+/// A version of the external representation of a brand.
+enum ExternalFormat {
+    /// A template, split around the `{}` standing for the inner value.
+    Template(String, String),
+    TypeId,
+}
+
+/// Derive the Display and FromStr implementations of a brand with versioned external
+/// representations.
+///
+/// Display writes the latest version, while FromStr tries every version from the latest, so
+/// clients can keep sending older versions while the external format is migrated.
+pub(crate) fn expand_external_impl(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    external: &ExternalOptions,
+    validate: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let prefix = external.prefix.as_ref().map(syn::LitStr::value);
+    if let (Some(prefix), Some(literal)) = (&prefix, &external.prefix) {
+        let valid = prefix.len() <= 63
+            && prefix.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            && !prefix.starts_with('_')
+            && !prefix.ends_with('_');
+        if !valid {
+            return Err(syn::Error::new(
+                literal.span(),
+                "a TypeID prefix must be at most 63 lowercase ASCII letters and underscores, \
+                 not starting or ending with an underscore",
+            ));
+        }
+    }
+    let mut formats = Vec::new();
+    for (_, literal) in &external.versions {
+        let value = literal.value();
+        let format = if value == "typeid" {
+            if prefix.is_none() {
+                return Err(syn::Error::new(
+                    literal.span(),
+                    "the `typeid` format requires `external(prefix = \"...\")`",
+                ));
+            }
+            ExternalFormat::TypeId
+        } else {
+            match value.split_once("{}") {
+                Some((before, after)) if !after.contains("{}") => {
+                    ExternalFormat::Template(before.to_owned(), after.to_owned())
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        literal.span(),
+                        "expected `typeid`, or a template with exactly one `{}` for the inner value",
+                    ));
+                }
+            }
+        };
+        formats.push(format);
+    }
+    let prefix = prefix.unwrap_or_default();
+    let type_id_value: syn::Path = syn::parse_quote!(::branded::external::TypeIdValue);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = where_predicates(generics);
+
+    let (write_latest, display_bound) = match &formats[0] {
+        ExternalFormat::Template(before, after) => (
+            quote!(::core::write!(f, "{}{}{}", #before, self.inner(), #after)),
+            quote!(::core::fmt::Display),
+        ),
+        ExternalFormat::TypeId => (
+            quote!(::branded::external::write_typeid(f, #prefix, #type_id_value::to_u128(self.inner()))),
+            quote!(#type_id_value),
+        ),
+    };
+    let construct = if validate {
+        quote!(Self::try_new(inner).map_err(|error| error.with_input(input)))
+    } else {
+        quote!(::core::result::Result::Ok(Self(inner)))
+    };
+    let attempts = formats.iter().map(|format| match format {
+        ExternalFormat::Template(before, after) => quote! {
+            if let ::core::option::Option::Some(body) = ::branded::external::strip_template(input, #before, #after) {
+                if let ::core::result::Result::Ok(inner) = body.parse::<<Self as Branded>::Inner>() {
+                    return #construct;
+                }
+            }
+        },
+        ExternalFormat::TypeId => quote! {
+            if let ::core::option::Option::Some(value) = ::branded::external::parse_typeid(#prefix, input) {
+                let inner = <<Self as Branded>::Inner as #type_id_value>::from_u128(value);
+                return #construct;
+            }
+        },
+    });
+    let mut from_str_bounds = Vec::new();
+    if formats.iter().any(|format| matches!(format, ExternalFormat::Template(..))) {
+        from_str_bounds.push(quote!(::core::str::FromStr));
+    }
+    if formats.iter().any(|format| matches!(format, ExternalFormat::TypeId)) {
+        from_str_bounds.push(quote!(#type_id_value));
+    }
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #brand_struct_name #ty_generics
         where
-            for<'__branded> <Self as Branded>::Inner: #type_trait<DB>,
-            DB: ::sqlx::Database,
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #display_bound,
         {
-            fn type_info() -> DB::TypeInfo {
-                <Self as Branded>::Inner::type_info()
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if f.alternate() {
+                    f.write_str(<Self as Branded>::BRAND)?;
+                    f.write_str(":")?;
+                }
+                #write_latest
             }
         }
 
-        impl<'de, DB> #decode_trait<'de, DB> for #brand_struct_name
+        impl #impl_generics ::core::str::FromStr for #brand_struct_name #ty_generics
         where
-            for<'__branded> Self: Branded,
-            <Self as Branded>::Inner: for<'a> #decode_trait<'a, DB>,
-            DB: ::sqlx::Database,
+            #(#predicates,)*
+            for<'__branded> <Self as Branded>::Inner: #(#from_str_bounds)+*,
         {
-            fn decode(value: DB::ValueRef<'_>) -> ::std::result::Result<#brand_struct_name, ::sqlx::error::BoxDynError> {
-                <Self as Branded>::Inner::decode(value).map(Self::new)
+            type Err = ::branded::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                #(#attempts)*
+                ::core::result::Result::Err(
+                    ::branded::Error::new(<Self as Branded>::BRAND, ::branded::ErrorKind::Parse).with_input(input),
+                )
             }
         }
+    })
+}
 
-        impl<'en, DB> #encode_trait<'en, DB> for #brand_struct_name
-        where
-            for<'__branded> Self: Branded,
-            <Self as Branded>::Inner: for<'a> #encode_trait<'a, DB>,
-            DB: ::sqlx::Database,
-        {
-            fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'_>) -> ::std::result::Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
-                self.inner().encode_by_ref(buf)
+/// Generate a test module with conformance tests for the branded type if asked for.
+///
+/// The tests check Display/FromStr round-trips, ordering consistency, hash/eq agreement, and serde
+/// round-trips through `serde_json` if serde is enabled. The samples are the default value if the
+/// brand implements Default, the nil UUID and two random UUIDs if uuid is enabled, and the values
+/// returned by the `samples` function, and must include at least two distinct values.
+pub(crate) fn expand_selftest(
+    brand_struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    serde: bool,
+    uuid: bool,
+    default: bool,
+    samples: Option<&syn::Path>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new(
+            brand_struct_name.span(),
+            "selftest is not supported for branded types with generic parameters",
+        ));
+    }
+    let module_name = quote::format_ident!(
+        "__branded_selftest_{}",
+        to_snake_case(&brand_struct_name.to_string())
+    );
+    let default_sample = default.then(|| quote!(values.push(::core::default::Default::default());));
+    let uuid_samples = uuid.then(|| {
+        quote! {
+            values.push(#brand_struct_name::nil());
+            values.push(#brand_struct_name::new_v4());
+            values.push(#brand_struct_name::new_v4());
+        }
+    });
+    let function_samples = samples.map(|samples| quote!(values.extend(#samples());));
+    let serde_test = serde.then(|| {
+        quote! {
+            #[test]
+            fn serde_round_trip() {
+                for sample in selftest_samples() {
+                    let json = ::serde_json::to_string(&sample).unwrap();
+                    let recovered: #brand_struct_name = ::serde_json::from_str(&json).unwrap();
+                    ::core::assert_eq!(recovered, sample, "serde round-trip changed the value");
+                }
+            }
+        }
+    });
+    // The module imports everything at the definition site of the brand, so that the samples
+    // function resolves as it would there.
+    Ok(quote! {
+        #[cfg(test)]
+        #[allow(deprecated)]
+        mod #module_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            fn selftest_samples() -> ::std::vec::Vec<#brand_struct_name> {
+                #[allow(unused_mut)]
+                let mut values = ::std::vec::Vec::new();
+                #default_sample
+                #uuid_samples
+                #function_samples
+                values
+            }
+
+            #[test]
+            fn samples_are_distinct() {
+                ::branded::selftest::check_samples(&selftest_samples());
+            }
+
+            #[test]
+            fn display_from_str_round_trip() {
+                ::branded::selftest::check_display_from_str(&selftest_samples(), #brand_struct_name);
+            }
+
+            #[test]
+            fn ordering_is_consistent() {
+                ::branded::selftest::check_ordering(&selftest_samples());
+            }
+
+            #[test]
+            fn hash_agrees_with_eq() {
+                ::branded::selftest::check_hash(&selftest_samples());
             }
+
+            #serde_test
         }
+    })
+}
+
+/// Get the Kani contracts of the constructors of validated brands with the `verify` feature, so
+/// that proofs can rely on brands only holding valid values.
+///
+/// The unchecked constructor requires its argument to pass validation, and `try_new` ensures that
+/// every value it returns does. Const and `new_into` constructors have no contract.
+fn expand_verify_contracts(
+    checks: &[proc_macro2::TokenStream],
+) -> (Option<proc_macro2::TokenStream>, Option<proc_macro2::TokenStream>) {
+    if !cfg!(feature = "verify") || checks.is_empty() {
+        return (None, None);
     }
+    let requires = quote! {
+        #[cfg_attr(kani, kani::requires({ let inner = &inner; #(#checks)&&* }))]
+    };
+    let ensures = quote! {
+        #[cfg_attr(kani, kani::ensures(|result: &::core::result::Result<Self, ::branded::Error>| {
+            match result {
+                ::core::result::Result::Ok(value) => { let inner = &value.0; #(#checks)&&* }
+                ::core::result::Result::Err(_) => true,
+            }
+        }))]
+    };
+    (Some(requires), Some(ensures))
 }
 
-pub(crate) fn expand_uuid_impl(brand_struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    quote! {
-        impl #brand_struct_name
-        where
-            for<'__branded> Self: Branded<Inner = ::uuid::Uuid>
-        {
-            /// Get the nil UUID.
-            pub fn nil() -> Self { Self::new(::uuid::Uuid::nil()) }
+/// Get the `CONSTRAINTS` constant of the Branded implementation with the `reflect` feature, if the
+/// brand declares any constraints.
+fn expand_constraints(
+    len: Option<&syn::LitStr>,
+    range: Option<&syn::LitStr>,
+    pattern: Option<&syn::LitStr>,
+    format: Option<StringFormat>,
+    prefix: Option<&syn::LitStr>,
+    custom: bool,
+) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    if !cfg!(feature = "reflect")
+        || (len.is_none()
+            && range.is_none()
+            && pattern.is_none()
+            && format.is_none()
+            && prefix.is_none()
+            && !custom)
+    {
+        return Ok(None);
+    }
+    let bound = |bound: Option<&syn::Expr>, included: bool, text: bool| match bound {
+        None => quote!(::core::ops::Bound::Unbounded),
+        Some(bound) => {
+            let bound = if text {
+                let text = quote!(#bound).to_string().replace(' ', "");
+                quote!(#text)
+            } else {
+                quote!(#bound)
+            };
+            if included {
+                quote!(::core::ops::Bound::Included(#bound))
+            } else {
+                quote!(::core::ops::Bound::Excluded(#bound))
+            }
+        }
+    };
+    let bounds = |range: Option<&syn::LitStr>, text: bool| -> syn::Result<_> {
+        let Some(range) = range else {
+            return Ok(quote!(::core::option::Option::None));
+        };
+        let range = parse_range(range)?;
+        let inclusive = matches!(range.limits, syn::RangeLimits::Closed(_));
+        let start = bound(range.start.as_deref(), true, text);
+        let end = bound(range.end.as_deref(), inclusive, text);
+        Ok(quote!(::core::option::Option::Some(::branded::constraints::Bounds::new(#start, #end))))
+    };
+    let len = bounds(len, false)?;
+    let range = bounds(range, true)?;
+    let literal = |literal: Option<&syn::LitStr>| match literal {
+        Some(literal) => quote!(::core::option::Option::Some(#literal)),
+        None => quote!(::core::option::Option::None),
+    };
+    let pattern = literal(pattern);
+    let prefix = literal(prefix);
+    let format = match format {
+        Some(StringFormat::Email) => quote!(::core::option::Option::Some("email")),
+        Some(StringFormat::Url) => quote!(::core::option::Option::Some("url")),
+        Some(StringFormat::Hostname) => quote!(::core::option::Option::Some("hostname")),
+        Some(StringFormat::Slug) => quote!(::core::option::Option::Some("slug")),
+        Some(StringFormat::UuidString) => quote!(::core::option::Option::Some("uuid_string")),
+        None => quote!(::core::option::Option::None),
+    };
+    Ok(Some(quote! {
+        const CONSTRAINTS: ::branded::constraints::Constraints =
+            ::branded::constraints::Constraints {
+                len: #len,
+                range: #range,
+                pattern: #pattern,
+                format: #format,
+                prefix: #prefix,
+                custom: #custom,
+            };
+    }))
+}
 
-            /// Get a new random UUID v4.
-            pub fn new_v4() -> Self { Self::new(::uuid::Uuid::new_v4()) }
+/// Get the checks of the inner value in the `try_new` constructor of validated brands, as boolean
+/// expressions of `inner`, a reference to the inner value.
+fn expand_validation_checks(
+    ty: &syn::Type,
+    validate: Option<&syn::Path>,
+    pattern: Option<&syn::LitStr>,
+    len: Option<&syn::LitStr>,
+    range: Option<&syn::LitStr>,
+    format: Option<StringFormat>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut checks = Vec::new();
+    if let Some(format) = format {
+        let validator: syn::Path = match format {
+            StringFormat::Email => syn::parse_quote!(::branded::format::is_email),
+            StringFormat::Url => syn::parse_quote!(::branded::format::is_url),
+            StringFormat::Hostname => syn::parse_quote!(::branded::format::is_hostname),
+            StringFormat::Slug => syn::parse_quote!(::branded::format::is_slug),
+            StringFormat::UuidString => syn::parse_quote!(::branded::format::is_uuid_string),
+        };
+        checks.push(quote!(#validator(::core::convert::AsRef::<str>::as_ref(inner))));
+    }
+    // The length of string brands is counted in characters.
+    if let Some(len) = len {
+        let len = parse_range(len)?;
+        checks.push(quote! {
+            ::core::ops::RangeBounds::<usize>::contains(
+                &(#len),
+                &::core::convert::AsRef::<str>::as_ref(inner).chars().count(),
+            )
+        });
+    }
+    if let Some(range) = range {
+        let range = parse_range(range)?;
+        checks.push(quote!(::core::ops::RangeBounds::<#ty>::contains(&(#range), inner)));
+    }
+    // The pattern is compiled at compile time with the default limits of the runtime, so patterns
+    // over the size limit are rejected too, and compiled again once at runtime, on first use.
+    if let Some(pattern) = pattern {
+        #[cfg(feature = "regex")]
+        if let Err(error) = regex::Regex::new(&pattern.value()) {
+            return Err(syn::Error::new(pattern.span(), format!("invalid pattern: {error}")));
+        }
+        #[cfg(not(feature = "regex"))]
+        return Err(syn::Error::new(
+            pattern.span(),
+            "the `pattern` option requires the `regex` feature",
+        ));
+        #[cfg(feature = "regex")]
+        checks.push(quote! {{
+            static PATTERN: ::branded::regex::Pattern = ::branded::regex::Pattern::new(#pattern);
+            PATTERN.is_match(::core::convert::AsRef::<str>::as_ref(inner))
+        }});
+    }
+    if let Some(validate) = validate {
+        checks.push(quote!(#validate(inner)));
+    }
+    Ok(checks)
+}
+
+/// Get the const expression parsing the string `literal` as the inner type, for the inner types
+/// supported by the `brand!` macro.
+fn expand_literal_parser(ty: &syn::Type) -> Option<proc_macro2::TokenStream> {
+    let ident = match ty {
+        syn::Type::Reference(reference) if reference.mutability.is_none() => {
+            return matches!(&*reference.elem, syn::Type::Path(path) if path.path.is_ident("str"))
+                .then(|| quote!(literal));
+        }
+        syn::Type::Path(path) if path.qself.is_none() => &path.path.segments.last()?.ident,
+        _ => return None,
+    };
+    match ident.to_string().as_str() {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => Some(quote! {{
+            let value = ::branded::__private::parse_unsigned_literal(literal);
+            ::core::assert!(value <= <#ty>::MAX as u128, "integer literal out of range");
+            value as #ty
+        }}),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => Some(quote! {{
+            let value = ::branded::__private::parse_signed_literal(literal);
+            ::core::assert!(
+                value >= <#ty>::MIN as i128 && value <= <#ty>::MAX as i128,
+                "integer literal out of range"
+            );
+            value as #ty
+        }}),
+        "Uuid" => Some(quote! {
+            match ::uuid::Uuid::try_parse(literal) {
+                ::core::result::Result::Ok(uuid) => uuid,
+                ::core::result::Result::Err(_) => ::core::panic!("invalid UUID literal"),
+            }
+        }),
+        _ => None,
+    }
+}
+
+/// Parse a range expression, such as `1..=64` or `1..`, from a string literal.
+fn parse_range(range: &syn::LitStr) -> syn::Result<syn::ExprRange> {
+    range.parse().map_err(|_| syn::Error::new(range.span(), "expected a range, such as `1..=64`"))
+}
+
+/// Construct the brand from a result of its inner value.
+///
+/// Validated brands check the inner value with `try_new`, and convert its error with `map_err`.
+fn expand_construct(validate: bool, map_err: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if validate {
+        quote!(.and_then(|inner| Self::try_new(inner).map_err(#map_err)))
+    } else {
+        quote!(.map(Self))
+    }
+}
+
+/// Convert an identifier in UpperCamelCase to snake_case.
+fn to_snake_case(ident: &str) -> String {
+    let mut snake_case = String::with_capacity(ident.len() + 4);
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        } else {
+            snake_case.push(c);
         }
     }
+    snake_case
+}
+
+/// Check whether the type is spelled as the standard library `String`.
+fn is_string_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(syn::TypePath {
+        qself: None,
+        path,
+    }) = ty
+    else {
+        return false;
+    };
+    let segments =
+        path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    path.segments.iter().all(|segment| segment.arguments.is_none())
+        && matches!(
+            segments.iter().map(String::as_str).collect::<Vec<_>>()[..],
+            ["String"] | ["std" | "alloc", "string", "String"]
+        )
+}
+
+/// Get the standard traits that the inner type does not implement, if it is spelled as a primitive
+/// type, `String`, `&str` or `uuid::Uuid`, or is a UUID because the `uuid` option is given.
+fn missing_inner_traits(ty: &syn::Type, uuid: bool) -> Option<&'static [&'static str]> {
+    let is_float = matches!(ty, syn::Type::Path(path) if path.path.get_ident().is_some_and(|ident| ident == "f32" || ident == "f64"));
+    if is_float {
+        Some(&["Eq", "Ord", "Hash"])
+    } else if is_primitive_type(ty) || uuid || is_uuid_type(ty) {
+        Some(&[])
+    } else if is_string_type(ty) {
+        Some(&["Copy"])
+    } else if is_str_ref_type(ty) {
+        Some(&["FromStr"])
+    } else {
+        None
+    }
+}
+
+/// Check whether the type is spelled as `uuid::Uuid`. A bare `Uuid` is only known to be one with
+/// the `uuid` option, since it may be another type of that name.
+fn is_uuid_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { qself: None, path })
+        if path.segments.len() == 2
+            && path.segments[0].ident == "uuid"
+            && path.segments[1].ident == "Uuid"
+            && path.segments.iter().all(|segment| segment.arguments.is_none()))
+}
+
+/// Get the lifetime of a `Cow<'a, str>` type that may borrow, unless it is `'static`.
+fn cow_str_lifetime(ty: &syn::Type) -> Option<&syn::Lifetime> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last().filter(|segment| segment.ident == "Cow")?;
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.iter().collect::<Vec<_>>()[..] {
+        [syn::GenericArgument::Lifetime(lifetime), syn::GenericArgument::Type(syn::Type::Path(str))]
+            if str.path.is_ident("str") && lifetime.ident != "static" =>
+        {
+            Some(lifetime)
+        }
+        _ => None,
+    }
+}
+
+/// Check whether the attributes include `#[repr(transparent)]`.
+fn is_repr_transparent(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().filter(|attr| attr.path().is_ident("repr")).any(|attr| {
+        let mut transparent = false;
+        let _ = attr.parse_nested_meta(|meta| {
+            transparent |= meta.path.is_ident("transparent");
+            Ok(())
+        });
+        transparent
+    })
+}
+
+/// Check whether the type is spelled as one of the primitive integer, float, `bool` or `char`
+/// types.
+fn is_primitive_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(syn::TypePath {
+        qself: None,
+        path,
+    }) = ty
+    else {
+        return false;
+    };
+    is_integer_type(ty)
+        || path.get_ident().is_some_and(|ident| {
+            matches!(ident.to_string().as_str(), "f32" | "f64" | "bool" | "char")
+        })
+}
+
+/// Check whether the type is spelled as one of the primitive integer types.
+fn is_integer_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(syn::TypePath {
+        qself: None,
+        path,
+    }) = ty
+    else {
+        return false;
+    };
+    path.get_ident().is_some_and(|ident| {
+        matches!(
+            ident.to_string().as_str(),
+            "u8" | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+        )
+    })
+}
+
+/// Check whether the type is spelled as a `&str` reference.
+fn is_str_ref_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Reference(reference)
+        if matches!(&*reference.elem, syn::Type::Path(path) if path.path.is_ident("str")))
+}
+
+/// Check whether the type mentions any of the type parameters of the branded type.
+fn is_generic_type(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    fn mentions(tokens: proc_macro2::TokenStream, params: &[&syn::Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => params.contains(&&ident),
+            proc_macro2::TokenTree::Group(group) => mentions(group.stream(), params),
+            _ => false,
+        })
+    }
+    let params = generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    mentions(quote!(#ty), &params)
+}
+
+/// Get the where-clause predicates declared on the branded type, so that they can be merged into
+/// the where-clause of every generated impl.
+fn where_predicates(generics: &syn::Generics) -> Vec<&syn::WherePredicate> {
+    generics.where_clause.iter().flat_map(|clause| clause.predicates.iter()).collect()
 }
@@ -0,0 +1,142 @@
+//! axum integration for branded types.
+//!
+//! The [`IdPath`] extractor parses a single path parameter into a brand with its `FromStr`
+//! implementation. Unlike `Path<Brand>`, a value that fails to parse is rejected with a
+//! `400 Bad Request` naming the brand and the reason, instead of an opaque error.
+//!
+//! ```ignore
+//! use axum::routing::get;
+//! use axum::Router;
+//! use branded::axum::IdPath;
+//!
+//! async fn get_user(IdPath(user_id): IdPath<UserId>) -> String {
+//!     format!("user {user_id}")
+//! }
+//!
+//! let app = Router::new().route("/users/{user_id}", get(get_user));
+//! ```
+//!
+//! With the `aide` feature, `IdPath<B>` documents itself as a path parameter with the schema of
+//! the brand, named after the brand in snake case, e.g. `user_id` for `UserId`. Derive the schema
+//! with the `aide` option on the `Branded` derive macro.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use axum::extract::rejection::PathRejection;
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::Branded;
+
+/// An extractor parsing a single path parameter into the brand `B`.
+#[derive(Debug, Clone, Copy)]
+pub struct IdPath<B>(pub B);
+
+impl<B, S> FromRequestParts<S> for IdPath<B>
+where
+    B: Branded + FromStr + Send,
+    B::Err: fmt::Display,
+    S: Send + Sync,
+{
+    type Rejection = IdPathRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(value) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(IdPathRejection::Path)?;
+        value.parse().map(IdPath).map_err(|err: B::Err| IdPathRejection::Invalid {
+            brand: B::BRAND,
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// The rejection returned by the [`IdPath`] extractor.
+#[derive(Debug)]
+pub enum IdPathRejection {
+    /// The path parameter could not be extracted.
+    Path(PathRejection),
+    /// The path parameter is not a valid value of the brand.
+    Invalid {
+        /// The name of the brand.
+        brand: &'static str,
+        /// The reason the value failed to parse.
+        reason: String,
+    },
+}
+
+impl fmt::Display for IdPathRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(rejection) => fmt::Display::fmt(rejection, f),
+            Self::Invalid {
+                brand,
+                reason,
+            } => write!(f, "invalid {brand} in path: {reason}"),
+        }
+    }
+}
+
+impl core::error::Error for IdPathRejection {}
+
+impl IntoResponse for IdPathRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Path(rejection) => rejection.into_response(),
+            invalid @ Self::Invalid {
+                ..
+            } => (StatusCode::BAD_REQUEST, format!("{invalid}")).into_response(),
+        }
+    }
+}
+
+#[cfg(feature = "aide")]
+impl<B> aide::OperationInput for IdPath<B>
+where
+    B: Branded + schemars::JsonSchema,
+{
+    fn operation_input(
+        ctx: &mut aide::generate::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) {
+        use aide::openapi::{Parameter, ParameterData, ParameterSchemaOrContent, PathStyle};
+
+        let schema = ctx.schema.subschema_for::<B>().into_object();
+        let parameter = Parameter::Path {
+            parameter_data: ParameterData {
+                name: snake_case(B::BRAND),
+                description: None,
+                required: true,
+                format: ParameterSchemaOrContent::Schema(aide::openapi::SchemaObject {
+                    json_schema: schema.into(),
+                    example: None,
+                    external_docs: None,
+                }),
+                extensions: Default::default(),
+                deprecated: None,
+                example: None,
+                examples: Default::default(),
+                explode: None,
+            },
+            style: PathStyle::Simple,
+        };
+        aide::operation::add_parameters(ctx, operation, [parameter]);
+    }
+}
+
+/// Convert a brand name in UpperCamelCase to snake_case.
+#[cfg(feature = "aide")]
+fn snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake_case.push('_');
+        }
+        snake_case.extend(c.to_lowercase());
+    }
+    snake_case
+}
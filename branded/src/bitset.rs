@@ -0,0 +1,272 @@
+//! Compact sets of dense integer brands.
+//!
+//! The `bitset` option on the `Branded` derive macro implements [`DenseId`] for brands whose inner
+//! type is an unsigned integer, so they can be stored in a [`BrandedBitSet`]. The set stores one
+//! bit per possible ID up to the largest one, which takes far less memory than a `HashSet` when the
+//! IDs are dense. With the `roaring` feature, the set is backed by a compressed roaring bitmap
+//! instead, which also suits sparse IDs.
+//!
+//! ```
+//! use branded::bitset::BrandedBitSet;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(bitset)]
+//! pub struct OrderId(u32);
+//!
+//! let mut orders = BrandedBitSet::new();
+//! orders.insert(OrderId::new(3));
+//! orders.insert(OrderId::new(5));
+//! assert!(orders.contains(&OrderId::new(3)));
+//! assert_eq!(orders.iter().collect::<Vec<_>>(), [OrderId::new(3), OrderId::new(5)]);
+//! ```
+//!
+//! Iterating a set constructs brands from their indices, so validated brands cannot use `bitset`:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(bitset, range = "1..")]
+//! pub struct OrderId(u32);
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::Branded;
+
+/// Inner types of brands that can be stored in a [`BrandedBitSet`].
+pub trait DenseInner: Copy {
+    /// Convert the value into its index in the set.
+    fn to_index(self) -> u64;
+
+    /// Convert an index in the set back into the value.
+    fn from_index(index: u64) -> Self;
+}
+
+macro_rules! impl_dense_inner {
+    ($($ty:ty),*) => {
+        $(
+            impl DenseInner for $ty {
+                fn to_index(self) -> u64 { self as u64 }
+
+                // Indices in a set are only ever created from values of the same type.
+                fn from_index(index: u64) -> Self { index as $ty }
+            }
+        )*
+    };
+}
+
+impl_dense_inner!(u8, u16, u32, u64, usize);
+
+/// Brands that can be stored in a [`BrandedBitSet`].
+///
+/// This trait is implemented by the `Branded` derive macro with the `bitset` option.
+pub trait DenseId: Branded {
+    /// Convert the ID into its index in the set.
+    fn to_index(&self) -> u64;
+
+    /// Convert an index in the set back into the ID.
+    fn from_index(index: u64) -> Self;
+}
+
+/// A set of IDs of the brand `B`, stored as a bitmap of their indices.
+pub struct BrandedBitSet<B> {
+    bits: Bits,
+    marker: PhantomData<fn() -> B>,
+}
+
+impl<B: DenseId> BrandedBitSet<B> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self {
+            bits: Bits::default(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Add an ID to the set, returning whether it was newly inserted.
+    pub fn insert(&mut self, id: B) -> bool { self.bits.insert(id.to_index()) }
+
+    /// Remove an ID from the set, returning whether it was present.
+    pub fn remove(&mut self, id: &B) -> bool { self.bits.remove(id.to_index()) }
+
+    /// Check whether the set contains an ID.
+    pub fn contains(&self, id: &B) -> bool { self.bits.contains(id.to_index()) }
+
+    /// Get the number of IDs in the set.
+    pub fn len(&self) -> usize { self.bits.len() }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Remove all IDs from the set.
+    pub fn clear(&mut self) { self.bits.clear() }
+
+    /// Iterate over the IDs in the set in ascending order of their indices.
+    pub fn iter(&self) -> impl Iterator<Item = B> + '_ { self.bits.iter().map(B::from_index) }
+
+    /// Add all IDs of `other` to the set.
+    pub fn union_with(&mut self, other: &Self) { self.bits.union_with(&other.bits) }
+
+    /// Remove all IDs that are not in `other` from the set.
+    pub fn intersect_with(&mut self, other: &Self) { self.bits.intersect_with(&other.bits) }
+
+    /// Get the set of IDs in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.union_with(other);
+        union
+    }
+
+    /// Get the set of IDs in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = self.clone();
+        intersection.intersect_with(other);
+        intersection
+    }
+}
+
+impl<B> Clone for BrandedBitSet<B> {
+    fn clone(&self) -> Self {
+        Self {
+            bits: self.bits.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B: DenseId> Default for BrandedBitSet<B> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<B> PartialEq for BrandedBitSet<B> {
+    fn eq(&self, other: &Self) -> bool { self.bits == other.bits }
+}
+
+impl<B> Eq for BrandedBitSet<B> {}
+
+impl<B: DenseId + fmt::Debug> fmt::Debug for BrandedBitSet<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<B: DenseId> Extend<B> for BrandedBitSet<B> {
+    fn extend<I: IntoIterator<Item = B>>(&mut self, ids: I) {
+        ids.into_iter().for_each(|id| {
+            self.insert(id);
+        });
+    }
+}
+
+impl<B: DenseId> FromIterator<B> for BrandedBitSet<B> {
+    fn from_iter<I: IntoIterator<Item = B>>(ids: I) -> Self {
+        let mut set = Self::new();
+        set.extend(ids);
+        set
+    }
+}
+
+/// A bitmap of indices backed by a vector of words.
+#[cfg(not(feature = "roaring"))]
+#[derive(Default, Clone, PartialEq, Eq)]
+struct Bits {
+    // Trailing zero words are trimmed, so equal sets have equal words.
+    words: alloc::vec::Vec<u64>,
+}
+
+#[cfg(not(feature = "roaring"))]
+impl Bits {
+    fn position(index: u64) -> (usize, u64) {
+        let word = usize::try_from(index / 64).expect("ID index exceeds the address space");
+        (word, 1 << (index % 64))
+    }
+
+    fn trim(&mut self) {
+        let len = self.words.iter().rposition(|&word| word != 0).map_or(0, |i| i + 1);
+        self.words.truncate(len);
+    }
+
+    fn insert(&mut self, index: u64) -> bool {
+        let (word, mask) = Self::position(index);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    fn remove(&mut self, index: u64) -> bool {
+        let (word, mask) = Self::position(index);
+        let removed = self.words.get(word).is_some_and(|&bits| bits & mask != 0);
+        if removed {
+            self.words[word] &= !mask;
+            self.trim();
+        }
+        removed
+    }
+
+    fn contains(&self, index: u64) -> bool {
+        let (word, mask) = Self::position(index);
+        self.words.get(word).is_some_and(|&bits| bits & mask != 0)
+    }
+
+    fn len(&self) -> usize { self.words.iter().map(|word| word.count_ones() as usize).sum() }
+
+    fn clear(&mut self) { self.words.clear() }
+
+    fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            core::iter::from_fn(move || {
+                let bit = (word != 0).then(|| u64::from(word.trailing_zeros()))?;
+                word &= word - 1;
+                Some(i as u64 * 64 + bit)
+            })
+        })
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word |= other);
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word &= other);
+        self.trim();
+    }
+}
+
+/// A bitmap of indices backed by a roaring bitmap.
+#[cfg(feature = "roaring")]
+#[derive(Default, Clone, PartialEq)]
+struct Bits {
+    bitmap: roaring::RoaringTreemap,
+}
+
+#[cfg(feature = "roaring")]
+impl Bits {
+    fn insert(&mut self, index: u64) -> bool { self.bitmap.insert(index) }
+
+    fn remove(&mut self, index: u64) -> bool { self.bitmap.remove(index) }
+
+    fn contains(&self, index: u64) -> bool { self.bitmap.contains(index) }
+
+    fn len(&self) -> usize {
+        usize::try_from(self.bitmap.len()).expect("set length exceeds the address space")
+    }
+
+    fn clear(&mut self) { self.bitmap.clear() }
+
+    fn iter(&self) -> impl Iterator<Item = u64> + '_ { self.bitmap.iter() }
+
+    fn union_with(&mut self, other: &Self) { self.bitmap |= &other.bitmap }
+
+    fn intersect_with(&mut self, other: &Self) { self.bitmap &= &other.bitmap }
+}
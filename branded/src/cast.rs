@@ -0,0 +1,115 @@
+//! Zero-cost conversions between collections of brands and collections of their inner values.
+//!
+//! Binding an array of IDs to a query, or handing it across an FFI boundary, usually needs the
+//! inner values, and mapping each element would copy the collection. Brands that are
+//! `#[repr(transparent)]` can pass the `cast` option to implement [`TransparentBrand`], whose
+//! methods reinterpret slices and vectors of brands, including nullable ones, in place:
+//!
+//! ```
+//! use branded::cast::TransparentBrand;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(cast)]
+//! #[repr(transparent)]
+//! pub struct UserId(u64);
+//!
+//! let ids = [Some(UserId::new(1)), None];
+//! assert_eq!(UserId::as_inner_option_slice(&ids), &[Some(1), None]);
+//! ```
+//!
+//! Reinterpreting inner values skips validation, so validated brands cannot use `cast`:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(cast, range = "1..")]
+//! #[repr(transparent)]
+//! pub struct UserId(u64);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::mem::ManuallyDrop;
+
+use crate::Branded;
+
+/// A brand with the same layout as its inner type, so that collections of either can be
+/// reinterpreted as collections of the other.
+///
+/// # Safety
+///
+/// The brand must be `#[repr(transparent)]` over `Self::Inner`. Implement this trait with the
+/// `cast` option, which checks the representation.
+pub unsafe trait TransparentBrand: Branded + Sized {
+    /// View a slice of inner values as a slice of brands.
+    fn from_inner_slice(inner: &[Self::Inner]) -> &[Self] {
+        // SAFETY: `Self` has the same layout as `Self::Inner`.
+        unsafe { &*(inner as *const [Self::Inner] as *const [Self]) }
+    }
+
+    /// View a slice of brands as a slice of inner values.
+    fn as_inner_slice(brands: &[Self]) -> &[Self::Inner] {
+        // SAFETY: `Self` has the same layout as `Self::Inner`.
+        unsafe { &*(brands as *const [Self] as *const [Self::Inner]) }
+    }
+
+    /// View a slice of optional inner values as a slice of optional brands.
+    fn from_inner_option_slice(inner: &[Option<Self::Inner>]) -> &[Option<Self>] {
+        // SAFETY: `Self` has the same layout and niches as `Self::Inner`, so `Option<Self>` has
+        // the same layout as `Option<Self::Inner>`.
+        unsafe { &*(inner as *const [Option<Self::Inner>] as *const [Option<Self>]) }
+    }
+
+    /// View a slice of optional brands as a slice of optional inner values.
+    fn as_inner_option_slice(brands: &[Option<Self>]) -> &[Option<Self::Inner>] {
+        // SAFETY: `Self` has the same layout and niches as `Self::Inner`, so `Option<Self>` has
+        // the same layout as `Option<Self::Inner>`.
+        unsafe { &*(brands as *const [Option<Self>] as *const [Option<Self::Inner>]) }
+    }
+
+    /// Convert a vector of inner values into a vector of brands, without reallocating.
+    #[cfg(feature = "alloc")]
+    fn from_inner_vec(inner: Vec<Self::Inner>) -> Vec<Self> {
+        // SAFETY: `Self` has the same layout as `Self::Inner`.
+        unsafe { cast_vec(inner) }
+    }
+
+    /// Convert a vector of brands into a vector of inner values, without reallocating.
+    #[cfg(feature = "alloc")]
+    fn into_inner_vec(brands: Vec<Self>) -> Vec<Self::Inner> {
+        // SAFETY: `Self` has the same layout as `Self::Inner`.
+        unsafe { cast_vec(brands) }
+    }
+
+    /// Convert a vector of optional inner values into a vector of optional brands, without
+    /// reallocating.
+    #[cfg(feature = "alloc")]
+    fn from_inner_option_vec(inner: Vec<Option<Self::Inner>>) -> Vec<Option<Self>> {
+        // SAFETY: `Option<Self>` has the same layout as `Option<Self::Inner>`.
+        unsafe { cast_vec(inner) }
+    }
+
+    /// Convert a vector of optional brands into a vector of optional inner values, without
+    /// reallocating.
+    #[cfg(feature = "alloc")]
+    fn into_inner_option_vec(brands: Vec<Option<Self>>) -> Vec<Option<Self::Inner>> {
+        // SAFETY: `Option<Self>` has the same layout as `Option<Self::Inner>`.
+        unsafe { cast_vec(brands) }
+    }
+}
+
+/// Reinterpret the allocation of a vector as a vector of another element type.
+///
+/// # Safety
+///
+/// `T` and `U` must have the same layout.
+#[cfg(feature = "alloc")]
+unsafe fn cast_vec<T, U>(vec: Vec<T>) -> Vec<U> {
+    let mut vec = ManuallyDrop::new(vec);
+    // SAFETY: The caller guarantees that `T` and `U` have the same size and alignment, so the
+    // allocation, length, and capacity are valid for `U`.
+    unsafe { Vec::from_raw_parts(vec.as_mut_ptr().cast::<U>(), vec.len(), vec.capacity()) }
+}
@@ -0,0 +1,134 @@
+//! Support for brands with a check character appended to their string form.
+//!
+//! The `checksum` option on the `Branded` derive macro generates `Display` and `FromStr`
+//! implementations that append and validate a check character computed with one of the
+//! algorithms in this module, so typos in hand-entered IDs are detected when parsing.
+
+use core::fmt;
+
+use crate::{Error, ErrorKind};
+
+/// An algorithm computing a check character over the characters of a string.
+pub trait Algorithm: Default {
+    /// Feed the next character of the string into the checksum.
+    fn update(&mut self, c: char);
+
+    /// Get the check character for the characters fed so far.
+    fn check_character(&self) -> char;
+
+    /// Check whether `c` matches the check character for the characters fed so far.
+    fn matches(&self, c: char) -> bool { self.check_character() == c }
+}
+
+/// The Luhn mod 10 algorithm, computed over the decimal digits of the string.
+///
+/// Characters other than decimal digits are ignored, and the check character is a decimal digit.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Luhn {
+    // The sums of the digits when doubling the digits at even and odd positions respectively,
+    // since which digits are doubled depends on the total number of digits.
+    sums: [u32; 2],
+    count: usize,
+}
+
+impl Algorithm for Luhn {
+    fn update(&mut self, c: char) {
+        let Some(digit) = c.to_digit(10) else {
+            return;
+        };
+        let doubled = if digit * 2 > 9 {
+            digit * 2 - 9
+        } else {
+            digit * 2
+        };
+        let parity = self.count % 2;
+        self.sums[parity] += doubled;
+        self.sums[1 - parity] += digit;
+        self.count += 1;
+    }
+
+    fn check_character(&self) -> char {
+        // The rightmost digit before the check digit is doubled.
+        let sum = match self.count {
+            0 => 0,
+            count => self.sums[(count - 1) % 2],
+        };
+        char::from_digit((10 - sum % 10) % 10, 10).unwrap_or('0')
+    }
+}
+
+/// A CRC-5 over the UTF-8 bytes of the string, rendered as a Crockford base32 character.
+///
+/// The check character is written in uppercase and matched case-insensitively.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Crc {
+    crc: u8,
+}
+
+impl Crc {
+    /// The CRC-5/ITU polynomial, x^5 + x^4 + x^2 + 1.
+    const POLYNOMIAL: u8 = 0x15;
+    const ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+}
+
+impl Algorithm for Crc {
+    fn update(&mut self, c: char) {
+        let mut buffer = [0; 4];
+        for byte in c.encode_utf8(&mut buffer).bytes() {
+            for bit in (0..8).rev() {
+                let feedback = ((byte >> bit) & 1) ^ (self.crc >> 4);
+                self.crc = (self.crc << 1) & 0x1f;
+                if feedback == 1 {
+                    self.crc ^= Self::POLYNOMIAL;
+                }
+            }
+        }
+    }
+
+    fn check_character(&self) -> char { Self::ALPHABET[usize::from(self.crc)] as char }
+
+    fn matches(&self, c: char) -> bool { self.check_character() == c.to_ascii_uppercase() }
+}
+
+/// A writer that passes through to a formatter while feeding the checksum.
+struct ChecksumWriter<'a, 'b, A> {
+    f: &'a mut fmt::Formatter<'b>,
+    algorithm: A,
+}
+
+impl<A: Algorithm> fmt::Write for ChecksumWriter<'_, '_, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().for_each(|c| self.algorithm.update(c));
+        self.f.write_str(s)
+    }
+}
+
+/// Write `value` followed by its check character to `f`.
+pub fn write_with_check<A: Algorithm>(
+    f: &mut fmt::Formatter<'_>,
+    value: &impl fmt::Display,
+) -> fmt::Result {
+    let mut writer = ChecksumWriter {
+        f,
+        algorithm: A::default(),
+    };
+    fmt::Write::write_fmt(&mut writer, format_args!("{value}"))?;
+    let check = writer.algorithm.check_character();
+    fmt::Write::write_char(writer.f, check)
+}
+
+/// Validate the check character at the end of `input`, returning the rest of the input.
+pub fn split_check<'a, A: Algorithm>(
+    brand: &'static str,
+    input: &'a str,
+) -> Result<&'a str, Error> {
+    let check = input.chars().next_back().ok_or(Error::new(brand, ErrorKind::Length))?;
+    let body = &input[..input.len() - check.len_utf8()];
+    let mut algorithm = A::default();
+    body.chars().for_each(|c| algorithm.update(c));
+    if algorithm.matches(check) {
+        Ok(body)
+    } else {
+        Err(Error::new(brand, ErrorKind::Checksum))
+    }
+}
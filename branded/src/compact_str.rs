@@ -0,0 +1,18 @@
+//! compact_str integration for branded types.
+//!
+//! The `compact_str` option on the `Branded` derive macro generates a `to_compact_string` method
+//! that formats the branded value into a `CompactString`, which stores short IDs inline without a
+//! heap allocation.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(compact_str)]
+//! pub struct UserId(u64);
+//!
+//! assert_eq!(UserId::new(42).to_compact_string(), "42");
+//! ```
+
+#[doc(hidden)]
+pub use compact_str::CompactString;
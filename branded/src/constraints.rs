@@ -0,0 +1,89 @@
+//! Runtime metadata of the constraints declared on brands, with the `reflect` feature.
+//!
+//! The derive macro records the `len`, `range`, `pattern`, `format`, and `serde(prefix)` options of
+//! a brand in [`Branded::CONSTRAINTS`](crate::Branded::CONSTRAINTS), so that API gateways and
+//! documentation generators can derive client-side validation and error descriptions from the
+//! same declarations that the brand is validated with.
+//!
+//! ```
+//! use branded::constraints::Bounds;
+//! use branded::Branded;
+//! use core::ops::Bound;
+//!
+//! #[derive(Branded)]
+//! #[branded(len = "3..=32", format = "slug")]
+//! pub struct Username(String);
+//!
+//! let constraints = Username::CONSTRAINTS;
+//! assert_eq!(constraints.len, Some(Bounds::new(Bound::Included(3), Bound::Included(32))));
+//! assert_eq!(constraints.format, Some("slug"));
+//! assert!(!constraints.custom);
+//! ```
+
+use core::ops::Bound;
+
+/// The constraints declared on a brand.
+///
+/// Constraints that are not declared are `None`. A brand with a `validate` function has further
+/// checks that cannot be described, which is indicated by `custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraints {
+    /// The bounds of the length of string brands in characters, from the `len` option.
+    pub len: Option<Bounds<usize>>,
+    /// The bounds of the value, from the `range` option, as the source of the bound expressions,
+    /// such as `"1"` or `"-10"`.
+    pub range: Option<Bounds<&'static str>>,
+    /// The regular expression string brands must match, from the `pattern` option.
+    pub pattern: Option<&'static str>,
+    /// The name of the string format, from the `format` option, such as `"email"`.
+    pub format: Option<&'static str>,
+    /// The prefix of the serialized form, from the `serde(prefix)` option.
+    pub prefix: Option<&'static str>,
+    /// Whether the brand is also checked with a `validate` function.
+    pub custom: bool,
+}
+
+impl Constraints {
+    /// The constraints of a brand without any declared constraints.
+    pub const NONE: Self = Self {
+        len: None,
+        range: None,
+        pattern: None,
+        format: None,
+        prefix: None,
+        custom: false,
+    };
+
+    /// Check whether no constraints are declared.
+    pub const fn is_empty(&self) -> bool {
+        self.len.is_none()
+            && self.range.is_none()
+            && self.pattern.is_none()
+            && self.format.is_none()
+            && self.prefix.is_none()
+            && !self.custom
+    }
+}
+
+impl Default for Constraints {
+    fn default() -> Self { Self::NONE }
+}
+
+/// The bounds of a range constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds<T> {
+    /// The lower bound.
+    pub start: Bound<T>,
+    /// The upper bound.
+    pub end: Bound<T>,
+}
+
+impl<T> Bounds<T> {
+    /// Create bounds from `start` to `end`.
+    pub const fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        Self {
+            start,
+            end,
+        }
+    }
+}
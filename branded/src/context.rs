@@ -0,0 +1,51 @@
+//! Error context naming the branded value that caused an error.
+//!
+//! The `context` option on the `Branded` derive macro generates a `context` method returning a
+//! [`BrandContext`], so errors can record which ID caused them uniformly across all brands.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(context)]
+//! pub struct UserId(u64);
+//!
+//! let context = UserId::new(42).context();
+//! assert_eq!(context.brand(), "UserId");
+//! assert_eq!(context.value(), "42");
+//! assert_eq!(context.to_string(), "UserId:42");
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+/// The name of a brand and the `Display` form of one of its values.
+///
+/// `Display` renders the context like the alternate form of the brand, as `UserId:42`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BrandContext {
+    brand: &'static str,
+    value: String,
+}
+
+impl BrandContext {
+    /// Create a new context for a value of the brand named `brand`.
+    pub fn new(brand: &'static str, value: String) -> Self {
+        Self {
+            brand,
+            value,
+        }
+    }
+
+    /// Get the name of the brand.
+    pub fn brand(&self) -> &'static str { self.brand }
+
+    /// Get the `Display` form of the value.
+    pub fn value(&self) -> &str { &self.value }
+}
+
+impl fmt::Display for BrandContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.brand, self.value)
+    }
+}
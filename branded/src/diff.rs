@@ -0,0 +1,95 @@
+//! Brand-aware differences between collections of branded values, for test failure messages.
+//!
+//! Comparing two lists of IDs with `assert_eq!` reports the raw tuples of both lists in full.
+//! [`Diff`] reports only the positions that differ, with the brand name and the short form of each
+//! value, such as `[1] UserId: a1b2c3d4… != e5f6a7b8…`, and [`assert_brands_eq!`] panics with it.
+//!
+//! ```
+//! use branded::diff::Diff;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(&'static str);
+//!
+//! let left = [UserId::new("a1b2c3d4e5"), UserId::new("f6a7")];
+//! let right = [UserId::new("a1b2c3d4e5"), UserId::new("b8c9"), UserId::new("d0e1")];
+//! let diff = Diff::new(&left, &right);
+//! assert_eq!(diff.to_string(), "[1] UserId: f6a7 != b8c9\n[2] UserId: (missing) != d0e1");
+//! ```
+//!
+//! [`assert_brands_eq!`]: crate::assert_brands_eq
+
+use core::fmt::{self, Display};
+
+use crate::fmt::write_truncated;
+use crate::Branded;
+
+/// The differences between two slices of brands, compared position by position.
+///
+/// Both `Display` and `Debug` render one line per differing position. By default, each value is
+/// truncated to 8 characters, so values that only differ after that look the same; use
+/// [`width`](Self::width) to show more.
+pub struct Diff<'a, B> {
+    left: &'a [B],
+    right: &'a [B],
+    width: usize,
+}
+
+impl<'a, B> Diff<'a, B> {
+    /// Compare the brands in `left` and `right`.
+    pub fn new(left: &'a [B], right: &'a [B]) -> Self {
+        Self {
+            left,
+            right,
+            width: 8,
+        }
+    }
+
+    /// Truncate each value to at most `width` characters.
+    pub fn width(self, width: usize) -> Self {
+        Self {
+            width,
+            ..self
+        }
+    }
+}
+
+impl<B: PartialEq> Diff<'_, B> {
+    /// Check whether the slices are equal.
+    pub fn is_empty(&self) -> bool { self.left == self.right }
+}
+
+impl<B: Branded + PartialEq + Display> Display for Diff<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.left.len().max(self.right.len());
+        let mut first = true;
+        for i in 0..len {
+            let (left, right) = (self.left.get(i), self.right.get(i));
+            if left == right {
+                continue;
+            }
+            if !first {
+                f.write_str("\n")?;
+            }
+            first = false;
+            write!(f, "[{i}] {}: ", B::BRAND)?;
+            self.write_value(f, left)?;
+            f.write_str(" != ")?;
+            self.write_value(f, right)?;
+        }
+        Ok(())
+    }
+}
+
+impl<B: Branded + PartialEq + Display> fmt::Debug for Diff<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Display::fmt(self, f) }
+}
+
+impl<B: Display> Diff<'_, B> {
+    fn write_value(&self, f: &mut fmt::Formatter<'_>, value: Option<&B>) -> fmt::Result {
+        match value {
+            Some(value) => write_truncated(f, value, self.width),
+            None => f.write_str("(missing)"),
+        }
+    }
+}
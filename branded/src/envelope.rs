@@ -0,0 +1,205 @@
+//! An event envelope keyed by a branded aggregate ID, for outboxes and event-sourced services.
+//!
+//! [`Envelope`] carries the ID of the aggregate an event belongs to, the sequence number of the
+//! event within the aggregate, and the event itself, so that services share one envelope type
+//! instead of declaring their own, and an event of one aggregate cannot be filed under the ID of
+//! another kind of aggregate.
+//!
+//! ```
+//! use branded::envelope::Envelope;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct OrderId(u64);
+//!
+//! pub enum OrderEvent {
+//!     Placed,
+//!     Shipped,
+//! }
+//!
+//! let placed = Envelope::new(OrderId::new(7), 1, OrderEvent::Placed);
+//! let shipped = placed.next(OrderEvent::Shipped);
+//! assert_eq!(shipped.aggregate_id, OrderId::new(7));
+//! assert_eq!(shipped.sequence, 2);
+//! ```
+//!
+//! With the `serde` feature, envelopes serialize as a struct with the `aggregate_id`, `sequence`,
+//! and `payload` fields, so the brand must be serializable, such as with the `serde` option.
+
+use crate::Branded;
+
+/// An event of the aggregate identified by `aggregate_id`, numbered by `sequence`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope<Id: Branded, T> {
+    /// The ID of the aggregate the event belongs to.
+    pub aggregate_id: Id,
+    /// The position of the event among the events of the aggregate.
+    pub sequence: u64,
+    /// The event.
+    pub payload: T,
+}
+
+impl<Id: Branded, T> Envelope<Id, T> {
+    /// Create an envelope of `payload`, the event numbered `sequence` of the aggregate
+    /// `aggregate_id`.
+    pub fn new(aggregate_id: Id, sequence: u64, payload: T) -> Self {
+        Self {
+            aggregate_id,
+            sequence,
+            payload,
+        }
+    }
+
+    /// Create an envelope of `payload`, the event following this one in the same aggregate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequence number overflows.
+    pub fn next<U>(&self, payload: U) -> Envelope<Id, U>
+    where
+        Id: Clone,
+    {
+        let sequence = self.sequence.checked_add(1).expect("sequence number overflowed");
+        Envelope::new(self.aggregate_id.clone(), sequence, payload)
+    }
+
+    /// Transform the payload, keeping the aggregate ID and sequence number.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Envelope<Id, U> {
+        Envelope::new(self.aggregate_id, self.sequence, f(self.payload))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use super::Envelope;
+    use crate::Branded;
+
+    const FIELDS: &[&str] = &["aggregate_id", "sequence", "payload"];
+
+    impl<Id: Branded + Serialize, T: Serialize> Serialize for Envelope<Id, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Envelope", 3)?;
+            state.serialize_field("aggregate_id", &self.aggregate_id)?;
+            state.serialize_field("sequence", &self.sequence)?;
+            state.serialize_field("payload", &self.payload)?;
+            state.end()
+        }
+    }
+
+    /// A field of the serialized envelope, or `Other` for unknown fields, which are ignored.
+    enum Field {
+        AggregateId,
+        Sequence,
+        Payload,
+        Other,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct FieldVisitor;
+
+            impl Visitor<'_> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a field identifier")
+                }
+
+                fn visit_u64<E: de::Error>(self, index: u64) -> Result<Field, E> {
+                    Ok(match index {
+                        0 => Field::AggregateId,
+                        1 => Field::Sequence,
+                        2 => Field::Payload,
+                        _ => Field::Other,
+                    })
+                }
+
+                fn visit_str<E: de::Error>(self, name: &str) -> Result<Field, E> {
+                    Ok(match name {
+                        "aggregate_id" => Field::AggregateId,
+                        "sequence" => Field::Sequence,
+                        "payload" => Field::Payload,
+                        _ => Field::Other,
+                    })
+                }
+
+                fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Field, E> {
+                    match core::str::from_utf8(name) {
+                        Ok(name) => self.visit_str(name),
+                        Err(_) => Ok(Field::Other),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct EnvelopeVisitor<Id, T>(PhantomData<fn() -> (Id, T)>);
+
+    impl<'de, Id, T> Visitor<'de> for EnvelopeVisitor<Id, T>
+    where
+        Id: Branded + Deserialize<'de>,
+        T: Deserialize<'de>,
+    {
+        type Value = Envelope<Id, T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("an event envelope")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let aggregate_id =
+                seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let sequence =
+                seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let payload = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+            Ok(Envelope::new(aggregate_id, sequence, payload))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut aggregate_id = None;
+            let mut sequence = None;
+            let mut payload = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::AggregateId if aggregate_id.is_some() => {
+                        return Err(de::Error::duplicate_field("aggregate_id"));
+                    }
+                    Field::AggregateId => aggregate_id = Some(map.next_value()?),
+                    Field::Sequence if sequence.is_some() => {
+                        return Err(de::Error::duplicate_field("sequence"));
+                    }
+                    Field::Sequence => sequence = Some(map.next_value()?),
+                    Field::Payload if payload.is_some() => {
+                        return Err(de::Error::duplicate_field("payload"));
+                    }
+                    Field::Payload => payload = Some(map.next_value()?),
+                    Field::Other => {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            Ok(Envelope::new(
+                aggregate_id.ok_or_else(|| de::Error::missing_field("aggregate_id"))?,
+                sequence.ok_or_else(|| de::Error::missing_field("sequence"))?,
+                payload.ok_or_else(|| de::Error::missing_field("payload"))?,
+            ))
+        }
+    }
+
+    impl<'de, Id, T> Deserialize<'de> for Envelope<Id, T>
+    where
+        Id: Branded + Deserialize<'de>,
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_struct("Envelope", FIELDS, EnvelopeVisitor(PhantomData))
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! The error type shared by the fallible code generated by the `Branded` derive macro.
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// The error returned when a value of a brand cannot be constructed from its input.
+///
+/// The error carries the name of the brand, the kind of failure, and with the `alloc` feature, the
+/// offending input, so that error handling and HTTP mapping can be written once for all brands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    brand: &'static str,
+    kind: ErrorKind,
+    #[cfg(feature = "alloc")]
+    input: Option<String>,
+}
+
+/// The reason a value of a brand could not be constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input is not a valid value of the inner type.
+    Parse,
+    /// The value was rejected by the brand's validation.
+    Validation,
+    /// The input does not start with the expected prefix.
+    WrongPrefix(&'static str),
+    /// The check character of the input does not match.
+    Checksum,
+    /// The input is too short or too long.
+    Length,
+}
+
+impl Error {
+    /// Create a new error for the brand named `brand`.
+    pub fn new(brand: &'static str, kind: ErrorKind) -> Self {
+        Self {
+            brand,
+            kind,
+            #[cfg(feature = "alloc")]
+            input: None,
+        }
+    }
+
+    /// Attach the offending input to the error.
+    ///
+    /// The input is only kept with the `alloc` feature.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+    pub fn with_input(self, input: &str) -> Self {
+        Self {
+            #[cfg(feature = "alloc")]
+            input: Some(input.into()),
+            ..self
+        }
+    }
+
+    /// Get the name of the brand.
+    pub fn brand(&self) -> &'static str { self.brand }
+
+    /// Get the reason the value could not be constructed.
+    pub fn kind(&self) -> ErrorKind { self.kind }
+
+    /// Get the offending input, if it was attached.
+    #[cfg(feature = "alloc")]
+    pub fn input(&self) -> Option<&str> { self.input.as_deref() }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}", self.brand)?;
+        #[cfg(feature = "alloc")]
+        if let Some(input) = &self.input {
+            write!(f, " {input:?}")?;
+        }
+        match self.kind {
+            ErrorKind::Parse => f.write_str(": malformed value"),
+            ErrorKind::Validation => f.write_str(": validation failed"),
+            ErrorKind::WrongPrefix(prefix) => write!(f, ": expected `{prefix}`"),
+            ErrorKind::Checksum => f.write_str(": check character does not match"),
+            ErrorKind::Length => f.write_str(": wrong length"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
@@ -0,0 +1,93 @@
+//! Support for brands with versioned external representations.
+//!
+//! The `external` option on the `Branded` derive macro declares the string forms a brand has used
+//! over time, such as `external(prefix = "user", v1 = "user:{}", v2 = "typeid")`. The generated
+//! `FromStr` implementation accepts every declared version, trying the latest first, while
+//! `Display` only writes the latest, so an external ID format can be changed without breaking
+//! clients that still send the old one.
+//!
+//! A version is either a template where `{}` stands for the inner value, or `typeid`, the
+//! [TypeID](https://github.com/jetify-com/typeid) format with the declared prefix, for inner types
+//! implementing [`TypeIdValue`].
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(external(prefix = "user", v1 = "user:{}", v2 = "typeid"))]
+//! pub struct UserId(u128);
+//!
+//! let user_id: UserId = "user:42".parse().unwrap();
+//! assert_eq!(user_id.to_string(), "user_0000000000000000000000001a");
+//! assert_eq!(user_id.to_string().parse::<UserId>().unwrap(), user_id);
+//! ```
+
+use core::fmt;
+
+/// An inner type that can be written in the TypeID format, which encodes 128 bits.
+pub trait TypeIdValue {
+    /// Get the bits of the value.
+    fn to_u128(&self) -> u128;
+
+    /// Construct the value from its bits.
+    fn from_u128(value: u128) -> Self;
+}
+
+impl TypeIdValue for u128 {
+    fn to_u128(&self) -> u128 { *self }
+
+    fn from_u128(value: u128) -> Self { value }
+}
+
+#[cfg(feature = "uuid")]
+impl TypeIdValue for uuid::Uuid {
+    fn to_u128(&self) -> u128 { self.as_u128() }
+
+    fn from_u128(value: u128) -> Self { uuid::Uuid::from_u128(value) }
+}
+
+/// The lowercase Crockford base32 alphabet of TypeID suffixes.
+const ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// The length of a TypeID suffix, which encodes 128 bits in 26 characters of 5 bits.
+const SUFFIX_LEN: usize = 26;
+
+/// Write `value` as a TypeID with `prefix`, which is omitted along with its separator if empty.
+pub fn write_typeid(f: &mut fmt::Formatter<'_>, prefix: &str, value: u128) -> fmt::Result {
+    if !prefix.is_empty() {
+        f.write_str(prefix)?;
+        f.write_str("_")?;
+    }
+    let mut suffix = [0; SUFFIX_LEN];
+    for (i, c) in suffix.iter_mut().enumerate() {
+        let shift = 5 * (SUFFIX_LEN - 1 - i);
+        *c = ALPHABET[((value >> shift) & 0x1f) as usize];
+    }
+    f.write_str(core::str::from_utf8(&suffix).map_err(|_| fmt::Error)?)
+}
+
+/// Parse a TypeID with `prefix`, returning `None` if the input is not one.
+pub fn parse_typeid(prefix: &str, input: &str) -> Option<u128> {
+    let suffix = match prefix {
+        "" => input,
+        prefix => input.strip_prefix(prefix)?.strip_prefix('_')?,
+    };
+    if suffix.len() != SUFFIX_LEN {
+        return None;
+    }
+    let mut value = 0u128;
+    for (i, c) in suffix.bytes().enumerate() {
+        let digit = ALPHABET.iter().position(|&a| a == c)? as u128;
+        // The first character only encodes the top 3 bits.
+        if i == 0 && digit > 7 {
+            return None;
+        }
+        value = (value << 5) | digit;
+    }
+    Some(value)
+}
+
+/// Get the part of `input` in place of `{}` in a template, given the text before and after it.
+pub fn strip_template<'a>(input: &'a str, before: &str, after: &str) -> Option<&'a str> {
+    input.strip_prefix(before)?.strip_suffix(after)
+}
@@ -0,0 +1,127 @@
+//! Formatters for logging collections of branded values.
+//!
+//! [`IdList`] renders a slice of brands as a short, readable summary, such as
+//! `3 UserIds: [a1b2c3d4…, e5f6a7b8…, +1]`, for log statements that would otherwise dump hundreds
+//! of UUIDs.
+//!
+//! ```
+//! use branded::fmt::IdList;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(&'static str);
+//!
+//! let ids = [UserId::new("a1b2c3d4e5"), UserId::new("f6a7"), UserId::new("b8c9")];
+//! assert_eq!(IdList::new(&ids).limit(2).to_string(), "3 UserIds: [a1b2c3d4…, f6a7, +1]");
+//! ```
+
+use core::fmt::{self, Display, Write};
+
+use crate::Branded;
+
+/// A summary of a slice of brands, showing the count and the first few values, truncated.
+///
+/// Both `Display` and `Debug` render the summary. By default, the first 5 values are shown, each
+/// truncated to 8 characters.
+pub struct IdList<'a, B> {
+    ids: &'a [B],
+    limit: usize,
+    width: usize,
+}
+
+impl<'a, B> IdList<'a, B> {
+    /// Summarize the brands in `ids`.
+    pub fn new(ids: &'a [B]) -> Self {
+        Self {
+            ids,
+            limit: 5,
+            width: 8,
+        }
+    }
+
+    /// Show at most `limit` values.
+    pub fn limit(self, limit: usize) -> Self {
+        Self {
+            limit,
+            ..self
+        }
+    }
+
+    /// Truncate each value to at most `width` characters.
+    pub fn width(self, width: usize) -> Self {
+        Self {
+            width,
+            ..self
+        }
+    }
+}
+
+impl<B: Branded + Display> Display for IdList<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.ids.len() == 1 {
+            ""
+        } else {
+            "s"
+        };
+        write!(f, "{} {}{plural}: [", self.ids.len(), B::BRAND)?;
+        for (i, id) in self.ids.iter().take(self.limit).enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write_truncated(f, id, self.width)?;
+        }
+        match self.ids.len().saturating_sub(self.limit) {
+            0 => {}
+            rest if self.limit == 0 => write!(f, "+{rest}")?,
+            rest => write!(f, ", +{rest}")?,
+        }
+        f.write_char(']')
+    }
+}
+
+impl<B: Branded + Display> fmt::Debug for IdList<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Display::fmt(self, f) }
+}
+
+/// Write `value` truncated to at most `width` characters, followed by `…` if it was truncated.
+pub(crate) fn write_truncated(
+    f: &mut fmt::Formatter<'_>,
+    value: &impl Display,
+    width: usize,
+) -> fmt::Result {
+    let mut truncated = Truncated {
+        f: &mut *f,
+        remaining: width,
+        truncated: false,
+    };
+    write!(truncated, "{value}")?;
+    if truncated.truncated {
+        f.write_char('…')?;
+    }
+    Ok(())
+}
+
+/// A writer that passes through at most `remaining` characters, and records whether more were
+/// written.
+struct Truncated<'a, 'f> {
+    f: &'a mut fmt::Formatter<'f>,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl Write for Truncated<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = s.char_indices().nth(self.remaining).map(|(end, _)| end);
+        match end {
+            Some(end) => {
+                self.truncated = true;
+                self.remaining = 0;
+                self.f.write_str(&s[..end])
+            }
+            None => {
+                self.remaining -= s.chars().count();
+                self.f.write_str(s)
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Validators for common string formats, used by the `format` option of the `Branded` derive macro.
+//!
+//! The validators check the syntax of the format only, and are deliberately stricter than the
+//! relevant RFCs where those allow rarely used forms, such as quoted local parts in email
+//! addresses.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(format = "email")]
+//! pub struct EmailAddress(String);
+//!
+//! assert!(EmailAddress::try_new("alice@example.com".to_owned()).is_ok());
+//! assert!("alice".parse::<EmailAddress>().is_err());
+//! ```
+
+/// Check whether `value` is an email address of the form `local@domain`.
+///
+/// The local part consists of letters, digits, and the characters ``!#$%&'*+-/=?^_`{|}~``,
+/// separated by single dots, and the domain is a [hostname](is_hostname) with at least two labels.
+pub fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+    let is_atom = |atom: &str| {
+        !atom.is_empty()
+            && atom
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-/=?^_`{|}~".contains(&b))
+    };
+    value.len() <= 254
+        && local.len() <= 64
+        && local.split('.').all(is_atom)
+        && domain.contains('.')
+        && is_hostname(domain)
+}
+
+/// Check whether `value` is an absolute URL with an authority, such as `https://example.com/path`.
+///
+/// The scheme consists of a letter followed by letters, digits, and the characters `+-.`, and the
+/// authority must not be empty. Whitespace and control characters are rejected anywhere.
+pub fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.bytes().all(|b| b.is_ascii_alphanumeric() || b"+-.".contains(&b))
+        && !authority.is_empty()
+        && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Check whether `value` is a hostname as defined by RFC 1123.
+///
+/// The hostname consists of labels of 1 to 63 letters, digits, and hyphens separated by dots,
+/// where no label starts or ends with a hyphen, and is at most 253 characters long.
+pub fn is_hostname(value: &str) -> bool {
+    let is_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    };
+    value.len() <= 253 && value.split('.').all(is_label)
+}
+
+/// Check whether `value` is a slug, such as `hello-world-2`.
+///
+/// The slug consists of lowercase letters and digits, in words separated by single hyphens.
+pub fn is_slug(value: &str) -> bool {
+    value.split('-').all(|word| {
+        !word.is_empty() && word.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+    })
+}
+
+/// Check whether `value` is a UUID in its hyphenated form, such as
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`, in either case.
+pub fn is_uuid_string(value: &str) -> bool {
+    let groups = [8, 4, 4, 4, 12];
+    let mut count = 0;
+    let valid = value.split('-').zip(groups).all(|(group, len)| {
+        count += 1;
+        group.len() == len && group.bytes().all(|b| b.is_ascii_hexdigit())
+    });
+    valid && count == groups.len() && value.len() == 36
+}
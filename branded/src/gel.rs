@@ -0,0 +1,25 @@
+//! Gel (formerly EdgeDB) integration for branded types.
+//!
+//! The `gel` option on the `Branded` derive macro implements the traits of the Gel protocol crate
+//! for the branded type by delegating to the inner type:
+//!
+//! - `Queryable`, so branded values can be read from query results.
+//! - `ScalarArg`, and through it `QueryArg`, so branded values can be passed as query arguments.
+//!
+//! The protocol and error crates are re-exported for the generated code, and must match the
+//! versions used by the Gel client.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(gel)]
+//! pub struct UserId(uuid::Uuid);
+//!
+//! let user_ids: Vec<UserId> = client.query("select User.id", &()).await?;
+//! ```
+
+#[doc(hidden)]
+pub use gel_errors as errors;
+#[doc(hidden)]
+pub use gel_protocol as protocol;
@@ -0,0 +1,167 @@
+//! A generic brand keyed by a marker type, for declaring many brands without a derive each.
+//!
+//! Every `#[derive(Branded)]` generates a full set of impls for its brand. Codebases with hundreds
+//! of IDs can instead declare an empty tag type per brand and alias [`Id`], which implements the
+//! common traits once for all tags:
+//!
+//! ```
+//! use branded::id::{Id, Tag};
+//!
+//! pub enum UserTag {}
+//! impl Tag for UserTag {
+//!     const NAME: &'static str = "UserId";
+//!     const COLUMN: &'static str = "user_id";
+//! }
+//! pub type UserId = Id<UserTag, u64>;
+//!
+//! pub enum OrderTag {}
+//! impl Tag for OrderTag {
+//!     const NAME: &'static str = "OrderId";
+//!     const COLUMN: &'static str = "order_id";
+//! }
+//! pub type OrderId = Id<OrderTag, u64>;
+//!
+//! let user_id: UserId = "42".parse().unwrap();
+//! assert_eq!(user_id, UserId::new(42));
+//! assert_eq!(format!("{user_id:?}"), "UserId(42)");
+//! ```
+//!
+//! Brands with different tags are distinct types, so mixing them up fails to compile:
+//!
+//! ```compile_fail
+//! # use branded::id::Id;
+//! # pub enum UserTag {}
+//! # pub type UserId = Id<UserTag, u64>;
+//! # pub enum OrderTag {}
+//! # pub type OrderId = Id<OrderTag, u64>;
+//! fn foo(order_id: OrderId) {}
+//!
+//! foo(UserId::new(42));
+//! ```
+//!
+//! The derive macro remains the way to opt into integrations and options; `Id` only implements
+//! the standard traits, and `Serialize` and `Deserialize` with the `serde` feature.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use crate::url::PathSegment;
+use crate::{Branded, Error, ErrorKind, FromInner};
+
+/// A marker type distinguishing a brand of [`Id`] from the others.
+pub trait Tag {
+    /// The name of the brand, used as [`Branded::BRAND`] and in `Debug` output.
+    const NAME: &'static str;
+
+    /// The name of database columns holding the brand, used as [`Branded::COLUMN`].
+    const COLUMN: &'static str;
+}
+
+/// A brand of `Inner`, distinguished from other brands by the marker type `T`.
+///
+/// The tag is never constructed, so it is usually an empty enum. Only [`Branded`], `Debug`, and
+/// `FromStr` require the tag to implement [`Tag`]; the other trait impls place no bounds on it.
+pub struct Id<T, Inner> {
+    inner: Inner,
+    tag: PhantomData<fn() -> T>,
+}
+
+impl<T, Inner> Id<T, Inner> {
+    /// Create a new instance of the branded type.
+    pub const fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            tag: PhantomData,
+        }
+    }
+}
+
+impl<T: Tag, Inner> Branded for Id<T, Inner> {
+    type Inner = Inner;
+
+    const BRAND: &'static str = T::NAME;
+
+    const COLUMN: &'static str = T::COLUMN;
+
+    fn inner(&self) -> &Inner { &self.inner }
+
+    fn into_inner(self) -> Inner { self.inner }
+}
+
+impl<T: Tag, Inner> FromInner for Id<T, Inner> {
+    fn from_inner(inner: Inner) -> Self { Self::new(inner) }
+}
+
+impl<T, Inner: Clone> Clone for Id<T, Inner> {
+    fn clone(&self) -> Self { Self::new(self.inner.clone()) }
+}
+
+impl<T, Inner: Copy> Copy for Id<T, Inner> {}
+
+impl<T, Inner: PartialEq> PartialEq for Id<T, Inner> {
+    fn eq(&self, other: &Self) -> bool { self.inner == other.inner }
+}
+
+impl<T, Inner: Eq> Eq for Id<T, Inner> {}
+
+impl<T, Inner: PartialOrd> PartialOrd for Id<T, Inner> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&self.inner, &other.inner)
+    }
+}
+
+impl<T, Inner: Ord> Ord for Id<T, Inner> {
+    fn cmp(&self, other: &Self) -> Ordering { Ord::cmp(&self.inner, &other.inner) }
+}
+
+impl<T, Inner: Hash> Hash for Id<T, Inner> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.inner.hash(state); }
+}
+
+impl<T, Inner: Default> Default for Id<T, Inner> {
+    fn default() -> Self { Self::new(Inner::default()) }
+}
+
+impl<T: Tag, Inner: fmt::Debug> fmt::Debug for Id<T, Inner> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(T::NAME).field(&self.inner).finish()
+    }
+}
+
+impl<T, Inner: fmt::Display> fmt::Display for Id<T, Inner> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.inner.fmt(f) }
+}
+
+impl<T: Tag, Inner: FromStr> FromStr for Id<T, Inner> {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .parse()
+            .map(Self::new)
+            .map_err(|_| Error::new(T::NAME, ErrorKind::Parse).with_input(input))
+    }
+}
+
+impl<T, Inner: PathSegment> PathSegment for Id<T, Inner> {}
+
+impl<T, Inner> AsRef<Inner> for Id<T, Inner> {
+    fn as_ref(&self) -> &Inner { &self.inner }
+}
+
+#[cfg(feature = "serde")]
+impl<T, Inner: serde::Serialize> serde::Serialize for Id<T, Inner> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, Inner: serde::Deserialize<'de>> serde::Deserialize<'de> for Id<T, Inner> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Inner::deserialize(deserializer).map(Self::new)
+    }
+}
@@ -0,0 +1,38 @@
+//! InfluxDB integration for branded types.
+//!
+//! The `influxdb` option on the `Branded` derive macro generates a conversion from the branded type
+//! into an `influxdb::Type`, so that branded values can be passed directly to
+//! `WriteQuery::add_tag` and `WriteQuery::add_field`. The conversion is implemented in terms of the
+//! [`InfluxValue`] trait.
+
+use alloc::string::String;
+use influxdb::Type;
+
+/// Inner types that can be written as an InfluxDB tag or field value.
+///
+/// This is implemented for all the types that `influxdb` can convert into a [`Type`] natively, as
+/// well as for `uuid::Uuid`, which is written in its hyphenated text form.
+pub trait InfluxValue {
+    /// Convert the value into an InfluxDB value.
+    fn into_influx_value(self) -> Type;
+}
+
+macro_rules! impl_influx_value {
+    ($($ty:ty),*) => {
+        $(
+            impl InfluxValue for $ty {
+                fn into_influx_value(self) -> Type { self.into() }
+            }
+        )*
+    };
+}
+
+impl_influx_value!(bool, f32, f64, i8, i16, i32, i64, u8, u16, u32, u64, String);
+
+#[cfg(feature = "uuid")]
+impl InfluxValue for uuid::Uuid {
+    fn into_influx_value(self) -> Type {
+        use alloc::string::ToString;
+        Type::Text(self.hyphenated().to_string())
+    }
+}
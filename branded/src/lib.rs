@@ -8,7 +8,9 @@
 //!
 //! Thanks to Rust's trait system, we can transparently derive traits for our branded types based on
 //! the inner type, making them completely transparent to other libraries such as `serde`, and
-//! `sqlx`.
+//! `sqlx`. Every brand also implements `AsRef<Inner>` and `Borrow<Inner>`, and brands of `String`
+//! also implement `AsRef<str>` and `Borrow<str>`, so maps keyed by them can be queried with a
+//! `&str`.
 //!
 //! > This crate is a continuation of the now-archived [bty](https://github.com/lffg/bty) crate. It
 //! > has been rewritten to be
@@ -23,46 +25,1377 @@
 //! pub struct UserId(String);
 //! ```
 //!
+//! ## Options
+//!
+//! Options are passed in the `#[branded(...)]` attribute. Some options take nested parameters,
+//! such as `serde(as_string)` or `uuid(v7, format = "simple")`, and the bare option is equivalent
+//! to passing no parameters. Unknown options and parameters are compile errors.
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid(v8))]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! ## Declaring many brands
+//!
+//! The [`declare!`] macro declares many brands at once, each with its inner type and the options
+//! passed to `#[branded(...)]` in brackets.
+//!
+//! ## Flexible constructors
+//!
+//! Pass `new_into` as an option to the `Branded` derive macro to make `new` accept any
+//! `impl Into<Inner>`. Integer literals then need a suffix, since they are no longer inferred as
+//! the inner type.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(new_into)]
+//! pub struct UserId(String);
+//!
+//! let user_id = UserId::new("abc");
+//! ```
+//!
+//! Pass `const_new` as an option to make `new` a `const fn`, so brands can be used in `const` and
+//! `static` items. It cannot be combined with `new_into`.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(const_new)]
+//! pub struct UserId(u64);
+//!
+//! pub const SYSTEM_USER: UserId = UserId::new(0);
+//! ```
+//!
+//! The [`brand!`] macro constructs such constants from string literals parsed at compile time.
+//!
+//! Pass `constructor = "name"` and `constructor_vis = "pub(crate)"` to rename the generated
+//! constructor or change its visibility, or `no_constructor` to omit it, so a validated brand can
+//! provide its own checked constructor instead.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(constructor = "from_raw", constructor_vis = "pub(crate)")]
+//! pub struct Email(String);
+//!
+//! impl Email {
+//!     pub fn try_new(value: String) -> Option<Self> {
+//!         value.contains('@').then(|| Self::from_raw(value))
+//!     }
+//! }
+//! ```
+//!
+//! Pass `from` as an option to generate `From` conversions in both directions between the brand
+//! and its inner type, so brands compose with APIs that take `impl Into<Inner>`. The conversion
+//! out of the brand is not possible for a brand that is generic over its inner type.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(from)]
+//! pub struct OrderId(u64);
+//!
+//! let order_id: OrderId = 42.into();
+//! let inner: u64 = order_id.into();
+//! ```
+//!
+//! With the `alloc` feature, pass `try_from` to generate `TryFrom<&str>` and `TryFrom<String>`,
+//! which parse the string with the brand's `FromStr` implementation and fail with its error.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(try_from)]
+//! pub struct OrderId(u64);
+//!
+//! assert!(OrderId::try_from("42").is_ok());
+//! assert!(OrderId::try_from(String::from("abc")).is_err());
+//! # }
+//! ```
+//!
+//! Brands of `String` and `&str` cannot combine `try_from` with `from`, since `From` of the
+//! string already implies `TryFrom` of it:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(from, try_from)]
+//! pub struct Sku(String);
+//! ```
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(from, try_from)]
+//! pub struct Label(&'static str);
+//! ```
+//!
+//! Pass `builder` to generate the conversions that builder setters accept, such as those of
+//! derive_builder and typed-builder: `From<Inner>`, or `TryFrom<Inner>` through `try_new` for
+//! validated brands, with `builder(inner)`, and the `try_from` conversions with `builder(parse)`.
+//! The bare option generates both.
+//!
+//! Pass `inner_mut` to generate an `inner_mut` method returning a mutable reference to the inner
+//! value, for brands that are mutated in place.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(inner_mut)]
+//! pub struct Counter(u64);
+//!
+//! let mut counter = Counter::new(1);
+//! *counter.inner_mut() += 1;
+//! assert_eq!(counter.into_inner(), 2);
+//! ```
+//!
+//! Mutating the inner value skips validation, so validated brands cannot use `inner_mut`, and
+//! should be transformed with `map` instead:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(inner_mut, range = "1..")]
+//! pub struct Counter(u64);
+//! ```
+//!
+//! Pass `structural` for integer brands that are matched on or used as const generic arguments.
+//! The constructor becomes a `const fn`, a `const fn get` accessor returns the inner value, and
+//! `PartialEq` and `Eq` are left to `#[derive]`, since only derived equality allows constants in
+//! match patterns.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded, PartialEq, Eq)]
+//! #[branded(structural)]
+//! pub struct Opcode(u8);
+//!
+//! impl Opcode {
+//!     pub const PING: Self = Self::new(1);
+//! }
+//!
+//! let opcodes: [u8; Opcode::PING.get() as usize] = [1];
+//! assert!(matches!(Opcode::new(opcodes[0]), Opcode::PING));
+//! ```
+//!
+//! ## Validation
+//!
+//! Pass `validate = "path::to::fn"` to check the inner value with a `fn(&Inner) -> bool`. This
+//! generates a `try_new` constructor returning an [`Error`] of kind [`ErrorKind::Validation`], and
+//! validates values constructed by `FromStr`, serde deserialization, and sqlx decoding. The `new`
+//! constructor stays unchecked, so combine `validate` with `constructor_vis` or `no_constructor`
+//! to hide it. A `new_unchecked` constructor with the same visibility names the unchecked
+//! construction explicitly, for trusted values such as those read from the database. Validated
+//! brands only implement `Default` with `generator(default)`, since the default inner value may
+//! not pass validation.
+//!
+//! ```
+//! use branded::{Branded, ErrorKind};
+//!
+//! fn is_port(port: &u16) -> bool { *port != 0 }
+//!
+//! #[derive(Branded)]
+//! #[branded(validate = "is_port", no_constructor)]
+//! pub struct Port(u16);
+//!
+//! assert!(Port::try_new(8080).is_ok());
+//! assert_eq!("0".parse::<Port>().unwrap_err().kind(), ErrorKind::Validation);
+//! ```
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(range = "1..", constructor_vis = "pub(crate)")]
+//! pub struct Port(u16);
+//!
+//! // Ports in the configuration were validated when it was loaded.
+//! let port = Port::new_unchecked(8080);
+//! ```
+//!
+//! Validated brands also get `validate`, which checks an inner value without constructing the
+//! brand, and `is_valid`, which checks string input such as a form field. String brands without
+//! a `validate` function check the input in place, without allocating.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(len = "3..=32")]
+//! pub struct Username(String);
+//!
+//! assert!(Username::is_valid("alice"));
+//! assert!(!Username::is_valid("al"));
+//! assert!(Username::validate(&"al".to_owned()).is_err());
+//! ```
+//!
+//! Pass `len = "1..=64"` to bound the length of a string brand in characters, or `range = "1.."`
+//! to bound the value of an integer brand, without writing a validator function.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(range = "1..")]
+//! pub struct Port(u16);
+//!
+//! assert!(Port::try_new(0).is_err());
+//! ```
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(range = "1..")]
+//! pub struct Port(u16);
+//!
+//! let port = Port::default();
+//! ```
+//!
+//! Pass `format = "email"`, `"url"`, `"hostname"`, `"slug"`, or `"uuid_string"` to validate a
+//! string brand with one of the validators in the [`format`] module.
+//!
+//! With the `regex` feature, pass `pattern = "..."` to validate string brands with a regular
+//! expression. See the `branded::regex` module for details.
+//!
+//! With the `reflect` feature, the declared `len`, `range`, `pattern`, `format`, and
+//! `serde(prefix)` constraints are available at runtime as `Branded::CONSTRAINTS`, such as to
+//! generate client-side validation. See the `branded::constraints` module for details.
+//!
+//! ## Comparing with the inner type
+//!
+//! Pass `partial_eq_inner` as an option to the `Branded` derive macro to implement
+//! `PartialEq<Inner>` for the brand. Brands of `String` can also be compared with `str` and `&str`.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(partial_eq_inner)]
+//! pub struct OrderId(u64);
+//!
+//! assert_eq!(OrderId::new(42), 42);
+//! ```
+//!
+//! ## Casting collections
+//!
+//! Pass `cast` to a `#[repr(transparent)]` brand to convert slices and vectors of the brand, and of
+//! `Option` of the brand, to and from those of the inner type without copying. See the [`cast`]
+//! module for details.
+//!
+//! ## Generic brands
+//!
+//! The generics, lifetimes, and where clauses of the struct are carried over to every generated
+//! impl, so brands may borrow their inner value or wrap a type parameter. The `from` option only
+//! generates the conversion into the brand when the inner type is generic.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(partial_eq_inner)]
+//! pub struct Key<'a>(&'a str);
+//!
+//! #[derive(Branded)]
+//! #[branded(from)]
+//! pub struct Wrapper<T: Copy>(T);
+//!
+//! assert_eq!(Key::new("tenant"), "tenant");
+//! assert_eq!(*Wrapper::from(7u8).inner(), 7);
+//! ```
+//!
+//! ## Tagged IDs
+//!
+//! [`id::Id`] is a single generic brand keyed by an empty tag type, for codebases that declare
+//! too many brands to derive each one. See the [`id`] module for details.
+//!
+//! ## Composite keys
+//!
+//! Deriving `Branded` on a struct with more than one field makes it a composite key, with a `new`
+//! constructor, an accessor per component, `into_parts`, and the standard traits comparing and
+//! hashing the components as a tuple. Tuple components are named after their type. The `serde`,
+//! `const_new`, `constructor_vis`, and `skip` options are supported.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct TenantId(u32);
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! pub struct TenantUserId(TenantId, UserId);
+//!
+//! let key = TenantUserId::new(TenantId::new(1), UserId::new(2));
+//! assert_eq!(key.user_id(), &UserId::new(2));
+//! ```
+//!
+//! ## Brand names
+//!
+//! [`Branded::BRAND`] is the name of the brand, defaulting to the name of the type. Pass
+//! `brand = "..."` as an option to the `Branded` derive macro to override it.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(brand = "User")]
+//! pub struct UserId(u64);
+//!
+//! assert_eq!(UserId::BRAND, "User");
+//! ```
+//!
+//! [`Branded::COLUMN`] is the name of database columns holding the brand, defaulting to the brand
+//! name in snake case. Pass `column = "..."` to override it.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! #[branded(column = "owner_id")]
+//! pub struct OwnerId(u64);
+//!
+//! assert_eq!(UserId::COLUMN, "user_id");
+//! assert_eq!(OwnerId::COLUMN, "owner_id");
+//! ```
+//!
+//! ## Generic construction
+//!
+//! [`FromInner::from_inner`] constructs a brand from its inner type, for generic repositories and
+//! caches over `B: FromInner<Inner = ...>`. The derive macro only implements [`FromInner`] for
+//! brands with a public constructor and without validation, so generic code cannot bypass
+//! `try_new` or `no_constructor`.
+//!
+//! ```
+//! use branded::{Branded, FromInner};
+//!
+//! fn load<B: FromInner<Inner = u64>>(row: u64) -> B {
+//!     B::from_inner(row)
+//! }
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! assert_eq!(load::<UserId>(42), UserId::new(42));
+//! ```
+//!
+//! ```compile_fail
+//! use branded::{Branded, FromInner};
+//!
+//! #[derive(Branded)]
+//! #[branded(len = "1..=64")]
+//! pub struct Name(String);
+//!
+//! let name = Name::from_inner(String::new());
+//! ```
+//!
+//! ```compile_fail
+//! use branded::{Branded, FromInner};
+//!
+//! #[derive(Branded)]
+//! #[branded(no_constructor)]
+//! pub struct Token(String);
+//!
+//! let token = Token::from_inner(String::new());
+//! ```
+//!
+//! ## Mapping
+//!
+//! Every brand has `map` and `try_map` methods transforming the inner value, which validate the
+//! result for validated brands. The [`FromInner`] trait provides them for generic code over
+//! brands without validation.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct Slug(&'static str);
+//!
+//! assert_eq!(Slug::new(" hello ").map(str::trim), Slug::new("hello"));
+//! ```
+//!
+//! ## Heterogeneous brands
+//!
+//! [`DynBranded`] is an object-safe trait implemented for every brand that implements `Debug` and
+//! `Display`, so that brands of different kinds can be stored together, such as in audit logs.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use branded::{Branded, DynBranded};
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! pub struct OrderId(String);
+//!
+//! let ids: Vec<Box<dyn DynBranded>> =
+//!     vec![Box::new(UserId::new(1)), Box::new(OrderId::new("a".to_owned()))];
+//! assert_eq!(ids[1].brand_name(), "OrderId");
+//! assert_eq!(ids[0].inner_string(), "1");
+//! # }
+//! ```
+//!
+//! ## Event envelopes
+//!
+//! [`envelope::Envelope`] carries a branded aggregate ID, a sequence number, and an event, as a
+//! shared envelope type for outboxes and event-sourced services. With the `serde` feature, it
+//! implements `Serialize` and `Deserialize`.
+//!
+//! ## Renaming brands
+//!
+//! Pass `alias_of = "OldBrand"` as an option to the `Branded` derive macro to generate `From`
+//! conversions in both directions between the brand and an older brand with the same inner type.
+//! Mark the old brand as `#[deprecated]` to get compiler guidance on its remaining uses. The
+//! conversion into the old brand requires it to implement [`FromInner`].
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[deprecated(note = "use UserId instead")]
+//! pub struct LegacyUserId(String);
+//!
+//! #[derive(Branded)]
+//! #[branded(alias_of = "LegacyUserId")]
+//! pub struct UserId(String);
+//! ```
+//!
+//! The conversion from the old brand cannot fail, so validated brands cannot use `alias_of`:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct LegacyUserId(String);
+//!
+//! #[derive(Branded)]
+//! #[branded(alias_of = "LegacyUserId", len = "1..=64")]
+//! pub struct UserId(String);
+//! ```
+//!
+//! Pass `convert_from(...)` with a list of brands to generate a `From` conversion from each of
+//! them into the brand only, or a `TryFrom` conversion for validated brands.
+//!
+//! ## Marker traits
+//!
+//! Pass `implements(...)` as an option to the `Branded` derive macro to implement your own marker
+//! traits for the brand, so generic code can be bounded over a subset of brands.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! pub trait EntityId: Branded {}
+//! pub trait Auditable {}
+//!
+//! #[derive(Branded)]
+//! #[branded(implements(EntityId, Auditable))]
+//! pub struct UserId(String);
+//! ```
+//!
+//! ## Same-brand bounds
+//!
+//! Bound generic parameters with [`same_brand::SameBrand`] to require that two branded values
+//! share a brand, turning mixups in generic code into compile errors.
+//!
+//! ## Custom rendering
+//!
+//! Pass `render = "path::to::fn"` as an option to the `Branded` derive macro to format the inner
+//! value with a `fn(&Inner, &mut fmt::Formatter<'_>) -> fmt::Result` in the `Display` and `Debug`
+//! implementations, for inner types without a `Display` implementation.
+//!
+//! ```
+//! use branded::Branded;
+//! use core::fmt;
+//!
+//! fn hex(bytes: &[u8; 4], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!     bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+//! }
+//!
+//! #[derive(Branded)]
+//! #[branded(render = "hex")]
+//! pub struct Checksum([u8; 4]);
+//!
+//! assert_eq!(Checksum::new([0xde, 0xad, 0xbe, 0xef]).to_string(), "deadbeef");
+//! ```
+//!
+//! ## Zero-padded numbers
+//!
+//! The `Display` implementation passes the width, fill, and alignment of the format string through
+//! to the inner value. Pass `display(zero_pad = N)` as an option to the `Branded` derive macro to
+//! always render an integer brand as at least `N` digits with leading zeros. `FromStr` then
+//! rejects inputs shorter than `N` characters, and `serde(as_string)` uses the same form.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(display(zero_pad = 8))]
+//! pub struct InvoiceNumber(u32);
+//!
+//! assert_eq!(InvoiceNumber::new(1234).to_string(), "00001234");
+//! assert_eq!("00001234".parse(), Ok(InvoiceNumber::new(1234)));
+//! ```
+//!
+//! ## Formatting without allocations
+//!
+//! Every brand with a `Display` implementation gets a `write_to` method that formats it into any
+//! [`core::fmt::Write`] buffer. With the `compact_str` feature, pass `compact_str` as an option to
+//! the `Branded` derive macro to also generate a `to_compact_string` method, which stores short IDs
+//! inline.
+//!
+//! ```
+//! use branded::Branded;
+//! use core::fmt::Write;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! let mut line = heapless::String::<32>::new();
+//! write!(line, "user=").unwrap();
+//! UserId::new(42).write_to(&mut line).unwrap();
+//! assert_eq!(line, "user=42");
+//! ```
+//!
+//! ## Logging lists of IDs
+//!
+//! [`fmt::IdList`] renders a slice of brands as a short summary with the count and the first few
+//! values, each truncated, for log statements that would otherwise dump every ID.
+//!
+//! ## Diffs in tests
+//!
+//! [`diff::Diff`] compares two slices of brands and reports only the positions that differ, with
+//! the brand name and the short form of each value, instead of the raw tuples `assert_eq!` prints.
+//! The `assert_brands_eq!` macro panics with it.
+//!
+//! ## URL paths
+//!
+//! With the `alloc` feature, the `path!` macro builds URL paths, percent-encoding each argument as
+//! a single path segment. Brands marked with the `secret` option are rejected at compile time. See
+//! the [`url`] module for details.
+//!
+//! ## Generators
+//!
+//! Pass `generator = "path::to::fn"` as an option to the `Branded` derive macro to generate a
+//! `generate()` constructor calling a function that returns the inner type, such as
+//! `ulid::Ulid::new`. Pass `generator(function = "...", default)` to also use it as the `Default`
+//! implementation. With the `testing` feature, `override_generator` replaces the generator of the
+//! brand in the whole process, for deterministic values in tests.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! fn next_id() -> u64 {
+//!     42
+//! }
+//!
+//! #[derive(Branded)]
+//! #[branded(generator(function = "next_id", default))]
+//! pub struct JobId(u64);
+//!
+//! assert_eq!(JobId::default(), JobId::new(42));
+//! ```
+//!
+//! Pass `on_generate = "path::to::fn"` to call a hook with a reference to every value minted by
+//! `generate()`, `new_v4()`, or `new_v7()`, such as to count ID creation per brand.
+//!
+//! ## Skipping generated impls
+//!
+//! Pass `skip(...)` as an option to the `Branded` derive macro to suppress specific standard trait
+//! impls. The supported traits are `Clone`, `Copy`, `Debug`, `Default`, `Display`, `FromStr`,
+//! `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash`, `AsRef`, and `Borrow`. Impls that depend on a
+//! skipped trait are skipped as well.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(skip(Default, Display, Debug))]
+//! pub struct SessionToken(String);
+//!
+//! impl std::fmt::Debug for SessionToken {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         f.write_str("SessionToken(<redacted>)")
+//!     }
+//! }
+//! ```
+//!
+//! The standard trait impls are only generated for the traits the inner type implements. For
+//! primitive types, `String`, `&str`, and `uuid::Uuid`, the derive knows which traits these are,
+//! so a brand of `f64` has no `Eq`, `Ord`, or `Hash` impl at all. Brands generic over their inner
+//! type implement each trait where the type parameter does. For other inner types, each impl
+//! applies only if the inner type implements the trait, so using a missing one fails where it is
+//! used, with an error naming the inner type, and `skip(...)` leaves out the impls that never do.
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct Price(f64);
+//!
+//! fn assert_hash<T: std::hash::Hash>() {}
+//! assert_hash::<Price>();
+//! ```
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! pub struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(Branded)]
+//! pub struct Origin(Point);
+//!
+//! // `Point` does not implement `Display`, so neither does `Origin`.
+//! let origin = Origin::new(Point { x: 0, y: 0 });
+//! println!("{origin}");
+//! ```
+//!
+//! ## Path-structured IDs
+//!
+//! Pass `path = "..."` as an option to the `Branded` derive macro to brand a tuple of other brands
+//! as a hierarchical resource name. Each `{Component}` placeholder names the type of the matching
+//! tuple element. `Display` renders the template, `FromStr` validates it and parses each component
+//! with [`Error`] on failure, and an accessor is generated per component.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct OrgId(String);
+//!
+//! #[derive(Branded)]
+//! pub struct ProjectId(u32);
+//!
+//! #[derive(Branded)]
+//! #[branded(path = "orgs/{OrgId}/projects/{ProjectId}")]
+//! pub struct ProjectName((OrgId, ProjectId));
+//!
+//! let name: ProjectName = "orgs/acme/projects/7".parse().unwrap();
+//! assert_eq!(name.project_id(), &ProjectId::new(7));
+//! assert_eq!(name.to_string(), "orgs/acme/projects/7");
+//! ```
+//!
+//! ## Checksums
+//!
+//! Pass `checksum = "luhn"` or `checksum = "crc"` as an option to the `Branded` derive macro to
+//! append a check character to the brand's `Display` output and validate it in its `FromStr`
+//! implementation. See the [`checksum`] module for the algorithms.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(checksum = "luhn")]
+//! pub struct AccountNumber(u64);
+//!
+//! let account = AccountNumber::new(7992739871);
+//! assert_eq!(account.to_string(), "79927398713");
+//! assert!("79927398712".parse::<AccountNumber>().is_err());
+//! ```
+//!
+//! ## Versioned external representations
+//!
+//! Pass `external(v1 = "...", v2 = "...")` as an option to the `Branded` derive macro to accept
+//! every declared string form of the brand when parsing, while writing the latest. See the
+//! [`external`] module for the formats.
+//!
+//! ## Parsing byte streams
+//!
+//! [`parser::Parser`] parses prefixed and checksummed IDs from byte slices without allocating,
+//! and reports the byte offset where parsing failed.
+//!
+//! ## Errors
+//!
+//! The fallible implementations generated by the `Branded` derive macro fail with [`Error`], which
+//! carries the name of the brand and an [`ErrorKind`], so error handling can be written once for
+//! all brands. With the `alloc` feature, the error also keeps the offending input. This includes
+//! the `FromStr` implementation derived for brands whose inner type implements `FromStr`.
+//!
+//! ```
+//! use branded::{Branded, ErrorKind};
+//!
+//! #[derive(Branded)]
+//! #[branded(checksum = "luhn")]
+//! pub struct AccountNumber(u64);
+//!
+//! let error = "79927398712".parse::<AccountNumber>().unwrap_err();
+//! assert_eq!(error.brand(), "AccountNumber");
+//! assert_eq!(error.kind(), ErrorKind::Checksum);
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! let error = "abc".parse::<UserId>().unwrap_err();
+//! assert_eq!(error.kind(), ErrorKind::Parse);
+//! ```
+//!
+//! ## Error context
+//!
+//! With the `alloc` feature, pass `context` as an option to the `Branded` derive macro to generate
+//! a `context()` method returning a [`context::BrandContext`] with the brand name and the value, to
+//! record which ID caused an error.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(context)]
+//! pub struct UserId(u64);
+//!
+//! assert_eq!(UserId::new(42).context().to_string(), "UserId:42");
+//! # }
+//! ```
+//!
+//! ## Sharding
+//!
+//! Pass `shard(modulo = N)` as an option to the `Branded` derive macro to generate a `shard()`
+//! method that maps the brand to a stable shard in `0..N`. See the [`shard`] module for details.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(shard(modulo = 16))]
+//! pub struct TenantId(u64);
+//!
+//! assert!(TenantId::new(1234).shard() < TenantId::SHARDS);
+//! ```
+//!
+//! ## Self-tests
+//!
+//! Pass `selftest` as an option to the `Branded` derive macro to generate a `#[cfg(test)]` module
+//! with conformance tests for the brand: Display/FromStr round-trips, ordering consistency, and
+//! hash/eq agreement, as well as serde round-trips through `serde_json` when combined with `serde`.
+//! The checks run over samples returned by the function given as `selftest(samples = "...")`, along
+//! with the default value, and random UUIDs for brands with the `uuid` option, which need no
+//! function. The samples must include at least two distinct values.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! fn user_ids() -> [UserId; 2] { [UserId::new("alice".to_owned()), UserId::new("bob".to_owned())] }
+//!
+//! #[derive(Branded)]
+//! #[branded(selftest(samples = "user_ids"))]
+//! pub struct UserId(String);
+//! ```
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! // Without samples, the checks could not fail.
+//! #[derive(Branded)]
+//! #[branded(selftest)]
+//! pub struct UserId(String);
+//! ```
+//!
 //! ## Serde
 //!
-//! The `serde` feature transparently derives the `Serialize` and `Deserialize` traits for the
-//! branded type. Pass `serde` as an option to the `Branded` derive macro to enable this feature.
+//! The `serde` feature transparently derives the `Serialize` and `Deserialize` traits for the
+//! branded type. Pass `serde` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(serde)]
+//! pub struct UserId(String);
+//! ```
+//!
+//! Brands of `&'a str` and `Cow<'a, str>` borrow the string from the input when deserializing,
+//! where the format allows it. Unlike the `Deserialize` implementation of `Cow` itself, `Cow`
+//! brands only allocate for strings that cannot be borrowed, such as strings with escape
+//! sequences in JSON, which requires the `alloc` feature.
+//!
+//! Pass `serde(default_generate)` instead to deserialize a missing or null value by generating a
+//! new one, such as a random UUID with the `uuid` option or a value of the `generator` option,
+//! which suits IDs in "create" payloads.
+//! The default only applies to human-readable formats, since it relies on deserializing the value
+//! as optional.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid, serde(default_generate))]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! Pass `serde(as_string)` to (de)serialize the brand as a string with the `Display` and `FromStr`
+//! implementations of the inner type, such as for 128-bit integers that JSON consumers cannot
+//! represent as numbers.
+//!
+//! Pass `serde(prefix = "...")` to (de)serialize the brand as a string of the inner value after a
+//! prefix, such as `"cus_42"`, so that API payloads carry self-describing IDs. Strings with another
+//! prefix are rejected, catching IDs of one brand sent in place of another.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(serde(prefix = "cus_"))]
+//! pub struct CustomerId(u64);
+//!
+//! assert_eq!(serde_json::to_string(&CustomerId::new(42)).unwrap(), r#""cus_42""#);
+//! assert!(serde_json::from_str::<CustomerId>(r#""acct_42""#).is_err());
+//! # }
+//! ```
+//!
+//! Add `accept_legacy = "..."` to also accept strings with an old prefix while migrating to a new
+//! one, and `on_legacy = "path::to::fn"` to call a hook with every value read with it. The new
+//! prefix is always written.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(serde(prefix = "user_", accept_legacy = "usr_"))]
+//! pub struct UserId(u64);
+//!
+//! let id = serde_json::from_str::<UserId>(r#""usr_42""#).unwrap();
+//! assert_eq!(serde_json::to_string(&id).unwrap(), r#""user_42""#);
+//! # }
+//! ```
+//!
+//! Pass `serde(style = "wrapped")` to (de)serialize the brand as an object of its brand name and
+//! inner value, such as `{"type": "UserId", "value": 42}`, so that event payloads carry
+//! self-describing IDs. Objects naming another brand are rejected.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(serde(style = "wrapped"))]
+//! pub struct UserId(u64);
+//!
+//! let json = serde_json::to_string(&UserId::new(42)).unwrap();
+//! assert_eq!(json, r#"{"type":"UserId","value":42}"#);
+//! assert!(serde_json::from_str::<UserId>(r#"{"type":"OrderId","value":42}"#).is_err());
+//! # }
+//! ```
+//!
+//! With the `alloc` feature, `#[serde(with = "branded::serde::comma_separated")]` encodes a `Vec`
+//! of brands as a comma-separated string using their `Display` and `FromStr` implementations, as
+//! in `?ids=a,b,c` query parameters.
+//!
+//! ## SQLx
+//!
+//! The `sqlx` feature derives the `Type`, `Encode`, and `Decode` traits for the branded type. Pass
+//! `sqlx` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(sqlx)]
+//! pub struct UserId(String);
+//! ```
+//!
+//! Pass `sqlx(table = "...", column = "...")` instead to also generate `exists` and `delete`
+//! methods querying the row keyed by the brand in the given table. The column defaults to `id`.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(sqlx(table = "users", column = "user_id"))]
+//! pub struct UserId(i64);
+//!
+//! let exists = UserId::new(1).exists(&pool).await?;
+//! ```
+//!
+//! Pass `sqlx(from_row)` to also implement `FromRow` reading the brand from the first column, or
+//! `sqlx(from_row = "...")` to read it from the named column, so a list of IDs can be fetched with
+//! `query_as` directly.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(sqlx(from_row))]
+//! pub struct UserId(i64);
+//!
+//! let ids = sqlx::query_as::<_, UserId>("SELECT id FROM users").fetch_all(&pool).await?;
+//! ```
+//!
+//! Pass `sqlx(postgres)` to implement `Type` for Postgres only, which also implements
+//! `PgHasArrayType` so arrays of the brand can be bound, and `sqlx(postgres, type_name = "...")` to
+//! map the brand to a custom Postgres type such as a domain.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(sqlx(postgres, type_name = "user_id"))]
+//! pub struct UserId(i64);
+//! ```
+//!
+//! Databases have no 128-bit integer type, so brands of `u128` or `i128` select a storage strategy
+//! with `sqlx(int128 = "bytes")`, storing 16 big-endian bytes, or `sqlx(int128 = "text")`, storing
+//! a decimal string that can be cast to `NUMERIC`.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(sqlx(int128 = "bytes"))]
+//! pub struct ContentHash(u128);
+//! ```
+//!
+//! The impls are generic over every sqlx database, unless restricted to a comma-separated list of
+//! `postgres`, `mysql`, and `sqlite` with `sqlx(only = "...")`.
+//!
+//! The [`notify`] module encodes IDs as the payloads of Postgres `NOTIFY` change notifications,
+//! prefixed with the brand name, and decodes them with validation, rejecting the IDs of other
+//! brands. It does not require the `sqlx` feature.
+//!
+//! ## UUID
+//!
+//! The `uuid` feature exposes `nil()` and `new_v4()` methods on the branded type. Pass `uuid` as an
+//! option to the `Branded` derive macro to enable this feature.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid)]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! Pass `uuid(v7)` to also expose `new_v7()`, which requires the `v7` feature of the `uuid` crate,
+//! and `uuid(format = "...")` to display the UUID as `hyphenated`, `simple`, `urn`, or `braced`.
 //!
 //! ```
 //! use branded::Branded;
 //!
 //! #[derive(Branded)]
-//! #[branded(serde)]
+//! #[branded(uuid(v7, format = "simple"))]
+//! pub struct EventId(uuid::Uuid);
+//!
+//! assert_eq!(EventId::nil().to_string(), "00000000000000000000000000000000");
+//! ```
+//!
+//! With `uuid(v7)`, the brand also gets `bucket` and `range_for_bucket` helpers for time-range
+//! scans over ID-ordered tables. See the [`time_bucket`] module for details.
+//!
+//! Pass `uuid(v5 = "...")` with one of the well-known namespaces `dns`, `url`, `oid`, and `x500`,
+//! or with a UUID, to expose the namespace as `NAMESPACE` and a `derive_from(name)` constructor
+//! of deterministic UUID v5 values, which requires the `v5` feature of the `uuid` crate.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid(v5 = "3f0a9c2e-5b1d-4e8a-9c7f-2d6b8e4a1f03"))]
+//! pub struct TenantId(uuid::Uuid);
+//!
+//! assert_eq!(TenantId::derive_from("acme"), TenantId::derive_from("acme"));
+//! ```
+//!
+//! ## newtype-uuid
+//!
+//! The `newtype_uuid` feature implements `GenericUuid` for UUID brands, along with `From`
+//! conversions in both directions with `TypedUuid<Kind>`. Pass `newtype_uuid = "Kind"` as an
+//! option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid, newtype_uuid = "UserKind")]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! The conversions from `TypedUuid<Kind>` cannot fail, so validated brands cannot use
+//! `newtype_uuid`:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid, newtype_uuid = "UserKind", validate = "is_not_nil")]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! ## defmt
+//!
+//! The `defmt` feature derives the `defmt::Format` trait for the branded type. Pass `defmt` as an
+//! option to the `Branded` derive macro to enable this feature.
+//!
+//! The crate is `no_std`, and the generated code only depends on `core`. Generic parameters on the
+//! branded type are propagated to all generated impls, so fixed-capacity inner types such as
+//! `heapless::String<N>` can be branded without allocation.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct DeviceId<const N: usize>(heapless::String<N>);
+//! ```
+//!
+//! ## bytemuck
+//!
+//! The `bytemuck` feature implements bytemuck's `Zeroable`, `Pod`, and `TransparentWrapper` traits
+//! for `#[repr(transparent)]` brands. Pass `bytemuck` as an option to the `Branded` derive macro to
+//! enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(bytemuck)]
+//! #[repr(transparent)]
+//! pub struct EntityId(u64);
+//!
+//! let ids: &[EntityId] = bytemuck::cast_slice(index);
+//! ```
+//!
+//! Casting from bytes skips validation, so validated brands cannot use `bytemuck`:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(bytemuck, range = "1..")]
+//! #[repr(transparent)]
+//! pub struct EntityId(u64);
+//! ```
+//!
+//! ## Kani
+//!
+//! The `verify` feature adds Kani contracts under `cfg(kani)` to the constructors of validated
+//! brands: `try_new` ensures that its values pass validation, and the unchecked `new` constructor
+//! requires that its argument does.
+//!
+//! ## Polars
+//!
+//! The `polars` feature generates `into_series` and `try_from_series` functions converting between
+//! columns of branded values and polars `Series`, as well as conversions between single branded
+//! values and `AnyValue`. Pass `polars` as an option to the `Branded` derive macro to enable this
+//! feature. See the `branded::polars` module for the supported inner types.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(polars)]
+//! pub struct OrderId(u64);
+//! ```
+//!
+//! ## InfluxDB
+//!
+//! The `influxdb` feature implements `From<Brand> for influxdb::Type`, so branded values can be
+//! used directly as tag and field values in `WriteQuery`. Pass `influxdb` as an option to the
+//! `Branded` derive macro to enable this feature. See the `branded::influxdb` module for the
+//! supported inner types.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(influxdb)]
+//! pub struct SensorId(uuid::Uuid);
+//! ```
+//!
+//! ## tonic
+//!
+//! The `tonic` feature generates conversions between the branded type and ASCII gRPC metadata
+//! values, so that tenant and request IDs can travel through metadata as branded values. Pass
+//! `tonic` as an option to the `Branded` derive macro to enable this feature. See the
+//! `branded::tonic` module for the conversions and an interceptor.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(tonic)]
+//! pub struct TenantId(uuid::Uuid);
+//! ```
+//!
+//! ## okapi
+//!
+//! The `okapi` feature derives `okapi::schemars::JsonSchema` for the branded type, with the schema
+//! of the inner type, so branded parameters and fields appear in rocket_okapi-generated OpenAPI
+//! documents. Pass `okapi` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(okapi)]
 //! pub struct UserId(String);
 //! ```
 //!
-//! ## SQLx
+//! ## axum
 //!
-//! The `sqlx` feature derives the `Type`, `Encode`, and `Decode` traits for the branded type. Pass
-//! `sqlx` as an option to the `Branded` derive macro to enable this feature.
+//! The `axum` feature provides the `branded::axum::IdPath` extractor, which parses a path
+//! parameter into a brand and rejects invalid values with a `400 Bad Request` naming the brand.
 //!
+//! ```ignore
+//! use branded::axum::IdPath;
+//!
+//! async fn get_user(IdPath(user_id): IdPath<UserId>) -> String {
+//!     format!("user {user_id}")
+//! }
 //! ```
+//!
+//! ## aide
+//!
+//! The `aide` feature derives `schemars::JsonSchema` for the branded type, with the schema of the
+//! inner type, so branded parameters document themselves in aide-generated specs. Pass `aide` as an
+//! option to the `Branded` derive macro to enable this feature. It also enables the `axum` feature
+//! and implements `aide::OperationInput` for `IdPath`.
+//!
+//! ```ignore
 //! use branded::Branded;
 //!
 //! #[derive(Branded)]
-//! #[branded(sqlx)]
+//! #[branded(aide)]
+//! pub struct UserId(uuid::Uuid);
+//! ```
+//!
+//! Pass `example = "..."` and `schema_format = "..."` along with the `okapi` or `aide` option to
+//! add an example and a format to the schema of the branded type.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(aide, example = "018f3a2b-7c1d-7e4f-9a0b-1c2d3e4f5a6b", schema_format = "uuid")]
 //! pub struct UserId(String);
 //! ```
 //!
-//! ## UUID
+//! ## cynic
 //!
-//! The `uuid` feature exposes `nil()` and `new_v4()` methods on the branded type. Pass `uuid` as an
-//! option to the `Branded` derive macro to enable this feature.
+//! The `cynic` feature implements cynic's scalar traits for the branded type, so branded IDs can be
+//! used directly in cynic query fragments and variables. Pass `cynic = "schema::Scalar"` as an
+//! option to the `Branded` derive macro, naming the scalar in the cynic schema module. The option
+//! also derives the serde traits, since cynic scalars are (de)serialized with serde.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(cynic = "schema::ID")]
+//! pub struct UserId(String);
+//! ```
+//!
+//! ## Prometheus
 //!
+//! The `prometheus` feature implements prometheus-client's `EncodeLabelValue` trait for the branded
+//! type, so branded IDs can be used as metric label values. Pass `prometheus` as an option to the
+//! `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(prometheus)]
+//! pub struct Region(String);
 //! ```
+//!
+//! ## MySQL
+//!
+//! The `mysql` feature implements the `mysql_common` value conversions used by the `mysql` and
+//! `mysql_async` drivers, so branded IDs can be bound as parameters and read from rows. Pass
+//! `mysql` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
 //! use branded::Branded;
 //!
 //! #[derive(Branded)]
-//! #[branded(uuid)]
+//! #[branded(mysql)]
+//! pub struct UserId(u64);
+//! ```
+//!
+//! ## Gel
+//!
+//! The `gel` feature implements the `Queryable` and `ScalarArg` traits of the Gel (formerly EdgeDB)
+//! client for the branded type, so branded values can be read from and passed to EdgeQL queries.
+//! Pass `gel` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(gel)]
 //! pub struct UserId(uuid::Uuid);
 //! ```
+//!
+//! ## Sentry
+//!
+//! The `sentry` feature attaches branded IDs to Sentry scopes as tags and contexts, keyed by the
+//! snake_case name of the brand. Pass `sentry` as an option to the `Branded` derive macro to enable
+//! this feature, or `sentry(pii)` to redact the value. See the `branded::sentry` module for the
+//! `sentry_tag!` and `sentry_context!` macros.
+//!
+//! ```ignore
+//! use branded::{sentry_tag, Branded};
+//!
+//! #[derive(Branded)]
+//! #[branded(sentry)]
+//! pub struct UserId(u64);
+//!
+//! sentry_tag!(user_id);
+//! ```
+//!
+//! ## tracing
+//!
+//! The `tracing` feature provides the `record_ids!` macro, which records branded IDs as fields
+//! of a tracing span. See the `branded::tracing` module for details.
+//!
+//! ## zbus
+//!
+//! The `zbus` feature implements `zvariant::Type` for the branded type with the signature of the
+//! inner type, along with the serde traits, so branded values can cross D-Bus interfaces. Pass
+//! `zbus` as an option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(zbus)]
+//! pub struct SessionId(u64);
+//! ```
+//!
+//! ## Rustler
+//!
+//! The `rustler` feature implements rustler's `Encoder` and `Decoder` traits for the branded type,
+//! so branded IDs map to the Elixir terms of the inner type at NIF boundaries. Pass `rustler` as an
+//! option to the `Branded` derive macro to enable this feature.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(rustler)]
+//! pub struct UserId(u64);
+//!
+//! #[rustler::nif]
+//! fn touch(user_id: UserId) -> UserId { user_id }
+//! ```
+//!
+//! ## Pseudonymization
+//!
+//! The `pseudonymize` feature provides keyed pseudonyms of branded IDs for data exported to
+//! analytics vendors: a truncated HMAC-SHA256 over the brand name and the inner value, rendered as
+//! hex or base64. See the `branded::pseudonymize` module for the single and batch APIs.
+//!
+//! ```ignore
+//! use branded::pseudonymize::pseudonymize;
+//!
+//! let pseudonym = pseudonymize(&user_id, &export_key).to_string();
+//! ```
+//!
+//! ## Bit sets
+//!
+//! The `bitset` feature provides `BrandedBitSet`, a compact set of dense integer brands with
+//! membership, union, and intersection. Pass `bitset` as an option to the `Branded` derive macro to
+//! store the brand in a set. The `roaring` feature backs the set with a roaring bitmap instead.
+//! See the `branded::bitset` module for details.
+//!
+//! ```ignore
+//! use branded::bitset::BrandedBitSet;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(bitset)]
+//! pub struct OrderId(u32);
+//!
+//! let orders: BrandedBitSet<OrderId> = order_ids.into_iter().collect();
+//! ```
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "bitset")]
+pub mod bitset;
+pub mod cast;
+pub mod checksum;
+#[cfg(feature = "compact_str")]
+pub mod compact_str;
+#[cfg(feature = "reflect")]
+pub mod constraints;
+#[cfg(feature = "alloc")]
+pub mod context;
+pub mod diff;
+pub mod envelope;
+pub mod error;
+pub mod external;
+pub mod fmt;
+pub mod format;
+#[cfg(feature = "gel")]
+pub mod gel;
+pub mod id;
+#[cfg(feature = "influxdb")]
+pub mod influxdb;
+#[cfg(feature = "mysql")]
+pub mod mysql;
+pub mod notify;
+pub mod parser;
+pub mod path;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "pseudonymize")]
+pub mod pseudonymize;
+#[cfg(feature = "regex")]
+pub mod regex;
+pub mod same_brand;
+#[doc(hidden)]
+pub mod selftest;
+#[cfg(feature = "sentry")]
+pub mod sentry;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod shard;
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
+#[cfg(feature = "uuid")]
+pub mod testing;
+pub mod time_bucket;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+pub mod url;
+#[cfg(feature = "zbus")]
+pub mod zbus;
 
 pub use branded_derive::Branded;
+pub use error::{Error, ErrorKind};
 
 /// A trait for types that are a brand of some inner type.
 ///
@@ -75,9 +1408,592 @@ pub trait Branded {
     /// any type is allowed.
     type Inner;
 
+    /// The name of the brand, such as `UserId`.
+    ///
+    /// The derive macro uses the name of the type, unless it is overridden with the `brand`
+    /// option. Errors, the alternate `Display` form, and the generic helpers of this crate refer to
+    /// the brand by this name.
+    const BRAND: &'static str;
+
+    /// The name of database columns holding the brand, such as `user_id`.
+    ///
+    /// The derive macro uses the brand name in snake case, unless it is overridden with the
+    /// `column` option, so that query building code can refer to the column through the brand.
+    const COLUMN: &'static str;
+
+    /// The constraints declared on the brand, such as the `len` and `pattern` options, with the
+    /// `reflect` feature.
+    ///
+    /// The derive macro records the declared constraints, and brands without constraints use
+    /// [`Constraints::NONE`](constraints::Constraints::NONE).
+    #[cfg(feature = "reflect")]
+    const CONSTRAINTS: constraints::Constraints = constraints::Constraints::NONE;
+
     /// Get a reference to the inner type.
     fn inner(&self) -> &Self::Inner;
 
     /// Convert the branded type to the inner type.
     fn into_inner(self) -> Self::Inner;
 }
+
+/// A brand that can be constructed from any value of its inner type, so that generic code can
+/// create brands.
+///
+/// The derive macro implements this trait for brands with a public constructor and without
+/// validation, since it would otherwise bypass `try_new` or the visibility of the constructor.
+pub trait FromInner: Branded {
+    /// Construct the branded type from the inner type, like the `new` constructor.
+    fn from_inner(inner: Self::Inner) -> Self;
+
+    /// Transform the inner value, such as to normalize it.
+    fn map(self, f: impl FnOnce(Self::Inner) -> Self::Inner) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_inner(f(self.into_inner()))
+    }
+
+    /// Transform the inner value with a fallible function.
+    fn try_map<E>(self, f: impl FnOnce(Self::Inner) -> Result<Self::Inner, E>) -> Result<Self, E>
+    where
+        Self: Sized,
+    {
+        f(self.into_inner()).map(Self::from_inner)
+    }
+}
+
+/// An object-safe view of a brand, for collections of brands of different kinds such as
+/// `Vec<Box<dyn DynBranded>>`.
+///
+/// This trait is implemented for every brand that implements `Debug` and `Display`, and whose
+/// inner type implements `Display`.
+pub trait DynBranded: core::fmt::Debug + core::fmt::Display {
+    /// Get the name of the brand, as in [`Branded::BRAND`].
+    fn brand_name(&self) -> &'static str;
+
+    /// Format the inner value as a string.
+    #[cfg(feature = "alloc")]
+    fn inner_string(&self) -> alloc::string::String;
+}
+
+impl<B> DynBranded for B
+where
+    B: Branded + core::fmt::Debug + core::fmt::Display,
+    B::Inner: core::fmt::Display,
+{
+    fn brand_name(&self) -> &'static str { B::BRAND }
+
+    #[cfg(feature = "alloc")]
+    fn inner_string(&self) -> alloc::string::String {
+        alloc::string::ToString::to_string(self.inner())
+    }
+}
+
+/// Parse a string into any branded type.
+///
+/// This is a thin wrapper around [`str::parse`] that is bounded on [`Branded`], so that generic
+/// request-handling code can parse brands uniformly without repeating the bounds.
+pub fn parse<B>(s: &str) -> Result<B, B::Err>
+where
+    B: Branded + core::str::FromStr,
+{
+    s.parse()
+}
+
+/// Extension trait for parsing strings into branded types.
+///
+/// This trait is implemented for [`str`], and is the method-call counterpart of [`parse`].
+pub trait TryParseBranded {
+    /// Parse the string into the branded type `B`.
+    fn parse_branded<B>(&self) -> Result<B, B::Err>
+    where
+        B: Branded + core::str::FromStr;
+}
+
+impl TryParseBranded for str {
+    fn parse_branded<B>(&self) -> Result<B, B::Err>
+    where
+        B: Branded + core::str::FromStr,
+    {
+        parse(self)
+    }
+}
+
+/// Assert at compile time that an expression is of a given brand, or that several expressions share
+/// the same brand.
+///
+/// The expressions are never evaluated, so the macro is free to use in generic code and inside
+/// other macros where type inference can otherwise hide a mixed-up brand.
+///
+/// ```
+/// use branded::{assert_brand, Branded};
+///
+/// #[derive(Branded)]
+/// pub struct UserId(u32);
+///
+/// let a = UserId::new(1);
+/// let b = UserId::new(2);
+/// assert_brand!(a => UserId);
+/// assert_brand!(a, b);
+/// ```
+///
+/// Mixing up brands is a compile error:
+///
+/// ```compile_fail
+/// use branded::{assert_brand, Branded};
+///
+/// #[derive(Branded)]
+/// pub struct UserId(u32);
+///
+/// #[derive(Branded)]
+/// pub struct OrderId(u32);
+///
+/// let user = UserId::new(1);
+/// let order = OrderId::new(1);
+/// assert_brand!(user, order);
+/// ```
+#[macro_export]
+macro_rules! assert_brand {
+    ($value:expr => $brand:ty $(,)?) => {{
+        let _ = || {
+            $crate::__private::expect_brand::<$brand>(&$value);
+        };
+    }};
+    ($first:expr $(, $rest:expr)+ $(,)?) => {{
+        let _ = || {
+            $( $crate::__private::expect_same_brand(&$first, &$rest); )+
+        };
+    }};
+}
+
+/// Assert that two slices of brands are equal, panicking with a [`diff::Diff`] of the positions
+/// that differ.
+///
+/// ```should_panic
+/// use branded::Branded;
+///
+/// #[derive(Branded)]
+/// pub struct UserId(u32);
+///
+/// branded::assert_brands_eq!([UserId::new(1), UserId::new(2)], [UserId::new(1), UserId::new(3)]);
+/// ```
+#[macro_export]
+macro_rules! assert_brands_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let diff = $crate::diff::Diff::new(&left[..], &right[..]);
+                if !diff.is_empty() {
+                    ::core::panic!("brands are not equal:\n{}", diff);
+                }
+            }
+        }
+    };
+}
+
+/// Construct a constant value of a brand from a string literal, checked at compile time.
+///
+/// The brand must use the `const_new` option, and its inner type must be an integer, `&'static
+/// str`, or with the `uuid` feature, `Uuid`. A literal that does not parse as the inner type is a
+/// compile error. Validation options, such as `validate` or `pattern`, cannot be checked at
+/// compile time, so validated brands are not supported.
+///
+/// ```
+/// use branded::{brand, Branded};
+///
+/// #[derive(Branded)]
+/// #[branded(const_new)]
+/// pub struct UserId(u64);
+///
+/// pub const SYSTEM_USER: UserId = brand!(UserId, "42");
+/// assert_eq!(SYSTEM_USER.into_inner(), 42);
+/// ```
+///
+/// An invalid literal fails to compile:
+///
+/// ```compile_fail
+/// use branded::{brand, Branded};
+///
+/// #[derive(Branded)]
+/// #[branded(const_new)]
+/// pub struct UserId(u8);
+///
+/// pub const SYSTEM_USER: UserId = brand!(UserId, "256");
+/// ```
+#[macro_export]
+macro_rules! brand {
+    ($brand:ty, $literal:literal $(,)?) => {{
+        const VALUE: $brand = <$brand>::__from_literal($literal);
+        VALUE
+    }};
+}
+
+/// Declare many brands at once, each with its inner type and derive options.
+///
+/// Each entry expands to a `#[derive(Branded)]` newtype struct, with the options in brackets
+/// passed to `#[branded(...)]`. Entries may have attributes, such as doc comments, and a
+/// visibility.
+///
+/// ```
+/// branded::declare! {
+///     /// The ID of a user.
+///     pub UserId: u64 [const_new];
+///     pub OrderId: u64 [const_new];
+///     pub Sku: String;
+/// }
+///
+/// const ROOT: UserId = UserId::new(0);
+/// assert_eq!(OrderId::new(7).to_string(), "7");
+/// ```
+#[macro_export]
+macro_rules! declare {
+    ($($(#[$attr:meta])* $vis:vis $brand:ident: $inner:ty $([$($option:tt)*])?;)*) => {
+        $(
+            $(#[$attr])*
+            #[derive($crate::Branded)]
+            $(#[branded($($option)*)])?
+            $vis struct $brand($inner);
+        )*
+    };
+}
+
+/// Build a URL path, percent-encoding each argument as a single path segment.
+///
+/// The arguments must implement [`url::PathSegment`], which brands marked as `secret` do not, and
+/// are interpolated into the `{}` placeholders of the template. Placeholders that name a variable,
+/// such as `{user_id}`, are not encoded, so only positional placeholders should be used. See the
+/// [`url`] module for details.
+///
+/// ```
+/// use branded::Branded;
+///
+/// #[derive(Branded)]
+/// pub struct OrgId(String);
+///
+/// let org_id = OrgId::new("acme corp".to_owned());
+/// assert_eq!(branded::path!("/orgs/{}/members?page={}", org_id, 2), "/orgs/acme%20corp/members?page=2");
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! path {
+    ($template:literal $(, $arg:expr)* $(,)?) => {
+        $crate::__private::format!($template $(, $crate::url::encode(&$arg))*)
+    };
+}
+
+/// Generate the `for_test` constructor of a UUID brand, which is only available with the `uuid`
+/// feature, since it derives the UUID with this crate's `uuid` dependency.
+#[cfg(feature = "uuid")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __uuid_for_test {
+    () => {
+        /// Derive a deterministic UUID from `name` for test fixtures, in a namespace of its own per
+        /// brand.
+        pub fn for_test(name: &str) -> Self {
+            Self($crate::testing::uuid_for_test(<Self as $crate::Branded>::BRAND, name))
+        }
+    };
+}
+
+#[cfg(not(feature = "uuid"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __uuid_for_test {
+    () => {};
+}
+
+/// Generate the impls of a brand that do not depend on its options, given the brand as
+/// `{Name [impl generics] [type generics] [where predicates] [inner type]}` followed by the names
+/// of the impls.
+///
+/// The `Branded` derive macro expands to a single invocation of this macro instead of an impl per
+/// trait, which keeps the expansion of crates with hundreds of brands small. The impls of traits
+/// of the inner type are bounded on the given inner type implementing the trait.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_std {
+    (@Clone {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::clone::Clone for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::clone::Clone,
+        {
+            fn clone(&self) -> Self { Self($crate::Branded::inner(self).clone()) }
+        }
+    };
+    (@Copy {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::marker::Copy for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::marker::Copy,
+        {
+        }
+    };
+    (@PartialEq {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::cmp::PartialEq for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::cmp::PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                ::core::cmp::PartialEq::eq($crate::Branded::inner(self), $crate::Branded::inner(other))
+            }
+        }
+    };
+    (@Eq {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::cmp::Eq for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::cmp::Eq,
+        {
+        }
+    };
+    (@PartialOrd {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::cmp::PartialOrd for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::cmp::PartialOrd,
+        {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::cmp::PartialOrd::partial_cmp(
+                    $crate::Branded::inner(self),
+                    $crate::Branded::inner(other),
+                )
+            }
+        }
+    };
+    (@Ord {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::cmp::Ord for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::cmp::Ord,
+        {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                ::core::cmp::Ord::cmp($crate::Branded::inner(self), $crate::Branded::inner(other))
+            }
+        }
+    };
+    (@Hash {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::hash::Hash for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::hash::Hash,
+        {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash($crate::Branded::inner(self), state);
+            }
+        }
+    };
+    (@AsRef ($($target:tt)*) {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::convert::AsRef<$($target)*> for $brand $($ty)*
+        where
+            $($where)*
+        {
+            fn as_ref(&self) -> &$($target)* { $crate::Branded::inner(self) }
+        }
+    };
+    (@Borrow ($($target:tt)*) {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::borrow::Borrow<$($target)*> for $brand $($ty)*
+        where
+            $($where)*
+        {
+            fn borrow(&self) -> &$($target)* { $crate::Branded::inner(self) }
+        }
+    };
+    (@Debug {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::fmt::Debug for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(::core::stringify!($brand)).field($crate::Branded::inner(self)).finish()
+            }
+        }
+    };
+    (@Default {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::default::Default for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::default::Default,
+        {
+            fn default() -> Self { Self(::core::default::Default::default()) }
+        }
+    };
+    (@Display {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::fmt::Display for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::fmt::Display,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if f.alternate() {
+                    f.write_str(<Self as $crate::Branded>::BRAND)?;
+                    f.write_str(":")?;
+                }
+                ::core::fmt::Display::fmt($crate::Branded::inner(self), f)
+            }
+        }
+    };
+    (@FromStr {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::str::FromStr for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::str::FromStr,
+        {
+            type Err = $crate::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                $crate::__impl_std!(@parse input).map(Self)
+            }
+        }
+    };
+    (@FromStr (try_new) {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* ::core::str::FromStr for $brand $($ty)*
+        where
+            $($where)*
+            $($inner)*: ::core::str::FromStr,
+        {
+            type Err = $crate::Error;
+
+            fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+                $crate::__impl_std!(@parse input)
+                    .and_then(|inner| Self::try_new(inner).map_err(|error| error.with_input(input)))
+            }
+        }
+    };
+    (@parse $input:ident) => {
+        $input.parse::<<Self as $crate::Branded>::Inner>().map_err(|_| {
+            $crate::Error::new(<Self as $crate::Branded>::BRAND, $crate::ErrorKind::Parse)
+                .with_input($input)
+        })
+    };
+    (@WriteTo {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* $brand $($ty)*
+        where
+            $($where)*
+            for<'__branded> Self: ::core::fmt::Display,
+        {
+            /// Format the value into `buf` without an intermediate `String`.
+            pub fn write_to(&self, buf: &mut impl ::core::fmt::Write) -> ::core::fmt::Result {
+                ::core::write!(buf, "{}", self)
+            }
+        }
+    };
+    (@PathSegment {$brand:ident [$($impl:tt)*] [$($ty:tt)*] [$($where:tt)*] [$($inner:tt)*]}) => {
+        impl $($impl)* $crate::url::PathSegment for $brand $($ty)*
+        where
+            $($where)*
+            for<'__branded> Self: ::core::fmt::Display,
+        {
+        }
+    };
+    ($brand:tt $($impl:ident $(($($arg:tt)*))?)*) => {
+        $($crate::__impl_std!(@$impl $(($($arg)*))? $brand);)*
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::Branded;
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::format;
+    #[cfg(feature = "alloc")]
+    pub use alloc::string::String;
+
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+
+    pub fn expect_brand<B: Branded>(_: &B) {}
+
+    /// Parse an unsigned decimal integer literal at compile time, for the `brand!` macro.
+    pub const fn parse_unsigned_literal(literal: &str) -> u128 {
+        parse_digits(literal.as_bytes(), 0)
+    }
+
+    /// Parse a signed decimal integer literal at compile time, for the `brand!` macro.
+    pub const fn parse_signed_literal(literal: &str) -> i128 {
+        let bytes = literal.as_bytes();
+        let negative = !bytes.is_empty() && bytes[0] == b'-';
+        let magnitude = parse_digits(bytes, negative as usize);
+        assert!(magnitude <= i128::MAX as u128 + negative as u128, "integer literal out of range");
+        if negative {
+            (magnitude as i128).wrapping_neg()
+        } else {
+            magnitude as i128
+        }
+    }
+
+    const fn parse_digits(bytes: &[u8], start: usize) -> u128 {
+        assert!(bytes.len() > start, "empty integer literal");
+        let mut value: u128 = 0;
+        let mut i = start;
+        while i < bytes.len() {
+            assert!(bytes[i].is_ascii_digit(), "invalid integer literal");
+            value = match value.checked_mul(10) {
+                Some(value) => value,
+                None => panic!("integer literal out of range"),
+            };
+            value = match value.checked_add((bytes[i] - b'0') as u128) {
+                Some(value) => value,
+                None => panic!("integer literal out of range"),
+            };
+            i += 1;
+        }
+        value
+    }
+
+    pub fn expect_same_brand<B: Branded>(_: &B, _: &B) {}
+
+    /// A value formatted with the `render` function of its brand, for the generated Debug
+    /// implementation.
+    pub struct Rendered<'a, T: ?Sized>(
+        pub &'a T,
+        pub fn(&T, &mut core::fmt::Formatter<'_>) -> core::fmt::Result,
+    );
+
+    impl<T: ?Sized> core::fmt::Debug for Rendered<'_, T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { (self.1)(self.0, f) }
+    }
+
+    /// The overridden generator of a brand, for the `override_generator` method generated with the
+    /// `testing` feature. It only needs atomics, so it also works without `std`, but it applies to
+    /// the whole process instead of the current thread.
+    #[cfg(feature = "testing")]
+    pub struct GeneratorOverride<T> {
+        generator: core::sync::atomic::AtomicPtr<()>,
+        marker: core::marker::PhantomData<fn() -> T>,
+    }
+
+    #[cfg(feature = "testing")]
+    impl<T> GeneratorOverride<T> {
+        pub const fn new() -> Self {
+            Self {
+                generator: core::sync::atomic::AtomicPtr::new(core::ptr::null_mut()),
+                marker: core::marker::PhantomData,
+            }
+        }
+
+        pub fn get(&self) -> Option<fn() -> T> {
+            let generator = self.generator.load(core::sync::atomic::Ordering::Acquire);
+            if generator.is_null() {
+                return None;
+            }
+            // SAFETY: The only non-null pointers stored are `fn() -> T` pointers cast by `set`.
+            Some(unsafe { core::mem::transmute::<*mut (), fn() -> T>(generator) })
+        }
+
+        pub fn set(&self, generator: Option<fn() -> T>) {
+            let generator =
+                generator.map_or(core::ptr::null_mut(), |generator| generator as *mut ());
+            self.generator.store(generator, core::sync::atomic::Ordering::Release);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    impl<T> Default for GeneratorOverride<T> {
+        fn default() -> Self { Self::new() }
+    }
+}
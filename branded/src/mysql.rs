@@ -0,0 +1,44 @@
+//! MySQL integration for branded types.
+//!
+//! The `mysql` option on the `Branded` derive macro implements the value conversions of
+//! `mysql_common`, the crate underlying both the `mysql` and `mysql_async` drivers:
+//!
+//! - `FromValue for Brand`, which reads the value as the inner type through [`Intermediate`].
+//! - `From<Brand> for Value`, so branded values can be bound as query parameters.
+//!
+//! ```ignore
+//! use branded::Branded;
+//! use mysql_async::prelude::*;
+//!
+//! #[derive(Branded)]
+//! #[branded(mysql)]
+//! pub struct UserId(u64);
+//!
+//! let names: Vec<(UserId, String)> = "SELECT id, name FROM users".fetch(&mut conn).await?;
+//! ```
+
+pub use mysql_common::value::convert::{FromValue, FromValueError};
+pub use mysql_common::value::Value;
+
+use crate::Branded;
+
+/// The intermediate result of converting a MySQL value into the brand `B`.
+///
+/// This wraps the intermediate result of the inner type, and is converted into the brand by the
+/// implementation generated by the `mysql` option.
+pub struct Intermediate<B>(pub <B::Inner as FromValue>::Intermediate)
+where
+    B: Branded,
+    B::Inner: FromValue;
+
+impl<B> TryFrom<Value> for Intermediate<B>
+where
+    B: Branded,
+    B::Inner: FromValue,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        <B::Inner as FromValue>::Intermediate::try_from(value).map(Self)
+    }
+}
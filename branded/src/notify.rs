@@ -0,0 +1,60 @@
+//! Payloads of Postgres `LISTEN`/`NOTIFY` change notifications carrying a branded ID.
+//!
+//! A payload is the brand name and the value, such as `UserId:42`, so that a listener on a channel
+//! shared by several brands, or subscribed to the wrong channel, rejects the IDs of other brands
+//! instead of parsing them as its own. [`Payload`] writes a payload with `Display`, and [`decode`]
+//! parses one, validating the value with the `FromStr` implementation of the brand.
+//!
+//! ```
+//! use branded::notify::{self, Payload};
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! pub struct OrderId(u64);
+//!
+//! // Sent with `SELECT pg_notify('changes', $1)`.
+//! let payload = Payload::new(&UserId::new(42)).to_string();
+//! assert_eq!(payload, "UserId:42");
+//! assert_eq!(notify::decode::<UserId>(&payload).unwrap(), UserId::new(42));
+//! assert!(notify::decode::<OrderId>(&payload).is_err());
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt::{self, Display};
+use core::str::FromStr;
+
+use crate::{Branded, Error, ErrorKind};
+
+/// The payload of a notification about `id`, written with `Display`.
+pub struct Payload<'a, B>(&'a B);
+
+impl<'a, B> Payload<'a, B> {
+    /// Create the payload of a notification about `id`.
+    pub fn new(id: &'a B) -> Self { Self(id) }
+}
+
+impl<B: Branded + Display> Display for Payload<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", B::BRAND, self.0)
+    }
+}
+
+/// Encode `id` as the payload of a notification.
+#[cfg(feature = "alloc")]
+pub fn encode<B: Branded + Display>(id: &B) -> String { Payload::new(id).to_string() }
+
+/// Decode the ID in the payload of a notification.
+///
+/// Payloads of other brands are rejected with [`ErrorKind::WrongPrefix`], and the value is parsed
+/// and validated with the `FromStr` implementation of the brand.
+pub fn decode<B: Branded + FromStr<Err = Error>>(payload: &str) -> Result<B, Error> {
+    payload
+        .strip_prefix(B::BRAND)
+        .and_then(|value| value.strip_prefix(':'))
+        .ok_or_else(|| Error::new(B::BRAND, ErrorKind::WrongPrefix(B::BRAND)).with_input(payload))?
+        .parse()
+}
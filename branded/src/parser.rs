@@ -0,0 +1,173 @@
+//! An incremental parser for prefixed and checksummed IDs in byte slices.
+//!
+//! [`Parser`] consumes an ID from a byte slice piece by piece, without allocating, and reports the
+//! byte offset of the first invalid byte on error. It is meant for code that inspects IDs in raw
+//! request streams, such as proxies, where the input is not known to be UTF-8.
+//!
+//! ```
+//! use branded::checksum::Luhn;
+//! use branded::parser::Parser;
+//! use branded::ErrorKind;
+//!
+//! let mut parser = Parser::new("AccountId", b"acct_79927398713");
+//! parser.expect("acct_")?;
+//! parser.split_check::<Luhn>()?;
+//! let account: u64 = parser.parse(None)?;
+//! parser.finish()?;
+//! assert_eq!(account, 7992739871);
+//!
+//! let mut parser = Parser::new("AccountId", b"user_79927398713");
+//! let error = parser.expect("acct_").unwrap_err();
+//! assert_eq!(error.kind(), ErrorKind::WrongPrefix("acct_"));
+//! assert_eq!(error.offset(), 0);
+//! # Ok::<(), branded::parser::ParseError>(())
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::checksum::Algorithm;
+use crate::{Error, ErrorKind};
+
+/// The error returned by [`Parser`], with the byte offset in the input where parsing failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    error: Error,
+    offset: usize,
+}
+
+impl ParseError {
+    /// Get the name of the brand.
+    pub fn brand(&self) -> &'static str { self.error.brand() }
+
+    /// Get the reason the input could not be parsed.
+    pub fn kind(&self) -> ErrorKind { self.error.kind() }
+
+    /// Get the byte offset in the input where parsing failed.
+    pub fn offset(&self) -> usize { self.offset }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.error, self.offset)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self { error.error }
+}
+
+/// An incremental parser for IDs in a byte slice.
+///
+/// Each method consumes a part of the input, and fails without consuming anything.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    brand: &'static str,
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a parser for an ID of the brand named `brand`.
+    pub fn new(brand: &'static str, input: &'a [u8]) -> Self {
+        Self {
+            brand,
+            input,
+            offset: 0,
+        }
+    }
+
+    /// Get the byte offset of the remaining input in the original input.
+    pub fn offset(&self) -> usize { self.offset }
+
+    /// Get the remaining input.
+    pub fn remaining(&self) -> &'a [u8] { self.input }
+
+    /// Consume the literal `prefix` from the start of the remaining input.
+    pub fn expect(&mut self, prefix: &'static str) -> Result<(), ParseError> {
+        match self.input.strip_prefix(prefix.as_bytes()) {
+            Some(rest) => {
+                self.advance(rest);
+                Ok(())
+            }
+            None => Err(self.error(ErrorKind::WrongPrefix(prefix), self.offset)),
+        }
+    }
+
+    /// Validate and remove the check character at the end of the remaining input.
+    pub fn split_check<A: Algorithm>(&mut self) -> Result<(), ParseError> {
+        let input = self.utf8(self.input)?;
+        let check =
+            input.chars().next_back().ok_or_else(|| self.error(ErrorKind::Length, self.offset))?;
+        let body = &input[..input.len() - check.len_utf8()];
+        let mut algorithm = A::default();
+        body.chars().for_each(|c| algorithm.update(c));
+        if !algorithm.matches(check) {
+            return Err(self.error(ErrorKind::Checksum, self.offset + body.len()));
+        }
+        self.input = body.as_bytes();
+        Ok(())
+    }
+
+    /// Consume the next component of the remaining input.
+    ///
+    /// The component extends up to the first occurrence of `terminator`, which is not consumed, or
+    /// to the end of the input without a terminator. Empty components are rejected.
+    pub fn component(&mut self, terminator: Option<&'static str>) -> Result<&'a str, ParseError> {
+        let end = match terminator {
+            Some(terminator) => self
+                .input
+                .windows(terminator.len().max(1))
+                .position(|window| window == terminator.as_bytes())
+                .ok_or_else(|| self.error(ErrorKind::WrongPrefix(terminator), self.offset))?,
+            None => self.input.len(),
+        };
+        if end == 0 {
+            return Err(self.error(ErrorKind::Length, self.offset));
+        }
+        let (component, rest) = self.input.split_at(end);
+        let component = self.utf8(component)?;
+        self.advance(rest);
+        Ok(component)
+    }
+
+    /// Consume the next component of the remaining input and parse it, as with
+    /// [`component`](Self::component).
+    pub fn parse<T: FromStr>(&mut self, terminator: Option<&'static str>) -> Result<T, ParseError> {
+        let start = self.offset;
+        let mut parser = self.clone();
+        let value = parser
+            .component(terminator)?
+            .parse()
+            .map_err(|_| self.error(ErrorKind::Parse, start))?;
+        *self = parser;
+        Ok(value)
+    }
+
+    /// Check that the whole input has been consumed.
+    pub fn finish(self) -> Result<(), ParseError> {
+        match self.input {
+            [] => Ok(()),
+            _ => Err(self.error(ErrorKind::Parse, self.offset)),
+        }
+    }
+
+    fn advance(&mut self, rest: &'a [u8]) {
+        self.offset += self.input.len() - rest.len();
+        self.input = rest;
+    }
+
+    fn utf8<'b>(&self, bytes: &'b [u8]) -> Result<&'b str, ParseError> {
+        core::str::from_utf8(bytes)
+            .map_err(|error| self.error(ErrorKind::Parse, self.offset + error.valid_up_to()))
+    }
+
+    fn error(&self, kind: ErrorKind, offset: usize) -> ParseError {
+        ParseError {
+            error: Error::new(self.brand, kind),
+            offset,
+        }
+    }
+}
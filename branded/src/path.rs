@@ -0,0 +1,45 @@
+//! Support for path-structured brands such as `orgs/{OrgId}/projects/{ProjectId}`.
+//!
+//! The `path` option on the `Branded` derive macro generates `Display` and `FromStr`
+//! implementations from a path template for brands whose inner type is a tuple of the components.
+//! The functions in this module are used by the generated parser.
+
+use crate::{Error, ErrorKind};
+
+/// Strip the literal segment `literal` from the start of `input`.
+pub fn strip_literal<'a>(
+    brand: &'static str,
+    input: &'a str,
+    literal: &'static str,
+) -> Result<&'a str, Error> {
+    input.strip_prefix(literal).ok_or(Error::new(brand, ErrorKind::WrongPrefix(literal)))
+}
+
+/// Split the next component from the start of `input`.
+///
+/// The component extends up to the first occurrence of the literal segment `terminator` that
+/// follows it in the template, or to the end of the input if it is the last segment.
+pub fn split_component<'a>(
+    brand: &'static str,
+    input: &'a str,
+    terminator: Option<&'static str>,
+) -> Result<(&'a str, &'a str), Error> {
+    let end = match terminator {
+        Some(terminator) => {
+            input.find(terminator).ok_or(Error::new(brand, ErrorKind::WrongPrefix(terminator)))?
+        }
+        None => input.len(),
+    };
+    match input.split_at(end) {
+        ("", _) => Err(Error::new(brand, ErrorKind::Length)),
+        split => Ok(split),
+    }
+}
+
+/// Check that the whole input has been consumed.
+pub fn finish(brand: &'static str, input: &str) -> Result<(), Error> {
+    match input {
+        "" => Ok(()),
+        _ => Err(Error::new(brand, ErrorKind::Parse)),
+    }
+}
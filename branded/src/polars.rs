@@ -0,0 +1,140 @@
+//! Polars integration for branded types.
+//!
+//! The `polars` option on the `Branded` derive macro generates conversions between columns of
+//! branded values and polars `Series`, as well as between single branded values and `AnyValue`.
+//! The conversions are implemented in terms of the [`PolarsInner`] trait, which is implemented for
+//! the inner types that have a natural polars representation.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use polars::prelude::{
+    polars_bail, polars_ensure, AnyValue, IntoSeries, NamedFromOwned, NewChunkedArray, PlSmallStr,
+    PolarsResult, Series, StringChunked,
+};
+
+/// Inner types that can be stored in a polars column.
+///
+/// Integers are stored in their native integer column type, strings are stored as `String`
+/// columns, and UUIDs are stored as 16-byte `Binary` columns. Reading a column back fails if the
+/// column has a different data type, or if it contains null values.
+pub trait PolarsInner: Sized {
+    /// Build a series from a column of values.
+    fn into_series(name: PlSmallStr, values: Vec<Self>) -> Series;
+
+    /// Read a column of values from a series.
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>>;
+
+    /// Convert a single value into a polars value.
+    fn into_any_value(self) -> AnyValue<'static>;
+
+    /// Convert a single polars value into a value.
+    fn from_any_value(value: AnyValue<'_>) -> PolarsResult<Self>;
+}
+
+macro_rules! impl_polars_inner_numeric {
+    ($($ty:ty => $accessor:ident, $variant:ident;)*) => {
+        $(
+            impl PolarsInner for $ty {
+                fn into_series(name: PlSmallStr, values: Vec<Self>) -> Series {
+                    Series::from_vec(name, values)
+                }
+
+                fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+                    let chunked = series.$accessor()?;
+                    polars_ensure!(
+                        chunked.null_count() == 0,
+                        ComputeError: "column `{}` contains null values", series.name()
+                    );
+                    Ok(chunked.into_no_null_iter().collect())
+                }
+
+                fn into_any_value(self) -> AnyValue<'static> { AnyValue::$variant(self) }
+
+                fn from_any_value(value: AnyValue<'_>) -> PolarsResult<Self> {
+                    match value {
+                        AnyValue::$variant(value) => Ok(value),
+                        other => polars_bail!(
+                            SchemaMismatch: "expected a {} value, got {}",
+                            stringify!($ty), other.dtype()
+                        ),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_polars_inner_numeric! {
+    u32 => u32, UInt32;
+    u64 => u64, UInt64;
+    i32 => i32, Int32;
+    i64 => i64, Int64;
+}
+
+impl PolarsInner for String {
+    fn into_series(name: PlSmallStr, values: Vec<Self>) -> Series {
+        StringChunked::from_iter_values(name, values.into_iter()).into_series()
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        let chunked = series.str()?;
+        polars_ensure!(
+            chunked.null_count() == 0,
+            ComputeError: "column `{}` contains null values", series.name()
+        );
+        Ok(chunked.into_no_null_iter().map(ToString::to_string).collect())
+    }
+
+    fn into_any_value(self) -> AnyValue<'static> { AnyValue::StringOwned(self.into()) }
+
+    fn from_any_value(value: AnyValue<'_>) -> PolarsResult<Self> {
+        match value {
+            AnyValue::String(value) => Ok(value.to_string()),
+            AnyValue::StringOwned(value) => Ok(value.to_string()),
+            other => polars_bail!(SchemaMismatch: "expected a string value, got {}", other.dtype()),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl PolarsInner for uuid::Uuid {
+    fn into_series(name: PlSmallStr, values: Vec<Self>) -> Series {
+        let values = values.iter().map(uuid::Uuid::as_bytes);
+        polars::prelude::BinaryChunked::from_iter_values(name, values).into_series()
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        let chunked = series.binary()?;
+        polars_ensure!(
+            chunked.null_count() == 0,
+            ComputeError: "column `{}` contains null values", series.name()
+        );
+        chunked.into_no_null_iter().map(uuid_from_bytes).collect()
+    }
+
+    fn into_any_value(self) -> AnyValue<'static> { AnyValue::BinaryOwned(self.as_bytes().to_vec()) }
+
+    fn from_any_value(value: AnyValue<'_>) -> PolarsResult<Self> {
+        match value {
+            AnyValue::Binary(value) => uuid_from_bytes(value),
+            AnyValue::BinaryOwned(value) => uuid_from_bytes(&value),
+            other => polars_bail!(SchemaMismatch: "expected a binary value, got {}", other.dtype()),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+fn uuid_from_bytes(bytes: &[u8]) -> PolarsResult<uuid::Uuid> {
+    match uuid::Uuid::from_slice(bytes) {
+        Ok(uuid) => Ok(uuid),
+        Err(_) => polars_bail!(ComputeError: "expected 16 bytes for a UUID, got {}", bytes.len()),
+    }
+}
+
+/// Convert the validation error of a brand read from polars into a polars error.
+///
+/// This is used by the conversions generated by the `polars` option for validated brands.
+pub fn validation_error(error: crate::Error) -> polars::prelude::PolarsError {
+    polars::prelude::PolarsError::ComputeError(error.to_string().into())
+}
@@ -0,0 +1,132 @@
+//! Keyed pseudonymization of branded IDs for analytics exports.
+//!
+//! A pseudonym is an HMAC-SHA256 of the brand name and the `Display` form of the inner value,
+//! truncated to 128 bits. The same ID and key always produce the same pseudonym, so exported rows
+//! can still be joined, while IDs of different brands with the same inner value produce unrelated
+//! pseudonyms. Pseudonyms cannot be reversed without the key.
+//!
+//! ```
+//! use branded::pseudonymize::Pseudonymizer;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! let pseudonymizer = Pseudonymizer::new(b"analytics export key");
+//! let pseudonym = pseudonymizer.pseudonymize(&UserId::new(42));
+//! assert_eq!(pseudonym.to_string().len(), 32);
+//! assert_eq!(pseudonym.base64().to_string().len(), 22);
+//! ```
+
+use core::fmt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::Branded;
+
+/// The number of bytes of the HMAC kept in a pseudonym.
+const PSEUDONYM_LEN: usize = 16;
+
+/// A keyed pseudonym of a branded ID.
+///
+/// `Display` renders the pseudonym as lowercase hex, and [`Pseudonym::base64`] as unpadded
+/// URL-safe base64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pseudonym([u8; PSEUDONYM_LEN]);
+
+impl Pseudonym {
+    /// Get the bytes of the pseudonym.
+    pub fn as_bytes(&self) -> &[u8; PSEUDONYM_LEN] { &self.0 }
+
+    /// Render the pseudonym as unpadded URL-safe base64.
+    pub fn base64(&self) -> impl fmt::Display + '_ { Base64(&self.0) }
+}
+
+impl fmt::Display for Pseudonym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// Bytes rendered as unpadded URL-safe base64.
+struct Base64<'a>(&'a [u8]);
+
+impl fmt::Display for Base64<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        for chunk in self.0.chunks(3) {
+            let bits = chunk.iter().fold(0u32, |bits, &byte| bits << 8 | u32::from(byte))
+                << (8 * (3 - chunk.len()));
+            for i in 0..=chunk.len() {
+                let index = (bits >> (18 - 6 * i)) & 0x3f;
+                fmt::Write::write_char(f, ALPHABET[index as usize] as char)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A writer feeding formatted output into a MAC.
+struct MacWriter<'a>(&'a mut Hmac<Sha256>);
+
+impl fmt::Write for MacWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Pseudonymizes branded IDs with a fixed key.
+///
+/// The key is processed once, so a pseudonymizer is cheaper than [`pseudonymize`] for batches.
+#[derive(Clone)]
+pub struct Pseudonymizer {
+    mac: Hmac<Sha256>,
+}
+
+impl Pseudonymizer {
+    /// Create a pseudonymizer with the secret `key`.
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            mac: Hmac::new_from_slice(key).expect("HMAC accepts keys of any length"),
+        }
+    }
+
+    /// Get the pseudonym of `id`.
+    pub fn pseudonymize<B>(&self, id: &B) -> Pseudonym
+    where
+        B: Branded,
+        B::Inner: fmt::Display,
+    {
+        let mut mac = self.mac.clone();
+        mac.update(B::BRAND.as_bytes());
+        mac.update(&[0]);
+        // Writing to a MAC never fails, unless the inner Display implementation does.
+        let _ = fmt::Write::write_fmt(&mut MacWriter(&mut mac), format_args!("{}", id.inner()));
+        let mut pseudonym = [0; PSEUDONYM_LEN];
+        pseudonym.copy_from_slice(&mac.finalize().into_bytes()[..PSEUDONYM_LEN]);
+        Pseudonym(pseudonym)
+    }
+
+    /// Get the pseudonyms of `ids`, in order.
+    pub fn pseudonymize_all<'a, B>(
+        &'a self,
+        ids: impl IntoIterator<Item = &'a B> + 'a,
+    ) -> impl Iterator<Item = Pseudonym> + 'a
+    where
+        B: Branded + 'a,
+        B::Inner: fmt::Display,
+    {
+        ids.into_iter().map(|id| self.pseudonymize(id))
+    }
+}
+
+/// Get the pseudonym of `id` with the secret `key`.
+pub fn pseudonymize<B>(id: &B, key: &[u8]) -> Pseudonym
+where
+    B: Branded,
+    B::Inner: fmt::Display,
+{
+    Pseudonymizer::new(key).pseudonymize(id)
+}
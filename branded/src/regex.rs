@@ -0,0 +1,60 @@
+//! Regular expression patterns for the `pattern` option of the `Branded` derive macro.
+//!
+//! The derive macro compiles the pattern at compile time with the default limits of `regex`, so
+//! invalid patterns and patterns over the size limit are compile errors, and the generated code
+//! stores it in a [`Pattern`] static, so it is compiled once, on first use.
+//!
+//! ```
+//! use branded::{Branded, ErrorKind};
+//!
+//! #[derive(Branded)]
+//! #[branded(pattern = "^[a-z0-9_-]{3,32}$")]
+//! pub struct Username(String);
+//!
+//! assert!(Username::try_new("alice".to_owned()).is_ok());
+//! assert_eq!("A!".parse::<Username>().unwrap_err().kind(), ErrorKind::Validation);
+//! ```
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(pattern = "\\w{1000}{1000}")]
+//! pub struct Essay(String);
+//! ```
+
+extern crate std;
+
+use ::regex::Regex;
+use std::sync::OnceLock;
+
+/// A regular expression that is compiled on first use.
+pub struct Pattern {
+    source: &'static str,
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl Pattern {
+    /// Create a pattern from the source of a regular expression.
+    pub const fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Get the source of the regular expression.
+    pub fn as_str(&self) -> &'static str { self.source }
+
+    /// Check whether the regular expression matches somewhere in `haystack`.
+    ///
+    /// Nothing matches if the source does not compile, such as with an invalid pattern or one over
+    /// the size limit, so values fail validation instead of panicking. Patterns of the derive macro
+    /// are compiled at compile time.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.regex
+            .get_or_init(|| Regex::new(self.source).ok())
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(haystack))
+    }
+}
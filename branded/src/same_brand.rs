@@ -0,0 +1,66 @@
+//! Bounds for generic code that must only combine values of the same brand.
+//!
+//! Generic utilities that take two branded parameters, such as a join over two collections,
+//! normally accept any pair of brands. Bounding one parameter with [`SameBrand`] turns a mixup of
+//! brands into a compile error, and the helpers in this module compare the two values without
+//! spelling out that they are the same type.
+//!
+//! ```
+//! use branded::same_brand::{self, SameBrand};
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! fn is_owner<A: SameBrand<B> + PartialEq, B: Branded>(actor: &A, owner: &B) -> bool {
+//!     same_brand::eq(actor, owner)
+//! }
+//!
+//! assert!(is_owner(&UserId::new(1), &UserId::new(1)));
+//! ```
+//!
+//! Mixing brands fails to compile:
+//!
+//! ```compile_fail
+//! # use branded::same_brand::{self, SameBrand};
+//! # use branded::Branded;
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! pub struct OrderId(u64);
+//!
+//! fn is_owner<A: SameBrand<B> + PartialEq, B: Branded>(actor: &A, owner: &B) -> bool {
+//!     same_brand::eq(actor, owner)
+//! }
+//!
+//! is_owner(&UserId::new(1), &OrderId::new(1));
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::Branded;
+
+mod sealed {
+    pub trait Sealed<B: ?Sized> {}
+
+    impl<B: crate::Branded> Sealed<B> for B {}
+}
+
+/// Implemented only when `Self` and `B` are the same brand.
+///
+/// This trait is sealed, so it cannot be implemented for different brands.
+pub trait SameBrand<B>: Branded + sealed::Sealed<B> {
+    /// View a reference to `B` as a reference to `Self`.
+    fn from_same_ref(other: &B) -> &Self;
+}
+
+impl<B: Branded> SameBrand<B> for B {
+    fn from_same_ref(other: &B) -> &Self { other }
+}
+
+/// Check whether two values of the same brand are equal.
+pub fn eq<A: SameBrand<B> + PartialEq, B>(a: &A, b: &B) -> bool { a == A::from_same_ref(b) }
+
+/// Compare two values of the same brand.
+pub fn cmp<A: SameBrand<B> + Ord, B>(a: &A, b: &B) -> Ordering { a.cmp(A::from_same_ref(b)) }
@@ -0,0 +1,147 @@
+//! Conformance checks used by the tests generated by the `selftest` option.
+//!
+//! Each check takes a set of sample values of the brand and panics with a descriptive message if
+//! the brand's trait implementations disagree with each other or with the inner type.
+
+use crate::Branded;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Write};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// Check that the samples include at least two distinct values, since the other checks cannot fail
+/// otherwise.
+pub fn check_samples<B: PartialEq + Debug>(samples: &[B]) {
+    assert!(
+        samples.iter().any(|sample| sample != &samples[0]),
+        "selftest needs at least two distinct samples, got {samples:?}"
+    );
+}
+
+/// Check that formatting each sample with `Display` and parsing it back with the inner type's
+/// `FromStr` yields the same value.
+pub fn check_display_from_str<B>(samples: &[B], new: fn(B::Inner) -> B)
+where
+    B: Branded + Display + PartialEq + Debug,
+    B::Inner: FromStr,
+    <B::Inner as FromStr>::Err: Debug,
+{
+    for sample in samples {
+        let mut buffer = FormatBuffer::default();
+        write!(buffer, "{sample}").expect("sample is too long to be formatted");
+        let parsed = buffer.as_str().parse::<B::Inner>().map(new);
+        match parsed {
+            Ok(parsed) => {
+                assert_eq!(&parsed, sample, "Display/FromStr round-trip changed the value")
+            }
+            Err(err) => {
+                panic!("failed to parse {:?} back into {sample:?}: {err:?}", buffer.as_str())
+            }
+        }
+    }
+}
+
+/// Check that the ordering of the samples is consistent with equality and with the ordering of the
+/// inner values.
+pub fn check_ordering<B>(samples: &[B])
+where
+    B: Branded + Ord + Debug,
+    B::Inner: Ord,
+{
+    for a in samples {
+        for b in samples {
+            let ordering = a.cmp(b);
+            assert_eq!(
+                ordering,
+                a.inner().cmp(b.inner()),
+                "ordering of {a:?} and {b:?} differs from the inner values"
+            );
+            assert_eq!(
+                a.partial_cmp(b),
+                Some(ordering),
+                "partial_cmp of {a:?} and {b:?} differs from cmp"
+            );
+            assert_eq!(
+                a == b,
+                ordering == Ordering::Equal,
+                "eq of {a:?} and {b:?} differs from cmp"
+            );
+        }
+    }
+}
+
+/// Check that equal samples hash equally, and that each sample hashes like its inner value.
+pub fn check_hash<B>(samples: &[B])
+where
+    B: Branded + Hash + Eq + Debug,
+    B::Inner: Hash,
+{
+    for a in samples {
+        assert_eq!(
+            hash(a),
+            hash(a.inner()),
+            "hash of {a:?} differs from the hash of its inner value"
+        );
+        for b in samples {
+            if a == b {
+                assert_eq!(hash(a), hash(b), "{a:?} and {b:?} are equal but hash differently");
+            }
+        }
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = FnvHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A 64-bit FNV-1a hasher, which keeps the checks free of `std`.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self { Self(0xcbf2_9ce4_8422_2325) }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 { self.0 }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// A fixed-size buffer to format samples into, which keeps the checks free of `alloc`.
+struct FormatBuffer {
+    bytes: [u8; 256],
+    len: usize,
+}
+
+impl Default for FormatBuffer {
+    fn default() -> Self {
+        Self {
+            bytes: [0; 256],
+            len: 0,
+        }
+    }
+}
+
+impl FormatBuffer {
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).expect("buffer only contains whole strings")
+    }
+}
+
+impl Write for FormatBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.bytes.len() {
+            return Err(core::fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Sentry integration for branded types.
+//!
+//! The `sentry` option on the `Branded` derive macro implements [`SentryId`] for the branded type,
+//! under a key that is the snake_case name of the brand. Brands marked with `sentry(pii)` report
+//! their value as `[redacted]`, so personal data does not end up in error reports.
+//!
+//! The [`sentry_tag!`](crate::sentry_tag) and [`sentry_context!`](crate::sentry_context) macros
+//! attach branded IDs to the current scope as tags and as a context respectively.
+//!
+//! ```ignore
+//! use branded::{sentry_context, sentry_tag, Branded};
+//!
+//! #[derive(Branded)]
+//! #[branded(sentry)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! #[branded(sentry(pii))]
+//! pub struct Email(String);
+//!
+//! sentry_tag!(user_id);
+//! sentry_context!("account", user_id, email);
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use core::fmt;
+use sentry_core::protocol::map::Map;
+use sentry_core::protocol::{Context, Value};
+
+/// The value reported for brands marked as personal data.
+pub const REDACTED: &str = "[redacted]";
+
+/// A branded type that can be attached to Sentry scopes.
+pub trait SentryId {
+    /// The key of the tag or context entry, the snake_case name of the brand.
+    const KEY: &'static str;
+
+    /// Format the value reported to Sentry.
+    fn fmt_sentry_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// Adapts a [`SentryId`] to Display its reported value.
+struct SentryValue<'a, B>(&'a B);
+
+impl<B: SentryId> fmt::Display for SentryValue<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.fmt_sentry_value(f) }
+}
+
+/// Attach `id` as a tag to the current scope.
+pub fn set_tag<B: SentryId>(id: &B) {
+    sentry_core::configure_scope(|scope| scope.set_tag(B::KEY, SentryValue(id)));
+}
+
+/// Get the key and reported value of `id`, as an entry of a context.
+pub fn context_entry<B: SentryId>(id: &B) -> (&'static str, Value) {
+    (B::KEY, Value::String(format!("{}", SentryValue(id))))
+}
+
+/// Attach the entries as a context named `name` to the current scope.
+pub fn set_context<const N: usize>(name: &str, entries: [(&'static str, Value); N]) {
+    let context =
+        entries.into_iter().map(|(key, value)| (key.to_owned(), value)).collect::<Map<_, _>>();
+    sentry_core::configure_scope(|scope| scope.set_context(name, Context::Other(context)));
+}
+
+/// Attach branded IDs as tags to the current Sentry scope.
+///
+/// Each ID is tagged under the snake_case name of its brand.
+#[macro_export]
+macro_rules! sentry_tag {
+    ($($id:expr),+ $(,)?) => {
+        $($crate::sentry::set_tag(&$id);)+
+    };
+}
+
+/// Attach branded IDs as a context with the given name to the current Sentry scope.
+///
+/// Each ID is added under the snake_case name of its brand.
+#[macro_export]
+macro_rules! sentry_context {
+    ($name:expr, $($id:expr),+ $(,)?) => {
+        $crate::sentry::set_context($name, [$($crate::sentry::context_entry(&$id)),+])
+    };
+}
@@ -0,0 +1,490 @@
+//! Serde helpers for branded types.
+//!
+//! The `Serialize` and `Deserialize` implementations generated with the `serde(default_generate)`,
+//! `serde(as_string)`, `serde(prefix = "...")`, and `serde(style = "wrapped")` options delegate to
+//! the generic functions in this module, so that each brand only adds a thin impl:
+//! [`serialize_str`] and [`serialize_prefixed`] write the value as a string, [`serialize_wrapped`]
+//! writes it with the brand name, and [`deserialize_or_generate`], [`deserialize_from_str`],
+//! [`deserialize_prefixed`], and [`deserialize_wrapped`] share their visitors between all brands.
+//! The plain `serde` option delegates to the inner type directly, and does not require this
+//! module. The `comma_separated` module encodes lists of brands as comma-separated strings, and
+//! the `transcode` module validates the brands in payloads re-encoded between formats.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess,
+    Unexpected, Visitor,
+};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[cfg(feature = "alloc")]
+pub mod transcode;
+
+/// Serialize a value as a string with its `Display` implementation.
+pub fn serialize_str<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display + ?Sized,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// A visitor deserializing an optional value, generating one when it is absent.
+struct OrGenerate<T, G> {
+    generate: G,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T, G> Visitor<'de> for OrGenerate<T, G>
+where
+    T: Deserialize<'de>,
+    G: FnOnce() -> T,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an optional value")
+    }
+
+    fn visit_none<E>(self) -> Result<T, E> { Ok((self.generate)()) }
+
+    fn visit_unit<E>(self) -> Result<T, E> { Ok((self.generate)()) }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Deserialize a value, calling `generate` if it is missing or null.
+///
+/// A struct field that is missing from the input is deserialized as an absent optional value, so
+/// this relies on `deserialize_option`. Since that changes the encoding in formats that are not
+/// self-describing, those formats deserialize the value as is.
+pub fn deserialize_or_generate<'de, D, T>(
+    deserializer: D,
+    generate: impl FnOnce() -> T,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return T::deserialize(deserializer);
+    }
+    deserializer.deserialize_option(OrGenerate {
+        generate,
+        marker: PhantomData,
+    })
+}
+
+/// A visitor parsing a string with `FromStr`.
+struct FromStrVisitor<T>(PhantomData<T>);
+
+impl<T> Visitor<'_> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a string") }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<T, E> { value.parse().map_err(E::custom) }
+}
+
+/// Deserialize a value from a string with its `FromStr` implementation.
+pub fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_str(FromStrVisitor(PhantomData))
+}
+
+/// A visitor borrowing a string from the input where possible.
+#[cfg(feature = "alloc")]
+struct CowStrVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> Visitor<'de> for CowStrVisitor {
+    type Value = alloc::borrow::Cow<'de, str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a string") }
+
+    fn visit_borrowed_str<E: Error>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(alloc::borrow::Cow::Borrowed(value))
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(alloc::borrow::Cow::Owned(value.into()))
+    }
+
+    fn visit_string<E: Error>(self, value: alloc::string::String) -> Result<Self::Value, E> {
+        Ok(alloc::borrow::Cow::Owned(value))
+    }
+}
+
+/// Deserialize a string that borrows from the input where possible, and is owned otherwise, such
+/// as when it contains escape sequences.
+///
+/// Unlike the `Deserialize` implementation of `Cow`, which always allocates, this is zero-copy
+/// for formats that can lend out their input, such as JSON from a string or slice.
+#[cfg(feature = "alloc")]
+pub fn deserialize_cow_str<'de: 'a, 'a, D>(
+    deserializer: D,
+) -> Result<alloc::borrow::Cow<'a, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(CowStrVisitor)
+}
+
+/// A value written after a prefix.
+struct Prefixed<'a, T: ?Sized> {
+    prefix: &'static str,
+    value: &'a T,
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Prefixed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.prefix)?;
+        self.value.fmt(f)
+    }
+}
+
+/// Serialize a value as a string with its `Display` implementation, after `prefix`.
+pub fn serialize_prefixed<T, S>(
+    prefix: &'static str,
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display + ?Sized,
+    S: Serializer,
+{
+    serializer.collect_str(&Prefixed {
+        prefix,
+        value,
+    })
+}
+
+/// A visitor stripping a prefix, or a legacy prefix, from a string and parsing the rest.
+struct PrefixedVisitor<F, L> {
+    brand: &'static str,
+    prefix: &'static str,
+    legacy: Option<&'static str>,
+    parse: F,
+    on_legacy: L,
+}
+
+impl<T, F, L> Visitor<'_> for PrefixedVisitor<F, L>
+where
+    F: FnOnce(&str) -> Result<T, crate::Error>,
+    L: FnOnce(&T),
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a string starting with `{}`", self.prefix)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<T, E> {
+        if let Some(rest) = value.strip_prefix(self.prefix) {
+            return (self.parse)(rest).map_err(E::custom);
+        }
+        let rest = self.legacy.and_then(|legacy| value.strip_prefix(legacy)).ok_or_else(|| {
+            let kind = crate::ErrorKind::WrongPrefix(self.prefix);
+            E::custom(crate::Error::new(self.brand, kind).with_input(value))
+        })?;
+        let parsed = (self.parse)(rest).map_err(E::custom)?;
+        (self.on_legacy)(&parsed);
+        Ok(parsed)
+    }
+}
+
+/// Deserialize a value of the brand named `brand` from a string starting with `prefix`, parsing
+/// the rest of the string with `parse`.
+///
+/// Strings without the prefix are rejected with [`ErrorKind::WrongPrefix`](crate::ErrorKind).
+pub fn deserialize_prefixed<'de, D, T>(
+    deserializer: D,
+    brand: &'static str,
+    prefix: &'static str,
+    parse: impl FnOnce(&str) -> Result<T, crate::Error>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(PrefixedVisitor {
+        brand,
+        prefix,
+        legacy: None,
+        parse,
+        on_legacy: |_: &T| {},
+    })
+}
+
+/// Deserialize a value of the brand named `brand` like [`deserialize_prefixed`], also accepting
+/// strings starting with the `legacy` prefix, such as while migrating stored IDs to a new prefix.
+///
+/// Values read with the legacy prefix are passed to `on_legacy`, such as to log them. Strings with
+/// neither prefix are rejected with a `WrongPrefix` error for the current prefix.
+pub fn deserialize_prefixed_or_legacy<'de, D, T>(
+    deserializer: D,
+    brand: &'static str,
+    prefix: &'static str,
+    legacy: &'static str,
+    parse: impl FnOnce(&str) -> Result<T, crate::Error>,
+    on_legacy: impl FnOnce(&T),
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(PrefixedVisitor {
+        brand,
+        prefix,
+        legacy: Some(legacy),
+        parse,
+        on_legacy,
+    })
+}
+
+/// The fields of a value written by [`serialize_wrapped`].
+const WRAPPED_FIELDS: &[&str] = &["type", "value"];
+
+/// Serialize a value as a struct of the brand name in its `type` field and the value in its `value`
+/// field, such as `{"type": "UserId", "value": 42}`.
+pub fn serialize_wrapped<T, S>(
+    brand: &'static str,
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + ?Sized,
+    S: Serializer,
+{
+    let mut state = serializer.serialize_struct(brand, 2)?;
+    state.serialize_field("type", brand)?;
+    state.serialize_field("value", value)?;
+    state.end()
+}
+
+/// A field of a wrapped value, or `Other` for unknown fields, which are ignored.
+enum WrappedField {
+    Type,
+    Value,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for WrappedField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = WrappedField;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a field identifier")
+            }
+
+            fn visit_u64<E: Error>(self, index: u64) -> Result<WrappedField, E> {
+                Ok(match index {
+                    0 => WrappedField::Type,
+                    1 => WrappedField::Value,
+                    _ => WrappedField::Other,
+                })
+            }
+
+            fn visit_str<E: Error>(self, name: &str) -> Result<WrappedField, E> {
+                Ok(match name {
+                    "type" => WrappedField::Type,
+                    "value" => WrappedField::Value,
+                    _ => WrappedField::Other,
+                })
+            }
+
+            fn visit_bytes<E: Error>(self, name: &[u8]) -> Result<WrappedField, E> {
+                match core::str::from_utf8(name) {
+                    Ok(name) => self.visit_str(name),
+                    Err(_) => Ok(WrappedField::Other),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// A seed checking that the `type` field of a wrapped value names the brand, without allocating.
+struct BrandName(&'static str);
+
+impl<'de> DeserializeSeed<'de> for BrandName {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for BrandName {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the brand name `{}`", self.0)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<(), E> {
+        if value == self.0 {
+            Ok(())
+        } else {
+            Err(E::invalid_value(Unexpected::Str(value), &self))
+        }
+    }
+}
+
+/// A visitor deserializing a value written by [`serialize_wrapped`].
+struct WrappedVisitor<T> {
+    brand: &'static str,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for WrappedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a wrapped `{}`", self.brand)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        seq.next_element_seed(BrandName(self.brand))?
+            .ok_or_else(|| Error::invalid_length(0, &self))?;
+        seq.next_element()?.ok_or_else(|| Error::invalid_length(1, &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut has_type = false;
+        let mut value = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                WrappedField::Type if has_type => return Err(Error::duplicate_field("type")),
+                WrappedField::Type => {
+                    map.next_value_seed(BrandName(self.brand))?;
+                    has_type = true;
+                }
+                WrappedField::Value if value.is_some() => {
+                    return Err(Error::duplicate_field("value"));
+                }
+                WrappedField::Value => value = Some(map.next_value()?),
+                WrappedField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        if !has_type {
+            return Err(Error::missing_field("type"));
+        }
+        value.ok_or_else(|| Error::missing_field("value"))
+    }
+}
+
+/// Deserialize the value of the brand named `brand` from a struct written by
+/// [`serialize_wrapped`].
+///
+/// Values whose `type` field names another brand are rejected, so that an ID cannot be read as
+/// an ID of another kind.
+pub fn deserialize_wrapped<'de, D, T>(deserializer: D, brand: &'static str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_struct(
+        brand,
+        WRAPPED_FIELDS,
+        WrappedVisitor {
+            brand,
+            marker: PhantomData,
+        },
+    )
+}
+
+/// A serde codec for lists of brands as comma-separated strings, such as `?ids=a,b,c`.
+///
+/// Use it with `#[serde(with = "branded::serde::comma_separated")]` on a `Vec` of brands. The
+/// elements are written with `Display` and parsed with `FromStr`, and an element that fails to
+/// parse is reported with its position. An empty string is an empty list.
+#[cfg(feature = "alloc")]
+pub mod comma_separated {
+    use alloc::vec::Vec;
+    use core::fmt;
+    use core::marker::PhantomData;
+    use core::str::FromStr;
+    use serde::de::{Deserializer, Error, Visitor};
+    use serde::Serializer;
+
+    /// The elements of a list joined with commas.
+    struct Joined<'a, T>(&'a [T]);
+
+    impl<T: fmt::Display> fmt::Display for Joined<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for (i, element) in self.0.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{element}")?;
+            }
+            Ok(())
+        }
+    }
+
+    /// A visitor parsing a comma-separated string into a list.
+    struct ListVisitor<T>(PhantomData<T>);
+
+    impl<T> Visitor<'_> for ListVisitor<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a comma-separated list")
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Vec<T>, E> {
+            if value.is_empty() {
+                return Ok(Vec::new());
+            }
+            value
+                .split(',')
+                .enumerate()
+                .map(|(i, element)| {
+                    element.parse().map_err(|error| {
+                        E::custom(format_args!("invalid list element {i} {element:?}: {error}"))
+                    })
+                })
+                .collect()
+        }
+    }
+
+    /// Serialize a list as a comma-separated string.
+    pub fn serialize<S, T>(list: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: fmt::Display,
+    {
+        serializer.collect_str(&Joined(list))
+    }
+
+    /// Deserialize a list from a comma-separated string.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        deserializer.deserialize_str(ListVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,476 @@
+//! Transcoding payloads between serde formats while validating the brands in them.
+//!
+//! A gateway re-encoding third-party payloads, such as from JSON to MessagePack, usually passes
+//! them through without a typed model, so the IDs in them are never validated. [`transcode`]
+//! streams a payload from a self-describing deserializer into any serializer, and deserializes
+//! the values at the paths of a [`Schema`] as their brands on the way. The first invalid value
+//! fails the transcoding with its path, and valid values are written unchanged.
+//!
+//! Paths are field names separated by dots, where `[]` matches every element of a sequence, such
+//! as `orders[].customer_id`. A path starting with `[]` matches the elements of a top-level
+//! sequence.
+//!
+//! ```
+//! use branded::serde::transcode::{transcode, Schema};
+//! use branded::Branded;
+//!
+//! fn is_positive(id: &u64) -> bool {
+//!     *id > 0
+//! }
+//!
+//! #[derive(Branded)]
+//! #[branded(serde, validate = "is_positive")]
+//! pub struct CustomerId(u64);
+//!
+//! let schema = Schema::new().brand::<CustomerId>("orders[].customer_id");
+//!
+//! let input = r#"{"orders":[{"customer_id":1},{"customer_id":0}]}"#;
+//! let mut output = Vec::new();
+//! let error = transcode(
+//!     &schema,
+//!     &mut serde_json::Deserializer::from_str(input),
+//!     &mut serde_json::Serializer::new(&mut output),
+//! )
+//! .unwrap_err();
+//! assert!(error.to_string().starts_with("invalid value at `orders[1].customer_id`"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+
+/// The paths of the brands in a payload, and the brands to validate them as.
+#[derive(Default)]
+pub struct Schema {
+    brands: Vec<(Vec<Pattern>, Check)>,
+}
+
+/// A segment of a path in a [`Schema`].
+enum Pattern {
+    Field(String),
+    Element,
+}
+
+/// A function deserializing a buffered value as a brand.
+type Check = fn(&Scalar) -> Result<(), CheckError>;
+
+impl Schema {
+    /// Create a schema without any brands.
+    pub fn new() -> Self { Self::default() }
+
+    /// Validate the values at `path` as the brand `B`, or use `Option<B>` to also accept nulls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path is empty or contains an empty field name.
+    pub fn brand<B: DeserializeOwned>(mut self, path: &str) -> Self {
+        let mut patterns = Vec::new();
+        for part in path.split('.') {
+            let mut name = part;
+            let mut elements = 0;
+            while let Some(rest) = name.strip_suffix("[]") {
+                name = rest;
+                elements += 1;
+            }
+            assert!(!name.is_empty() || elements > 0, "invalid brand path {path:?}");
+            if !name.is_empty() {
+                patterns.push(Pattern::Field(name.to_string()));
+            }
+            patterns.extend((0..elements).map(|_| Pattern::Element));
+        }
+        self.brands.push((patterns, check::<B>));
+        self
+    }
+
+    /// Get the check of the value at `path`, if it is the path of a brand.
+    fn check(&self, path: &[Segment]) -> Option<Check> {
+        self.brands.iter().find_map(|(patterns, check)| {
+            let matches = patterns.len() == path.len()
+                && patterns.iter().zip(path).all(|(pattern, segment)| match (pattern, segment) {
+                    (Pattern::Field(name), Segment::Field(Some(key))) => name == key,
+                    (Pattern::Element, Segment::Index(_)) => true,
+                    _ => false,
+                });
+            matches.then_some(*check)
+        })
+    }
+}
+
+/// Deserialize a buffered value as the brand `B`.
+fn check<B: DeserializeOwned>(scalar: &Scalar) -> Result<(), CheckError> {
+    B::deserialize(ScalarDeserializer(scalar)).map(drop)
+}
+
+/// Transcode a payload from `deserializer` into `serializer`, validating the brands of `schema`.
+///
+/// The deserializer must be self-describing, such as JSON or MessagePack. Errors of the
+/// deserializer and of the validation are reported as errors of the serializer.
+pub fn transcode<'de, D, S>(
+    schema: &Schema,
+    deserializer: D,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    D: Deserializer<'de>,
+    S: Serializer,
+{
+    let state = State {
+        schema,
+        path: RefCell::default(),
+        key: RefCell::default(),
+    };
+    Value::new(deserializer, &state, false).serialize(serializer)
+}
+
+/// The state shared by the whole transcoding.
+struct State<'a> {
+    schema: &'a Schema,
+    /// The path of the value being transcoded.
+    path: RefCell<Vec<Segment>>,
+    /// The last map key, if it was a string.
+    key: RefCell<Option<String>>,
+}
+
+/// A segment of the path of a value being transcoded.
+enum Segment {
+    /// A map entry, with its key if it is a string.
+    Field(Option<String>),
+    Index(usize),
+}
+
+/// A path being transcoded, formatted for error messages.
+struct Path<'a>(&'a [Segment]);
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Field(key) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(key.as_deref().unwrap_or("<key>"))?;
+                }
+                Segment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A value of a deserializer, serialized as it is deserialized.
+struct Value<'a, D> {
+    deserializer: RefCell<Option<D>>,
+    state: &'a State<'a>,
+    key: bool,
+}
+
+impl<'a, D> Value<'a, D> {
+    fn new(deserializer: D, state: &'a State<'a>, key: bool) -> Self {
+        Self {
+            deserializer: RefCell::new(Some(deserializer)),
+            state,
+            key,
+        }
+    }
+}
+
+impl<'de, D: Deserializer<'de>> Serialize for Value<'_, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let deserializer =
+            self.deserializer.borrow_mut().take().expect("a value is serialized once");
+        let check = match self.key {
+            true => None,
+            false => self.state.schema.check(&self.state.path.borrow()),
+        };
+        let Some(check) = check else {
+            let transcode = Transcode {
+                serializer,
+                state: self.state,
+                key: self.key,
+            };
+            return deserializer.deserialize_any(transcode).map_err(ser::Error::custom);
+        };
+        let invalid = |error: &dyn fmt::Display| {
+            let path = self.state.path.borrow();
+            ser::Error::custom(format_args!("invalid value at `{}`: {error}", Path(&path)))
+        };
+        let scalar =
+            deserializer.deserialize_any(ScalarVisitor).map_err(|error| invalid(&error))?;
+        check(&scalar).map_err(|error| invalid(&error))?;
+        scalar.serialize(serializer)
+    }
+}
+
+/// A visitor writing every value it visits into a serializer.
+struct Transcode<'a, S> {
+    serializer: S,
+    state: &'a State<'a>,
+    /// Whether the value is a map key, which is recorded for the path of its value.
+    key: bool,
+}
+
+impl<'de, S: Serializer> Visitor<'de> for Transcode<'_, S> {
+    type Value = S::Ok;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("any value") }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<S::Ok, E> {
+        self.serializer.serialize_bool(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<S::Ok, E> {
+        self.serializer.serialize_i64(v).map_err(E::custom)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<S::Ok, E> {
+        self.serializer.serialize_i128(v).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<S::Ok, E> {
+        self.serializer.serialize_u64(v).map_err(E::custom)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<S::Ok, E> {
+        self.serializer.serialize_u128(v).map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<S::Ok, E> {
+        self.serializer.serialize_f64(v).map_err(E::custom)
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<S::Ok, E> {
+        self.serializer.serialize_char(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<S::Ok, E> {
+        if self.key {
+            *self.state.key.borrow_mut() = Some(v.to_string());
+        }
+        self.serializer.serialize_str(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<S::Ok, E> {
+        self.serializer.serialize_bytes(v).map_err(E::custom)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<S::Ok, E> {
+        self.serializer.serialize_none().map_err(E::custom)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Ok, D::Error> {
+        let value = Value::new(deserializer, self.state, self.key);
+        self.serializer.serialize_some(&value).map_err(de::Error::custom)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<S::Ok, E> {
+        self.serializer.serialize_unit().map_err(E::custom)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<S::Ok, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<S::Ok, A::Error> {
+        let mut output =
+            self.serializer.serialize_seq(seq.size_hint()).map_err(de::Error::custom)?;
+        let mut index = 0;
+        while let Some(()) = seq.next_element_seed(Element {
+            output: &mut output,
+            state: self.state,
+            index,
+        })? {
+            index += 1;
+        }
+        output.end().map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<S::Ok, A::Error> {
+        let mut output =
+            self.serializer.serialize_map(map.size_hint()).map_err(de::Error::custom)?;
+        while let Some(()) = map.next_key_seed(Key {
+            output: &mut output,
+            state: self.state,
+        })? {
+            let key = self.state.key.borrow_mut().take();
+            self.state.path.borrow_mut().push(Segment::Field(key));
+            let result = map.next_value_seed(Entry {
+                output: &mut output,
+                state: self.state,
+            });
+            self.state.path.borrow_mut().pop();
+            result?;
+        }
+        output.end().map_err(de::Error::custom)
+    }
+}
+
+/// A seed writing an element of a sequence into the serialized sequence.
+struct Element<'a, 'b, C> {
+    output: &'b mut C,
+    state: &'a State<'a>,
+    index: usize,
+}
+
+impl<'de, C: SerializeSeq> DeserializeSeed<'de> for Element<'_, '_, C> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.state.path.borrow_mut().push(Segment::Index(self.index));
+        let result = self.output.serialize_element(&Value::new(deserializer, self.state, false));
+        self.state.path.borrow_mut().pop();
+        result.map_err(de::Error::custom)
+    }
+}
+
+/// A seed writing a map key into the serialized map.
+struct Key<'a, 'b, C> {
+    output: &'b mut C,
+    state: &'a State<'a>,
+}
+
+impl<'de, C: SerializeMap> DeserializeSeed<'de> for Key<'_, '_, C> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        let key = Value::new(deserializer, self.state, true);
+        self.output.serialize_key(&key).map_err(de::Error::custom)
+    }
+}
+
+/// A seed writing a map value into the serialized map.
+struct Entry<'a, 'b, C> {
+    output: &'b mut C,
+    state: &'a State<'a>,
+}
+
+impl<'de, C: SerializeMap> DeserializeSeed<'de> for Entry<'_, '_, C> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        let value = Value::new(deserializer, self.state, false);
+        self.output.serialize_value(&value).map_err(de::Error::custom)
+    }
+}
+
+/// A value at the path of a brand, buffered to be validated before it is written.
+enum Scalar {
+    Bool(bool),
+    I64(i64),
+    I128(i128),
+    U64(u64),
+    U128(u128),
+    F64(f64),
+    Char(char),
+    Str(String),
+    None,
+    Unit,
+}
+
+impl Serialize for Scalar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::I64(v) => serializer.serialize_i64(*v),
+            Self::I128(v) => serializer.serialize_i128(*v),
+            Self::U64(v) => serializer.serialize_u64(*v),
+            Self::U128(v) => serializer.serialize_u128(*v),
+            Self::F64(v) => serializer.serialize_f64(*v),
+            Self::Char(v) => serializer.serialize_char(*v),
+            Self::Str(v) => serializer.serialize_str(v),
+            Self::None => serializer.serialize_none(),
+            Self::Unit => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// A visitor buffering a scalar value.
+struct ScalarVisitor;
+
+impl<'de> Visitor<'de> for ScalarVisitor {
+    type Value = Scalar;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a scalar value") }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Scalar, E> { Ok(Scalar::Bool(v)) }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Scalar, E> { Ok(Scalar::I64(v)) }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Scalar, E> { Ok(Scalar::I128(v)) }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Scalar, E> { Ok(Scalar::U64(v)) }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Scalar, E> { Ok(Scalar::U128(v)) }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Scalar, E> { Ok(Scalar::F64(v)) }
+
+    fn visit_char<E>(self, v: char) -> Result<Scalar, E> { Ok(Scalar::Char(v)) }
+
+    fn visit_str<E>(self, v: &str) -> Result<Scalar, E> { Ok(Scalar::Str(v.to_string())) }
+
+    fn visit_string<E>(self, v: String) -> Result<Scalar, E> { Ok(Scalar::Str(v)) }
+
+    fn visit_none<E>(self) -> Result<Scalar, E> { Ok(Scalar::None) }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Scalar, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_unit<E>(self) -> Result<Scalar, E> { Ok(Scalar::Unit) }
+}
+
+/// A deserializer of a buffered scalar value.
+struct ScalarDeserializer<'a>(&'a Scalar);
+
+impl<'de> Deserializer<'de> for ScalarDeserializer<'_> {
+    type Error = CheckError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CheckError> {
+        match self.0 {
+            Scalar::Bool(v) => visitor.visit_bool(*v),
+            Scalar::I64(v) => visitor.visit_i64(*v),
+            Scalar::I128(v) => visitor.visit_i128(*v),
+            Scalar::U64(v) => visitor.visit_u64(*v),
+            Scalar::U128(v) => visitor.visit_u128(*v),
+            Scalar::F64(v) => visitor.visit_f64(*v),
+            Scalar::Char(v) => visitor.visit_char(*v),
+            Scalar::Str(v) => visitor.visit_str(v),
+            Scalar::None => visitor.visit_none(),
+            Scalar::Unit => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CheckError> {
+        match self.0 {
+            Scalar::None | Scalar::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// An error deserializing a brand from a buffered value.
+#[derive(Debug)]
+struct CheckError(String);
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+}
+
+impl de::StdError for CheckError {}
+
+impl de::Error for CheckError {
+    fn custom<T: fmt::Display>(msg: T) -> Self { Self(msg.to_string()) }
+}
@@ -0,0 +1,67 @@
+//! Partition routing derived from branded IDs.
+//!
+//! The `shard(modulo = N)` option on the `Branded` derive macro generates a `shard` method that
+//! maps the brand to a shard in `0..N` from the [`ShardKey`] of its inner type. Integers use their
+//! low bits, and strings and UUIDs use a 64-bit FNV-1a hash of their bytes, so the shard of an ID
+//! is stable across processes, platforms, and releases.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(shard(modulo = 64))]
+//! pub struct TenantId(u64);
+//!
+//! assert_eq!(TenantId::new(130).shard(), 2);
+//! ```
+
+/// A type with a stable 64-bit key to derive shards from.
+///
+/// Implement this trait for custom inner types to use them with the `shard` option. The key must
+/// not depend on the process, platform, or Rust version.
+pub trait ShardKey {
+    /// Get the stable key of the value.
+    fn shard_key(&self) -> u64;
+}
+
+macro_rules! impl_shard_key_for_integers {
+    ($($ty:ty),*) => {
+        $(
+            impl ShardKey for $ty {
+                fn shard_key(&self) -> u64 { *self as u64 }
+            }
+        )*
+    };
+}
+
+impl_shard_key_for_integers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl ShardKey for str {
+    fn shard_key(&self) -> u64 { fnv1a(self.as_bytes()) }
+}
+
+#[cfg(feature = "alloc")]
+impl ShardKey for alloc::string::String {
+    fn shard_key(&self) -> u64 { fnv1a(self.as_bytes()) }
+}
+
+#[cfg(feature = "uuid")]
+impl ShardKey for uuid::Uuid {
+    fn shard_key(&self) -> u64 { fnv1a(self.as_bytes()) }
+}
+
+impl<T: ShardKey + ?Sized> ShardKey for &T {
+    fn shard_key(&self) -> u64 { (**self).shard_key() }
+}
+
+/// Map a key to a shard in `0..modulo`.
+pub fn shard<K: ShardKey + ?Sized>(key: &K, modulo: u16) -> u16 {
+    (key.shard_key() % u64::from(modulo)) as u16
+}
+
+/// Compute the 64-bit FNV-1a hash of `bytes`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,116 @@
+//! Keyed queries for brands with the `sqlx(table = "...")` option, and the storage strategies of
+//! the `sqlx(int128 = "...")` option.
+//!
+//! The functions in this module are used by the code generated by the `Branded` derive macro. The
+//! keyed queries format the bind placeholder through the database's arguments type, so the same
+//! query works across databases.
+
+use alloc::format;
+#[doc(hidden)]
+pub use alloc::string::String;
+use alloc::string::ToString;
+#[doc(hidden)]
+pub use alloc::vec::Vec;
+use core::fmt;
+use core::num::ParseIntError;
+use core::str::FromStr;
+use sqlx::error::BoxDynError;
+use sqlx::{Arguments, Database, Encode, Executor, IntoArguments, Pool, Type};
+
+/// 128-bit integers, which no database driver supports natively.
+///
+/// With `sqlx(int128 = "bytes")`, values are stored as 16 big-endian bytes, as in a `BYTEA`,
+/// `BLOB`, or `BINARY(16)` column. With `sqlx(int128 = "text")`, values are stored as decimal
+/// strings, which can be cast to and from `NUMERIC` columns in SQL.
+pub trait Int128: Copy + fmt::Display + FromStr<Err = ParseIntError> {
+    /// Convert the value into big-endian bytes.
+    fn to_be_bytes(self) -> [u8; 16];
+
+    /// Convert big-endian bytes into a value.
+    fn from_be_bytes(bytes: [u8; 16]) -> Self;
+}
+
+impl Int128 for u128 {
+    fn to_be_bytes(self) -> [u8; 16] { self.to_be_bytes() }
+
+    fn from_be_bytes(bytes: [u8; 16]) -> Self { Self::from_be_bytes(bytes) }
+}
+
+impl Int128 for i128 {
+    fn to_be_bytes(self) -> [u8; 16] { self.to_be_bytes() }
+
+    fn from_be_bytes(bytes: [u8; 16]) -> Self { Self::from_be_bytes(bytes) }
+}
+
+/// Encode a 128-bit integer as 16 big-endian bytes.
+pub fn encode_int128_bytes<T: Int128>(value: T) -> Vec<u8> { value.to_be_bytes().to_vec() }
+
+/// Encode a 128-bit integer as a decimal string.
+pub fn encode_int128_text<T: Int128>(value: T) -> String { value.to_string() }
+
+/// Decode a 128-bit integer from 16 big-endian bytes.
+pub fn decode_int128_bytes<T: Int128>(bytes: &[u8]) -> Result<T, BoxDynError> {
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| format!("expected 16 bytes for a 128-bit integer, got {}", bytes.len()))?;
+    Ok(T::from_be_bytes(bytes))
+}
+
+/// Decode a 128-bit integer from a decimal string.
+pub fn decode_int128_text<T: Int128>(text: &str) -> Result<T, BoxDynError> { Ok(text.parse()?) }
+
+/// Build a query for `statement` filtered on `value` in `column`.
+fn keyed_query<'q, DB, T>(
+    statement: &str,
+    table: &str,
+    column: &str,
+    value: &'q T,
+) -> Result<(String, DB::Arguments<'q>), sqlx::Error>
+where
+    DB: Database,
+    T: Encode<'q, DB> + Type<DB> + Sync,
+{
+    let mut arguments = DB::Arguments::default();
+    arguments.add(value).map_err(sqlx::Error::Encode)?;
+    let mut sql = String::from(statement);
+    for part in [" ", table, " WHERE ", column, " = "] {
+        sql.push_str(part);
+    }
+    arguments.format_placeholder(&mut sql).map_err(|error| sqlx::Error::Encode(error.into()))?;
+    Ok((sql, arguments))
+}
+
+/// Check whether a row with `value` in `column` exists in `table`.
+pub async fn exists<DB, T>(
+    pool: &Pool<DB>,
+    table: &str,
+    column: &str,
+    value: &T,
+) -> Result<bool, sqlx::Error>
+where
+    DB: Database,
+    T: for<'q> Encode<'q, DB> + Type<DB> + Sync,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let (sql, arguments) = keyed_query::<DB, T>("SELECT 1 FROM", table, column, value)?;
+    let row = sqlx::query_with(&sql, arguments).fetch_optional(pool).await?;
+    Ok(row.is_some())
+}
+
+/// Delete the rows with `value` in `column` from `table`.
+pub async fn delete<DB, T>(
+    pool: &Pool<DB>,
+    table: &str,
+    column: &str,
+    value: &T,
+) -> Result<DB::QueryResult, sqlx::Error>
+where
+    DB: Database,
+    T: for<'q> Encode<'q, DB> + Type<DB> + Sync,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let (sql, arguments) = keyed_query::<DB, T>("DELETE FROM", table, column, value)?;
+    sqlx::query_with(&sql, arguments).execute(pool).await
+}
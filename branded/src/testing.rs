@@ -0,0 +1,28 @@
+//! Helpers for deterministic brands in tests.
+//!
+//! With the `uuid` feature and option, the `Branded` derive macro generates a `for_test(name)`
+//! constructor that derives a UUID v5 from the name, so fixtures are stable across runs and
+//! readable in snapshots instead of hard-coding UUID literals. The UUID is derived in a namespace
+//! of its own per brand, so the same name gives different IDs for different brands.
+//!
+//! ```
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid)]
+//! pub struct UserId(uuid::Uuid);
+//!
+//! assert_eq!(UserId::for_test("alice"), UserId::for_test("alice"));
+//! assert_ne!(UserId::for_test("alice"), UserId::for_test("bob"));
+//! ```
+
+use uuid::Uuid;
+
+/// The namespace the namespaces of the brands in tests are derived in.
+pub const NAMESPACE: Uuid = Uuid::from_u128(0x9b2c0e4f_1d7a_4c3b_8e65_2f0a7d19c4b8);
+
+/// Derive the UUID v5 of `name` in the test namespace of `brand`.
+pub fn uuid_for_test(brand: &str, name: &str) -> Uuid {
+    let namespace = Uuid::new_v5(&NAMESPACE, brand.as_bytes());
+    Uuid::new_v5(&namespace, name.as_bytes())
+}
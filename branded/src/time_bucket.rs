@@ -0,0 +1,116 @@
+//! Time buckets over time-ordered UUID v7 brands.
+//!
+//! UUID v7 values start with their creation time in milliseconds since the Unix epoch, so the IDs
+//! created in a time window form a contiguous range. With the `uuid(v7)` option, the `Branded`
+//! derive macro generates a `bucket` method returning the [`TimeBucket`] an ID falls in, and a
+//! `range_for_bucket` function returning the smallest and largest possible IDs of a bucket, for
+//! time-range scans over ID-ordered tables without a separate timestamp column.
+//!
+//! ```
+//! use branded::time_bucket::TimeBucket;
+//! use branded::Branded;
+//! use core::time::Duration;
+//!
+//! #[derive(Branded)]
+//! #[branded(uuid(v7))]
+//! pub struct EventId(uuid::Uuid);
+//!
+//! let event_id = EventId::new_v7();
+//! let bucket = event_id.bucket(Duration::from_secs(3600));
+//! assert!(EventId::range_for_bucket(bucket).contains(&event_id));
+//! ```
+
+use core::time::Duration;
+
+/// The largest timestamp a UUID v7 can hold, in milliseconds since the Unix epoch.
+const MAX_TIMESTAMP_MILLIS: u64 = (1 << 48) - 1;
+
+/// A window of time of a fixed granularity, aligned to the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeBucket {
+    start_millis: u64,
+    granularity_millis: u64,
+}
+
+impl TimeBucket {
+    /// Get the bucket of the given granularity containing the Unix timestamp `timestamp_millis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the granularity is shorter than a millisecond.
+    pub fn containing(timestamp_millis: u64, granularity: Duration) -> Self {
+        let granularity_millis = u64::try_from(granularity.as_millis()).unwrap_or(u64::MAX);
+        assert!(granularity_millis > 0, "time bucket granularity must be at least 1ms");
+        Self {
+            start_millis: timestamp_millis - timestamp_millis % granularity_millis,
+            granularity_millis,
+        }
+    }
+
+    /// Get the bucket of the given granularity containing the timestamp of the UUID v7 with the
+    /// given bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the granularity is shorter than a millisecond.
+    pub fn of_uuid_bytes(uuid: &[u8; 16], granularity: Duration) -> Self {
+        Self::containing(timestamp_millis(uuid), granularity)
+    }
+
+    /// Get the start of the bucket, in milliseconds since the Unix epoch.
+    pub fn start_millis(&self) -> u64 { self.start_millis }
+
+    /// Get the end of the bucket, exclusive, in milliseconds since the Unix epoch.
+    pub fn end_millis(&self) -> u64 { self.start_millis.saturating_add(self.granularity_millis) }
+
+    /// Get the granularity of the bucket.
+    pub fn granularity(&self) -> Duration { Duration::from_millis(self.granularity_millis) }
+
+    /// Get the bucket following this one.
+    pub fn next(&self) -> Self {
+        Self {
+            start_millis: self.end_millis(),
+            ..*self
+        }
+    }
+
+    /// Get the bucket preceding this one, or `None` for the bucket starting at the Unix epoch.
+    pub fn previous(&self) -> Option<Self> {
+        Some(Self {
+            start_millis: self.start_millis.checked_sub(self.granularity_millis)?,
+            ..*self
+        })
+    }
+
+    /// Get the bytes of the smallest UUID v7 in the bucket.
+    pub fn min_uuid_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..6].copy_from_slice(&timestamp_bytes(self.start_millis));
+        bytes[6] = 0x70;
+        bytes[8] = 0x80;
+        bytes
+    }
+
+    /// Get the bytes of the largest UUID v7 in the bucket.
+    pub fn max_uuid_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0xff; 16];
+        bytes[..6].copy_from_slice(&timestamp_bytes(self.end_millis() - 1));
+        bytes[6] = 0x7f;
+        bytes[8] = 0xbf;
+        bytes
+    }
+}
+
+/// Read the timestamp of a UUID v7, in milliseconds since the Unix epoch.
+fn timestamp_millis(uuid: &[u8; 16]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes[2..].copy_from_slice(&uuid[..6]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Encode a timestamp as the 48-bit big-endian prefix of a UUID v7, clamping it to the largest
+/// timestamp that fits.
+fn timestamp_bytes(timestamp_millis: u64) -> [u8; 6] {
+    let bytes = timestamp_millis.min(MAX_TIMESTAMP_MILLIS).to_be_bytes();
+    [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+}
@@ -0,0 +1,110 @@
+//! tonic integration for branded types.
+//!
+//! The `tonic` option on the `Branded` derive macro generates conversions between the branded type
+//! and ASCII gRPC metadata values:
+//!
+//! - `TryFrom<&MetadataValue<Ascii>> for Brand`, which parses the value with the `FromStr`
+//!   implementation of the inner type, and fails with an `invalid_argument` [`Status`].
+//! - `TryFrom<&Brand> for MetadataValue<Ascii>`, which formats the value with the `Display`
+//!   implementation of the inner type. This is fallible because a metadata value may only contain
+//!   visible ASCII characters.
+//!
+//! The [`MetadataInterceptor`] uses the former to move a branded value from the request metadata
+//! into the request extensions, where handlers can read it with `request.extensions().get()`.
+//!
+//! ```ignore
+//! use branded::tonic::MetadataInterceptor;
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(tonic)]
+//! pub struct TenantId(uuid::Uuid);
+//!
+//! let interceptor = MetadataInterceptor::<TenantId>::new("x-tenant-id");
+//! let service = TenantServiceServer::with_interceptor(TenantService, interceptor);
+//! ```
+
+use alloc::format;
+use alloc::string::ToString;
+use core::fmt::Display;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use tonic::metadata::errors::InvalidMetadataValue;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Parse a metadata value into the inner type of the brand named `brand`.
+///
+/// This is used by the conversions generated by the `tonic` option.
+#[allow(clippy::result_large_err)] // `Status` is what tonic expects handlers to return.
+pub fn parse_metadata_value<T>(brand: &str, value: &MetadataValue<Ascii>) -> Result<T, Status>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = value
+        .to_str()
+        .map_err(|_| Status::invalid_argument(format!("{brand} metadata is not valid ASCII")))?;
+    value
+        .parse()
+        .map_err(|err| Status::invalid_argument(format!("invalid {brand} in metadata: {err}")))
+}
+
+/// Convert the validation error of a brand parsed from metadata into an `invalid_argument` status.
+///
+/// This is used by the conversions generated by the `tonic` option for validated brands.
+pub fn validation_status(error: crate::Error) -> Status {
+    Status::invalid_argument(error.to_string())
+}
+
+/// Format a value of the inner type of a brand as a metadata value.
+///
+/// This is used by the conversions generated by the `tonic` option.
+pub fn to_metadata_value<T>(value: &T) -> Result<MetadataValue<Ascii>, InvalidMetadataValue>
+where
+    T: Display,
+{
+    MetadataValue::try_from(value.to_string())
+}
+
+/// An interceptor that moves a branded value from the request metadata into the request
+/// extensions.
+///
+/// Requests without the metadata entry, or with a value that does not parse, are rejected with an
+/// `invalid_argument` status.
+pub struct MetadataInterceptor<B> {
+    key: &'static str,
+    brand: PhantomData<fn() -> B>,
+}
+
+impl<B> MetadataInterceptor<B> {
+    /// Create an interceptor reading the ASCII metadata entry `key`.
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            brand: PhantomData,
+        }
+    }
+}
+
+impl<B> Clone for MetadataInterceptor<B> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<B> Copy for MetadataInterceptor<B> {}
+
+impl<B> Interceptor for MetadataInterceptor<B>
+where
+    B: for<'a> TryFrom<&'a MetadataValue<Ascii>, Error = Status> + Clone + Send + Sync + 'static,
+{
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let value = request
+            .metadata()
+            .get(self.key)
+            .ok_or_else(|| Status::invalid_argument(format!("missing `{}` metadata", self.key)))?;
+        let value = B::try_from(value)?;
+        request.extensions_mut().insert(value);
+        Ok(request)
+    }
+}
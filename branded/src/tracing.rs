@@ -0,0 +1,55 @@
+//! Recording branded IDs as fields of tracing spans.
+//!
+//! The [`record_ids!`](crate::record_ids) macro records several branded IDs on a span at once,
+//! using their `Display` implementation. As with [`Span::record`], the fields must be declared
+//! when the span is created, for example as [`tracing::field::Empty`].
+//!
+//! ```
+//! use branded::{record_ids, Branded};
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(u64);
+//!
+//! #[derive(Branded)]
+//! pub struct OrderId(u64);
+//!
+//! let user_id = UserId::new(1);
+//! let order = (OrderId::new(2),);
+//! let span = tracing::info_span!(
+//!     "checkout",
+//!     user_id = tracing::field::Empty,
+//!     order_id = tracing::field::Empty
+//! );
+//! record_ids!(span, user_id, order_id = order.0);
+//! ```
+
+use core::fmt::Display;
+
+use tracing::Span;
+
+use crate::Branded;
+
+/// Record a branded ID as the field named `field` of `span`, using its `Display` implementation.
+pub fn record<B: Branded + Display>(span: &Span, field: &str, id: &B) {
+    span.record(field, tracing::field::display(id));
+}
+
+/// Record branded IDs as fields of a tracing span, using their `Display` implementation.
+///
+/// Each ID is either a variable, recorded as the field of the same name, or `field = expression`.
+/// Recording anything but a branded type is a compile error.
+///
+/// See the [`tracing`](crate::tracing) module for an example.
+#[macro_export]
+macro_rules! record_ids {
+    (@record $span:ident, $field:ident) => {
+        $crate::tracing::record($span, ::core::stringify!($field), &$field)
+    };
+    (@record $span:ident, $field:ident, $id:expr) => {
+        $crate::tracing::record($span, ::core::stringify!($field), &$id)
+    };
+    ($span:expr, $($field:ident $(= $id:expr)?),+ $(,)?) => {{
+        let span: &$crate::__private::tracing::Span = &$span;
+        $( $crate::record_ids!(@record span, $field $(, $id)?); )+
+    }};
+}
@@ -0,0 +1,91 @@
+//! Helpers for building URL paths out of branded values.
+//!
+//! Interpolating IDs into request paths with `format!` breaks on values containing `/`, `?`, or
+//! other reserved characters, and makes it easy to put an access token into a URL, where it ends
+//! up in server logs. The `branded::path!` macro percent-encodes each argument as a single
+//! path segment, and only accepts [`PathSegment`] values. Brands implement it unless they are
+//! marked with the `secret` option.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! pub struct UserId(String);
+//!
+//! let user_id = UserId::new("alice/admin".to_owned());
+//! assert_eq!(branded::path!("/users/{}/posts", user_id), "/users/alice%2Fadmin/posts");
+//! # }
+//! ```
+//!
+//! Secret brands fail to compile:
+//!
+//! ```compile_fail
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(secret)]
+//! pub struct SessionToken(String);
+//!
+//! let token = SessionToken::new("hunter2".to_owned());
+//! branded::path!("/sessions/{}", token);
+//! ```
+
+use core::fmt::{self, Display, Write};
+
+/// A value that may be interpolated into a URL path.
+///
+/// Implemented by every brand not marked as `secret`, and by strings, integers, and `bool`.
+pub trait PathSegment: Display {}
+
+impl PathSegment for str {}
+impl<T: PathSegment + ?Sized> PathSegment for &T {}
+#[cfg(feature = "alloc")]
+impl PathSegment for alloc::string::String {}
+impl PathSegment for bool {}
+
+macro_rules! impl_path_segment {
+    ($($ty:ty),*) => {
+        $(impl PathSegment for $ty {})*
+    };
+}
+
+impl_path_segment!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The `Display` of a value, percent-encoded as a single URL path segment.
+///
+/// Every byte except the unreserved characters of RFC 3986, ASCII letters and digits, `-`, `.`,
+/// `_`, and `~`, is encoded, including `/`.
+pub struct Encoded<'a, T: ?Sized>(&'a T);
+
+impl<T: Display + ?Sized> Display for Encoded<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(PercentEncoder(f), "{}", self.0)
+    }
+}
+
+/// Percent-encode the `Display` of `value` as a single URL path segment.
+pub fn encode<T: PathSegment + ?Sized>(value: &T) -> Encoded<'_, T> { Encoded(value) }
+
+/// A writer that percent-encodes everything written to it.
+struct PercentEncoder<'a, 'b>(&'a mut fmt::Formatter<'b>);
+
+impl Write for PercentEncoder<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for chunk in s.split_inclusive(|c: char| !is_unreserved(c)) {
+            let (plain, reserved) = match chunk.char_indices().last() {
+                Some((i, c)) if !is_unreserved(c) => chunk.split_at(i),
+                _ => (chunk, ""),
+            };
+            self.0.write_str(plain)?;
+            for byte in reserved.bytes() {
+                write!(self.0, "%{byte:02X}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
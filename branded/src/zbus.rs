@@ -0,0 +1,24 @@
+//! zbus (D-Bus) integration for branded types.
+//!
+//! The `zbus` option on the `Branded` derive macro implements `zvariant::Type` for the branded type
+//! with the signature of the inner type, together with the transparent serde implementations, so
+//! branded values can be passed to and returned from D-Bus methods, signals, and properties.
+//!
+//! The zvariant crate is re-exported for the generated code, and must match the version used by
+//! zbus.
+//!
+//! ```ignore
+//! use branded::Branded;
+//!
+//! #[derive(Branded)]
+//! #[branded(zbus)]
+//! pub struct SessionId(u64);
+//!
+//! #[zbus::proxy(interface = "org.example.Agent")]
+//! trait Agent {
+//!     fn lock_session(&self, id: SessionId) -> zbus::Result<()>;
+//! }
+//! ```
+
+#[doc(hidden)]
+pub use zvariant;
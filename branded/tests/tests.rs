@@ -36,12 +36,365 @@ fn test_accessors() {
     assert_eq!(user_id.into_inner(), 123);
 }
 
+fn self_tested_ids() -> [SelfTestedId; 3] {
+    [SelfTestedId::new(1), SelfTestedId::new(42), SelfTestedId::new(u64::MAX)]
+}
+
+#[derive(Branded)]
+#[branded(selftest(samples = "self_tested_ids"))]
+pub struct SelfTestedId(u64);
+
+fn first_port() -> u16 { 1 }
+
+fn self_tested_ports() -> Vec<SelfTestedPort> {
+    [80, 443, u16::MAX].into_iter().map(SelfTestedPort::new_unchecked).collect()
+}
+
+#[derive(Branded)]
+#[branded(
+    selftest(samples = "self_tested_ports"),
+    range = "1..",
+    generator(function = "first_port", default)
+)]
+pub struct SelfTestedPort(u16);
+
+#[test]
+#[should_panic(expected = "at least two distinct samples")]
+fn test_selftest_rejects_single_sample() {
+    branded::selftest::check_samples(&[SelfTestedId::new(1), SelfTestedId::new(1)]);
+}
+
+#[test]
+fn test_new_into() {
+    #[derive(Branded)]
+    #[branded(new_into)]
+    pub struct UserId(String);
+
+    #[derive(Branded)]
+    #[branded(new_into, serde)]
+    pub struct OrderId(u64);
+
+    assert_eq!(UserId::new("abc").into_inner(), "abc");
+    assert_eq!(UserId::new(String::from("abc")), UserId::new("abc"));
+    assert_eq!(OrderId::new(42u32).into_inner(), 42);
+    assert_eq!("42".parse::<u64>().map(OrderId::new), Ok(OrderId::new(42u64)));
+}
+
+#[test]
+fn test_new_unchecked() {
+    use branded::ErrorKind;
+
+    fn is_port(port: &u16) -> bool { *port != 0 }
+
+    #[derive(Branded)]
+    #[branded(validate = "is_port")]
+    pub struct Port(u16);
+
+    #[derive(Branded)]
+    #[branded(validate = "is_port", const_new, constructor = "from_raw")]
+    pub struct AdminPort(u16);
+
+    const ADMIN: AdminPort = AdminPort::new_unchecked(9090);
+
+    assert_eq!(ADMIN, AdminPort::from_raw(9090));
+    assert_eq!(Port::try_new(8080), Ok(Port::new(8080)));
+    assert_eq!(Port::try_new(0).unwrap_err().kind(), ErrorKind::Validation);
+    assert_eq!(Port::new_unchecked(8080), Port::try_new(8080).unwrap());
+    assert_eq!(Port::new_unchecked(0).into_inner(), 0);
+}
+
+#[test]
+fn test_is_valid() {
+    use branded::ErrorKind;
+
+    #[allow(clippy::ptr_arg)]
+    fn is_lowercase(name: &String) -> bool { name.chars().all(char::is_lowercase) }
+
+    #[derive(Branded)]
+    #[branded(len = "1..=4")]
+    pub struct Code(String);
+
+    #[derive(Branded)]
+    #[branded(validate = "is_lowercase")]
+    pub struct Name(String);
+
+    #[derive(Branded)]
+    #[branded(range = "1..")]
+    pub struct Port(u16);
+
+    assert!(Code::is_valid("abcd"));
+    assert!(!Code::is_valid(""));
+    assert!(!Code::is_valid("abcde"));
+    assert!(Name::is_valid("alice"));
+    assert!(!Name::is_valid("Alice"));
+    assert!(Port::is_valid("8080"));
+    assert!(!Port::is_valid("0"));
+    assert!(!Port::is_valid("abc"));
+
+    assert_eq!(Port::validate(&1), Ok(()));
+    let error = Port::validate(&0).unwrap_err();
+    assert_eq!(error.brand(), "Port");
+    assert_eq!(error.kind(), ErrorKind::Validation);
+}
+
+mod hygiene {
+    // Different items named `Branded`, `Result`, and `write` at the definition site must not be
+    // picked up by the generated code.
+    #[allow(dead_code)]
+    trait Branded {}
+
+    #[allow(dead_code)]
+    type Result<T> = core::result::Result<T, ()>;
+
+    #[allow(unused_macros)]
+    macro_rules! write {
+        ($($arg:tt)*) => {
+            compile_error!("the generated code must not use the `write!` macro in scope")
+        };
+    }
+
+    #[derive(branded::Branded)]
+    pub struct UserId(String);
+
+    #[derive(branded::Branded)]
+    #[branded(len = "1..=8")]
+    pub struct Code(String);
+
+    #[derive(branded::Branded)]
+    #[branded(path = "users/{UserId}")]
+    pub struct UserName((UserId,));
+
+    #[derive(branded::Branded)]
+    #[branded(external(v1 = "ord-{}", v2 = "order:{}"))]
+    pub struct OrderId(u64);
+
+    #[cfg(feature = "serde")]
+    #[derive(branded::Branded)]
+    #[branded(serde, range = "1..")]
+    pub struct Port(u16);
+
+    #[test]
+    fn test_derive_without_import() {
+        let user_id = UserId::new("jane".to_owned());
+        assert_eq!(user_id.clone(), user_id);
+        assert_eq!(branded::Branded::inner(&user_id), "jane");
+    }
+
+    #[test]
+    fn test_derive_with_shadowed_names() {
+        assert!(Code::try_new("abc".to_owned()).is_ok());
+        assert!("".parse::<Code>().is_err());
+        let name = UserName::new((UserId::new("jane".to_owned()),));
+        assert_eq!(name.to_string(), "users/jane");
+        assert_eq!("users/jane".parse::<UserName>().unwrap(), name);
+        assert_eq!(OrderId::new(7).to_string(), "order:7");
+        assert_eq!("ord-7".parse::<OrderId>().unwrap(), OrderId::new(7));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_with_shadowed_result() {
+        assert_eq!(serde_json::to_string(&Port::try_new(80).unwrap()).unwrap(), "80");
+        assert!(serde_json::from_str::<Port>("0").is_err());
+    }
+}
+
+#[test]
+fn test_alternate_display() {
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    let user_id = UserId::new(1234);
+    assert_eq!(format!("{user_id}"), "1234");
+    assert_eq!(format!("{user_id:#}"), "UserId:1234");
+}
+
+#[test]
+fn test_from_inner() {
+    use branded::FromInner;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    pub enum OrderTag {}
+    impl branded::id::Tag for OrderTag {
+        const NAME: &'static str = "OrderId";
+        const COLUMN: &'static str = "order_id";
+    }
+    pub type OrderId = branded::id::Id<OrderTag, u64>;
+
+    fn load<B: FromInner<Inner = u64>>(row: u64) -> B { B::from_inner(row) }
+
+    assert_eq!(load::<UserId>(42), UserId::new(42));
+    assert_eq!(load::<OrderId>(7), OrderId::new(7));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_dyn_branded() {
+    use branded::DynBranded;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    #[derive(Branded)]
+    #[branded(brand = "Order")]
+    pub struct OrderId(String);
+
+    let ids: Vec<Box<dyn DynBranded>> =
+        vec![Box::new(UserId::new(1)), Box::new(OrderId::new("a".to_owned()))];
+    let described: Vec<_> = ids
+        .iter()
+        .map(|id| format!("{} {} {id} {id:?}", id.brand_name(), id.inner_string()))
+        .collect();
+    assert_eq!(described, ["UserId 1 1 UserId(1)", "Order a a OrderId(\"a\")"]);
+}
+
+#[test]
+fn test_map() {
+    use branded::FromInner;
+
+    fn is_lowercase(slug: &str) -> bool { !slug.chars().any(char::is_uppercase) }
+
+    #[derive(Branded)]
+    pub struct Name(&'static str);
+
+    #[derive(Branded)]
+    #[branded(validate = "is_lowercase")]
+    pub struct Slug(String);
+
+    fn trim<B: FromInner<Inner = &'static str>>(brand: B) -> B { FromInner::map(brand, str::trim) }
+
+    assert_eq!(Name::new(" a ").map(str::trim), Name::new("a"));
+    assert_eq!(trim(Name::new(" a ")), Name::new("a"));
+    assert_eq!(Name::new("a").try_map(|_| Err::<&str, _>(1)), Err(1));
+
+    let slug = Slug::new("a".to_owned());
+    assert_eq!(slug.map(|slug| slug + "b").unwrap(), Slug::new("ab".to_owned()));
+    let error = Slug::new("a".to_owned()).map(|slug| slug.to_uppercase()).unwrap_err();
+    assert_eq!(error.kind(), branded::ErrorKind::Validation);
+    let error = Slug::new("a".to_owned())
+        .try_map(|slug| Ok::<_, branded::Error>(slug.to_uppercase()))
+        .unwrap_err();
+    assert_eq!(error.brand(), "Slug");
+}
+
+#[test]
+fn test_external() {
+    fn is_small(id: &u128) -> bool { *id < 100 }
+
+    #[derive(Branded)]
+    #[branded(external(prefix = "user", v1 = "{}", v3 = "typeid", v2 = "user:{}"))]
+    pub struct UserId(u128);
+
+    #[derive(Branded)]
+    #[branded(external(v1 = "ord-{}", v2 = "order:{}"), validate = "is_small")]
+    pub struct OrderId(u128);
+
+    let user_id = UserId::new(42);
+    assert_eq!(user_id.to_string(), "user_0000000000000000000000001a");
+    assert_eq!(format!("{user_id:#}"), "UserId:user_0000000000000000000000001a");
+    for input in ["42", "user:42", "user_0000000000000000000000001a"] {
+        assert_eq!(input.parse::<UserId>().unwrap(), user_id, "{input}");
+    }
+    for input in ["user_8000000000000000000000001a", "user_0000000000000000000000001A", "user:x"] {
+        let error = input.parse::<UserId>().unwrap_err();
+        assert_eq!(error.kind(), branded::ErrorKind::Parse, "{input}");
+    }
+
+    assert_eq!(OrderId::new(7).to_string(), "order:7");
+    assert_eq!("ord-7".parse::<OrderId>().unwrap(), OrderId::new(7));
+    let error = "order:700".parse::<OrderId>().unwrap_err();
+    assert_eq!(error.kind(), branded::ErrorKind::Validation);
+}
+
+#[test]
+fn test_brand_const() {
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    #[derive(Branded)]
+    #[branded(brand = "Order")]
+    pub struct OrderId(u32);
+
+    assert_eq!(UserId::BRAND, "UserId");
+    assert_eq!(OrderId::BRAND, "Order");
+    assert_eq!(format!("{:#}", OrderId::new(7)), "Order:7");
+    assert_eq!("x".parse::<OrderId>().unwrap_err().brand(), "Order");
+}
+
+#[cfg(feature = "reflect")]
+#[test]
+fn test_constraints() {
+    use branded::constraints::{Bounds, Constraints};
+    use std::ops::Bound;
+
+    fn is_even(value: &i32) -> bool { value % 2 == 0 }
+
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    #[derive(Branded)]
+    #[branded(range = "-10..10", validate = "is_even")]
+    pub struct Offset(i32);
+
+    #[derive(Branded)]
+    #[branded(len = "..=8", format = "slug")]
+    pub struct Code(String);
+
+    assert!(UserId::CONSTRAINTS.is_empty());
+    assert_eq!(UserId::CONSTRAINTS, Constraints::NONE);
+    let constraints = Offset::CONSTRAINTS;
+    assert_eq!(constraints.range, Some(Bounds::new(Bound::Included("-10"), Bound::Excluded("10"))));
+    assert!(constraints.custom);
+    assert_eq!(constraints.len, None);
+    let constraints = Code::CONSTRAINTS;
+    assert_eq!(constraints.len, Some(Bounds::new(Bound::Unbounded, Bound::Included(8))));
+    assert_eq!(constraints.format, Some("slug"));
+    assert!(!constraints.custom);
+
+    #[cfg(feature = "serde")]
+    {
+        #[derive(Branded)]
+        #[branded(serde(prefix = "cus_"))]
+        pub struct CustomerId(u64);
+
+        assert_eq!(CustomerId::CONSTRAINTS.prefix, Some("cus_"));
+    }
+}
+
+#[test]
+fn test_column_const() {
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    #[derive(Branded)]
+    #[branded(brand = "PurchaseOrder")]
+    pub struct OrderId(u32);
+
+    #[derive(Branded)]
+    #[branded(column = "owner")]
+    pub struct OwnerId(u32);
+
+    assert_eq!(UserId::COLUMN, "user_id");
+    assert_eq!(OrderId::COLUMN, "purchase_order");
+    assert_eq!(OwnerId::COLUMN, "owner");
+}
+
 #[cfg(feature = "serde")]
 mod serde {
     use branded::Branded;
     use serde::de::DeserializeOwned;
     use serde::Serialize;
 
+    fn self_tested_names() -> [SelfTestedName; 2] {
+        [SelfTestedName::new("jane".to_owned()), SelfTestedName::new("doe".to_owned())]
+    }
+
+    #[derive(Branded)]
+    #[branded(serde, selftest(samples = "self_tested_names"))]
+    pub struct SelfTestedName(String);
+
     #[test]
     fn test_serde_derive() {
         #[derive(Branded)]
@@ -60,44 +413,2558 @@ mod serde {
         let recovered: UserId = serde_json::from_str(&json).unwrap();
         assert_eq!(recovered, id);
     }
-}
 
-#[cfg(feature = "sqlx")]
-mod sqlx {
-    use branded::Branded;
-    use sqlx::Database;
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_serde_borrowed() {
+        use std::borrow::Cow;
+
+        #[derive(Branded)]
+        #[branded(serde)]
+        pub struct Name<'a>(&'a str);
+
+        #[derive(Branded)]
+        #[branded(serde)]
+        pub struct Username<'a>(Cow<'a, str>);
+
+        #[derive(Branded)]
+        #[branded(serde, len = "1..=8")]
+        pub struct Code<'a>(Cow<'a, str>);
+
+        #[derive(serde::Deserialize)]
+        struct Record<'a> {
+            #[serde(borrow)]
+            name: Name<'a>,
+            #[serde(borrow)]
+            username: Username<'a>,
+        }
+
+        let input = String::from(r#"{"name":"Alice","username":"alice"}"#);
+        let record = serde_json::from_str::<Record>(&input).unwrap();
+        assert_eq!(*record.name.inner(), "Alice");
+        assert!(matches!(record.username.inner(), Cow::Borrowed("alice")));
+
+        let username = serde_json::from_str::<Username>(r#""al\"ice""#).unwrap();
+        assert!(matches!(username.inner(), Cow::Owned(name) if name == "al\"ice"));
+        assert!(matches!(
+            serde_json::from_str::<Code>(r#""abc""#).unwrap().inner(),
+            Cow::Borrowed(_)
+        ));
+        assert!(serde_json::from_str::<Code>(r#""""#).is_err());
+    }
 
     #[test]
-    fn test_sqlx_derive() {
+    fn test_serde_composite_key() {
         #[derive(Branded)]
-        #[branded(sqlx)]
+        #[branded(serde)]
+        pub struct TenantId(u32);
+
+        #[derive(Branded)]
+        #[branded(serde)]
         pub struct UserId(String);
 
-        fn needs_type<T: sqlx::Type<DB>, DB: Database>() {}
-        fn needs_encode<'en, T: sqlx::Encode<'en, DB>, DB: Database>() {}
-        fn needs_decode<'de, T: sqlx::Decode<'de, DB>, DB: Database>() {}
+        #[derive(Branded)]
+        #[branded(serde)]
+        pub struct TenantUserId(TenantId, UserId);
 
-        needs_type::<UserId, sqlx::Sqlite>();
-        needs_encode::<UserId, sqlx::Sqlite>();
-        needs_decode::<UserId, sqlx::Sqlite>();
+        let key = TenantUserId::new(TenantId::new(1), UserId::new("alice".to_string()));
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, r#"[1,"alice"]"#);
+        assert_eq!(serde_json::from_str::<TenantUserId>(&json).unwrap(), key);
     }
-}
 
-#[cfg(feature = "uuid")]
-mod uuid {
-    use branded::Branded;
+    #[test]
+    fn test_serde_envelope() {
+        use branded::envelope::Envelope;
+
+        #[derive(Branded)]
+        #[branded(serde)]
+        pub struct OrderId(u64);
+
+        let envelope = Envelope::new(OrderId::new(7), 2, "shipped".to_owned());
+        let json = serde_json::to_string(&envelope).unwrap();
+        assert_eq!(json, r#"{"aggregate_id":7,"sequence":2,"payload":"shipped"}"#);
+        assert_eq!(serde_json::from_str::<Envelope<OrderId, String>>(&json).unwrap(), envelope);
+
+        let reordered = r#"{"payload":"shipped","extra":null,"sequence":2,"aggregate_id":7}"#;
+        assert_eq!(serde_json::from_str::<Envelope<OrderId, String>>(reordered).unwrap(), envelope);
+        assert_eq!(
+            serde_json::from_str::<Envelope<OrderId, String>>(r#"[7,2,"shipped"]"#).unwrap(),
+            envelope
+        );
+        let error =
+            serde_json::from_str::<Envelope<OrderId, String>>(r#"{"aggregate_id":7,"payload":""}"#)
+                .unwrap_err();
+        assert!(error.to_string().contains("missing field `sequence`"));
+    }
 
+    #[cfg(feature = "alloc")]
     #[test]
-    fn test_uuid_derive() {
+    fn test_serde_transcode() {
+        use branded::serde::transcode::{transcode, Schema};
+
+        fn is_slug(slug: &str) -> bool { !slug.is_empty() && !slug.contains(' ') }
+
         #[derive(Branded)]
-        #[branded(uuid)]
+        #[branded(serde)]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        #[branded(serde, validate = "is_slug")]
+        pub struct Slug(String);
+
+        let schema = Schema::new()
+            .brand::<UserId>("user.id")
+            .brand::<Option<Slug>>("posts[].slug")
+            .brand::<Slug>("[].slug");
+        let run = |input: &str| {
+            let mut output = Vec::new();
+            transcode(
+                &schema,
+                &mut serde_json::Deserializer::from_str(input),
+                &mut serde_json::Serializer::new(&mut output),
+            )
+            .map(|()| String::from_utf8(output).unwrap())
+            .map_err(|error| error.to_string())
+        };
+
+        let input = r#"{"user":{"id":7,"name":"a b"},"posts":[{"slug":"hello"},{"slug":null}],"n":[1.5,true]}"#;
+        assert_eq!(run(input).unwrap(), input);
+        assert_eq!(run(r#"[{"slug":"a"}]"#).unwrap(), r#"[{"slug":"a"}]"#);
+
+        let error = run(r#"{"posts":[{"slug":"ok"},{"slug":"not ok"}]}"#).unwrap_err();
+        assert!(error.starts_with("invalid value at `posts[1].slug`"), "{error}");
+        let error = run(r#"{"user":{"id":"7"}}"#).unwrap_err();
+        assert!(error.starts_with("invalid value at `user.id`"), "{error}");
+        let error = run(r#"{"user":{"id":{}}}"#).unwrap_err();
+        assert!(error.starts_with("invalid value at `user.id`"), "{error}");
+        assert!(run(r#"[{"slug":null}]"#).is_err());
+    }
+
+    #[test]
+    fn test_serde_tagged_id() {
+        pub enum UserTag {}
+        impl branded::id::Tag for UserTag {
+            const NAME: &'static str = "UserId";
+            const COLUMN: &'static str = "user_id";
+        }
+        pub type UserId = branded::id::Id<UserTag, u64>;
+
+        let json = serde_json::to_string(&UserId::new(7)).unwrap();
+        assert_eq!(json, "7");
+        assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), UserId::new(7));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_serde_default_generate() {
+        #[derive(Branded)]
+        #[branded(uuid, serde(default_generate))]
         pub struct UserId(uuid::Uuid);
 
+        #[derive(serde::Deserialize)]
+        struct CreateUser {
+            id: UserId,
+        }
+
         let id = UserId::new_v4();
-        let str = id.to_string();
-        assert_eq!(str.len(), 36);
+        let json = format!(r#"{{"id":"{}"}}"#, id.inner());
+        assert_eq!(serde_json::from_str::<CreateUser>(&json).unwrap().id, id);
+        let generated = serde_json::from_str::<CreateUser>("{}").unwrap().id;
+        assert_ne!(generated, UserId::nil());
+        assert_ne!(generated, serde_json::from_str::<CreateUser>("{}").unwrap().id);
+        assert!(serde_json::from_str::<CreateUser>(r#"{"id":"x"}"#).is_err());
 
-        let nil = UserId::nil();
-        assert_eq!(nil.inner(), &uuid::Uuid::nil());
+        fn zero() -> u32 { 0 }
+
+        #[derive(Branded)]
+        #[branded(serde(default_generate), generator = "zero")]
+        pub struct Counter(u32);
+
+        assert_eq!(serde_json::from_str::<Counter>("null").unwrap(), Counter::new(0));
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        #[derive(Branded)]
+        #[branded(serde(as_string))]
+        pub struct ContentHash(u128);
+
+        let hash = ContentHash::new(u128::MAX);
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, r#""340282366920938463463374607431768211455""#);
+        assert_eq!(serde_json::from_str::<ContentHash>(&json).unwrap(), hash);
+        assert!(serde_json::from_str::<ContentHash>("42").is_err());
+        assert!(serde_json::from_str::<ContentHash>(r#""-1""#).is_err());
+    }
+
+    #[test]
+    fn test_serde_prefix() {
+        #[derive(Branded)]
+        #[branded(serde(prefix = "cus_"))]
+        pub struct CustomerId(u64);
+
+        #[derive(Branded)]
+        #[branded(serde(prefix = "inv_"), display(zero_pad = 4), range = "1..")]
+        pub struct InvoiceNumber(u32);
+
+        let json = serde_json::to_string(&CustomerId::new(42)).unwrap();
+        assert_eq!(json, r#""cus_42""#);
+        assert_eq!(serde_json::from_str::<CustomerId>(&json).unwrap(), CustomerId::new(42));
+        let error = serde_json::from_str::<CustomerId>(r#""acct_42""#).unwrap_err();
+        assert!(error.to_string().starts_with("invalid CustomerId"), "{error}");
+        assert!(error.to_string().contains("expected `cus_`"), "{error}");
+        assert!(serde_json::from_str::<CustomerId>(r#""cus_x""#).is_err());
+        assert!(serde_json::from_str::<CustomerId>("42").is_err());
+
+        let json = serde_json::to_string(&InvoiceNumber::new(7)).unwrap();
+        assert_eq!(json, r#""inv_0007""#);
+        assert_eq!(serde_json::from_str::<InvoiceNumber>(&json).unwrap(), InvoiceNumber::new(7));
+        assert!(serde_json::from_str::<InvoiceNumber>(r#""inv_7""#).is_err());
+        assert!(serde_json::from_str::<InvoiceNumber>(r#""inv_0000""#).is_err());
+    }
+
+    #[test]
+    fn test_serde_legacy_prefix() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static LEGACY: AtomicU64 = AtomicU64::new(0);
+
+        fn count_legacy(id: &UserId) { LEGACY.fetch_add(id.into_inner(), Ordering::Relaxed); }
+
+        #[derive(Branded)]
+        #[branded(serde(prefix = "user_", accept_legacy = "usr_", on_legacy = "count_legacy"))]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        #[branded(serde(prefix = "cus_", accept_legacy = ""))]
+        pub struct CustomerId(u64);
+
+        assert_eq!(serde_json::from_str::<UserId>(r#""user_1""#).unwrap(), UserId::new(1));
+        assert_eq!(LEGACY.load(Ordering::Relaxed), 0);
+        assert_eq!(serde_json::from_str::<UserId>(r#""usr_2""#).unwrap(), UserId::new(2));
+        assert_eq!(LEGACY.load(Ordering::Relaxed), 2);
+        assert_eq!(serde_json::to_string(&UserId::new(2)).unwrap(), r#""user_2""#);
+        let error = serde_json::from_str::<UserId>(r#""acct_3""#).unwrap_err();
+        assert!(error.to_string().contains("expected `user_`"), "{error}");
+        assert!(serde_json::from_str::<UserId>(r#""usr_x""#).is_err());
+        assert_eq!(LEGACY.load(Ordering::Relaxed), 2);
+
+        assert_eq!(serde_json::from_str::<CustomerId>(r#""cus_4""#).unwrap(), CustomerId::new(4));
+        assert_eq!(serde_json::from_str::<CustomerId>(r#""4""#).unwrap(), CustomerId::new(4));
+        assert!(serde_json::from_str::<CustomerId>(r#""acct_4""#).is_err());
+    }
+
+    #[test]
+    fn test_serde_wrapped() {
+        #[derive(Branded)]
+        #[branded(serde(style = "wrapped"))]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        #[branded(serde(style = "wrapped"), len = "1..=8")]
+        pub struct Code(String);
+
+        let json = serde_json::to_string(&UserId::new(42)).unwrap();
+        assert_eq!(json, r#"{"type":"UserId","value":42}"#);
+        assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), UserId::new(42));
+        let json = r#"{"value":42,"type":"UserId","version":1}"#;
+        assert_eq!(serde_json::from_str::<UserId>(json).unwrap(), UserId::new(42));
+        assert_eq!(serde_json::from_str::<UserId>(r#"["UserId",42]"#).unwrap(), UserId::new(42));
+        let error = serde_json::from_str::<UserId>(r#"{"type":"OrderId","value":42}"#).unwrap_err();
+        assert!(error.to_string().contains("expected the brand name `UserId`"), "{error}");
+        assert!(serde_json::from_str::<UserId>(r#"{"value":42}"#).is_err());
+        assert!(serde_json::from_str::<UserId>("42").is_err());
+
+        assert!(serde_json::from_str::<Code>(r#"{"type":"Code","value":"abc"}"#).is_ok());
+        assert!(serde_json::from_str::<Code>(r#"{"type":"Code","value":""}"#).is_err());
+    }
+
+    #[test]
+    fn test_serde_zero_pad() {
+        #[derive(Branded)]
+        #[branded(serde(as_string), display(zero_pad = 8))]
+        pub struct InvoiceNumber(u32);
+
+        let invoice = InvoiceNumber::new(1234);
+        let json = serde_json::to_string(&invoice).unwrap();
+        assert_eq!(json, r#""00001234""#);
+        assert_eq!(serde_json::from_str::<InvoiceNumber>(&json).unwrap(), invoice);
+        assert!(serde_json::from_str::<InvoiceNumber>(r#""1234""#).is_err());
+    }
+
+    #[test]
+    fn test_serde_validate() {
+        fn is_port(port: &u16) -> bool { *port != 0 }
+
+        #[derive(Branded)]
+        #[branded(serde, validate = "is_port")]
+        pub struct Port(u16);
+
+        #[derive(Branded)]
+        #[branded(serde(as_string), validate = "is_port")]
+        pub struct PortString(u16);
+
+        assert_eq!(serde_json::from_str::<Port>("8080").unwrap(), Port::new(8080));
+        let error = serde_json::from_str::<Port>("0").unwrap_err();
+        assert_eq!(error.to_string(), "invalid Port: validation failed");
+        assert!(serde_json::from_str::<PortString>(r#""0""#).is_err());
+
+        #[derive(Branded)]
+        #[branded(serde, range = "1..=100")]
+        pub struct Percent(u8);
+
+        #[derive(Branded)]
+        #[branded(serde, len = "1..=8")]
+        pub struct Code(String);
+
+        assert_eq!(serde_json::from_str::<Percent>("100").unwrap(), Percent::new(100));
+        assert!(serde_json::from_str::<Percent>("101").is_err());
+        assert!(serde_json::from_str::<Percent>("0").is_err());
+        assert!(serde_json::from_str::<Code>(r#""""#).is_err());
+        assert!(serde_json::from_str::<Code>(r#""ABCDEFGHI""#).is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_serde_pattern() {
+        #[derive(Branded)]
+        #[branded(serde, pattern = "^[a-z]+$")]
+        pub struct Slug(String);
+
+        assert_eq!(serde_json::from_str::<Slug>(r#""abc""#).unwrap().into_inner(), "abc");
+        let error = serde_json::from_str::<Slug>(r#""ABC""#).unwrap_err();
+        assert!(error.to_string().starts_with("invalid Slug: "));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_comma_separated() {
+        #[derive(Branded)]
+        #[branded(serde)]
+        pub struct UserId(u32);
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Query {
+            #[serde(with = "branded::serde::comma_separated")]
+            ids: Vec<UserId>,
+        }
+
+        let query = Query {
+            ids: vec![UserId::new(1), UserId::new(2), UserId::new(3)],
+        };
+        let json = serde_json::to_string(&query).unwrap();
+        assert_eq!(json, r#"{"ids":"1,2,3"}"#);
+        assert_eq!(serde_json::from_str::<Query>(&json).unwrap(), query);
+        assert_eq!(serde_json::from_str::<Query>(r#"{"ids":""}"#).unwrap().ids, []);
+        let error = serde_json::from_str::<Query>(r#"{"ids":"1,x,3"}"#).unwrap_err();
+        assert!(error.to_string().starts_with(r#"invalid list element 1 "x": "#));
+    }
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx {
+    use branded::Branded;
+    use sqlx::Database;
+
+    #[test]
+    fn test_sqlx_derive() {
+        #[derive(Branded)]
+        #[branded(sqlx)]
+        pub struct UserId(String);
+
+        fn needs_type<T: sqlx::Type<DB>, DB: Database>() {}
+        fn needs_encode<'en, T: sqlx::Encode<'en, DB>, DB: Database>() {}
+        fn needs_decode<'de, T: sqlx::Decode<'de, DB>, DB: Database>() {}
+
+        needs_type::<UserId, sqlx::Sqlite>();
+        needs_encode::<UserId, sqlx::Sqlite>();
+        needs_decode::<UserId, sqlx::Sqlite>();
+    }
+
+    #[test]
+    fn test_sqlx_postgres_derive() {
+        use sqlx::postgres::PgHasArrayType;
+        use sqlx::{Postgres, Type, TypeInfo};
+
+        #[derive(Branded)]
+        #[branded(sqlx(postgres))]
+        pub struct UserId(i64);
+
+        #[derive(Branded)]
+        #[branded(sqlx(postgres, type_name = "org_id"))]
+        pub struct OrgId(i64);
+
+        fn needs_encode<'en, T: sqlx::Encode<'en, Postgres>>() {}
+        fn needs_decode<'de, T: sqlx::Decode<'de, Postgres>>() {}
+
+        needs_encode::<Vec<UserId>>();
+        needs_decode::<Vec<UserId>>();
+        assert_eq!(UserId::type_info(), <i64 as Type<Postgres>>::type_info());
+        assert_eq!(UserId::array_type_info(), i64::array_type_info());
+        assert_eq!(OrgId::type_info().name(), "org_id");
+        assert_eq!(OrgId::array_type_info().name(), "_org_id");
+        assert!(OrgId::compatible(&<i64 as Type<Postgres>>::type_info()));
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_only() {
+        use sqlx::{Postgres, Sqlite};
+
+        #[derive(Branded)]
+        #[branded(sqlx(only = "postgres, sqlite"))]
+        pub struct UserId(i64);
+
+        #[derive(Branded)]
+        #[branded(sqlx(only = "sqlite", int128 = "text"))]
+        pub struct TraceId(u128);
+
+        fn needs_encode<'en, T: sqlx::Encode<'en, DB> + sqlx::Type<DB>, DB: Database>() {}
+        fn needs_decode<'de, T: sqlx::Decode<'de, DB> + sqlx::Type<DB>, DB: Database>() {}
+
+        needs_encode::<UserId, Postgres>();
+        needs_decode::<UserId, Postgres>();
+        needs_encode::<TraceId, Sqlite>();
+        needs_decode::<TraceId, Sqlite>();
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let user_id: UserId =
+            sqlx::query_scalar("SELECT ?").bind(UserId::new(7)).fetch_one(&pool).await.unwrap();
+        assert_eq!(user_id, UserId::new(7));
+        let trace_id: TraceId = sqlx::query_scalar("SELECT ?")
+            .bind(TraceId::new(u128::MAX))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(trace_id, TraceId::new(u128::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_validate() {
+        fn is_positive(value: &i64) -> bool { *value > 0 }
+
+        #[derive(Branded)]
+        #[branded(sqlx, validate = "is_positive")]
+        pub struct UserId(i64);
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let user_id: UserId = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await.unwrap();
+        assert_eq!(user_id, UserId::new(1));
+        let error = sqlx::query_scalar::<_, UserId>("SELECT 0").fetch_one(&pool).await.unwrap_err();
+        assert!(error.to_string().contains("invalid UserId: validation failed"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_table_derive() {
+        #[derive(Branded)]
+        #[branded(sqlx(table = "users", column = "user_id"))]
+        pub struct UserId(i64);
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE users (user_id INTEGER PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users (user_id) VALUES (1)").execute(&pool).await.unwrap();
+
+        assert!(UserId::new(1).exists(&pool).await.unwrap());
+        assert!(!UserId::new(2).exists(&pool).await.unwrap());
+        assert_eq!(UserId::new(2).delete(&pool).await.unwrap().rows_affected(), 0);
+        assert_eq!(UserId::new(1).delete(&pool).await.unwrap().rows_affected(), 1);
+        assert!(!UserId::new(1).exists(&pool).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_from_row_derive() {
+        #[derive(Branded)]
+        #[branded(sqlx(from_row))]
+        pub struct UserId(i64);
+
+        #[derive(Branded)]
+        #[branded(sqlx(from_row = "org_id"))]
+        pub struct OrgId(i64);
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, org_id INTEGER NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users (id, org_id) VALUES (1, 10), (2, 20)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let users = sqlx::query_as::<_, UserId>("SELECT id FROM users ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, [UserId::new(1), UserId::new(2)]);
+        let orgs = sqlx::query_as::<_, OrgId>("SELECT id, org_id FROM users ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(orgs, [OrgId::new(10), OrgId::new(20)]);
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_int128_derive() {
+        #[derive(Branded)]
+        #[branded(sqlx(int128 = "bytes"))]
+        pub struct ContentHash(u128);
+
+        #[derive(Branded)]
+        #[branded(sqlx(int128 = "text"))]
+        pub struct LedgerAmount(i128);
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE entries (hash BLOB NOT NULL, amount TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let hash = ContentHash::new(u128::MAX - 1);
+        let amount = LedgerAmount::new(i128::MIN);
+        sqlx::query("INSERT INTO entries (hash, amount) VALUES (?, ?)")
+            .bind(hash)
+            .bind(amount)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let row: (ContentHash, LedgerAmount, Vec<u8>, String) =
+            sqlx::query_as("SELECT hash, amount, hash, amount FROM entries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((row.0, row.1), (hash, amount));
+        assert_eq!(row.2, (u128::MAX - 1).to_be_bytes());
+        assert_eq!(row.3, i128::MIN.to_string());
+        let error = sqlx::query_scalar::<_, ContentHash>("SELECT x'0102'")
+            .fetch_one(&pool)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("expected 16 bytes"));
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    use branded::Branded;
+
+    #[derive(Branded)]
+    #[branded(uuid, selftest)]
+    pub struct SelfTestedUserId(uuid::Uuid);
+
+    #[test]
+    fn test_uuid_inner_traits() {
+        use uuid::Uuid;
+
+        #[derive(Branded)]
+        pub struct SessionId(uuid::Uuid);
+
+        #[derive(Branded)]
+        #[branded(uuid)]
+        pub struct UserId(Uuid);
+
+        let session_id = SessionId::new(Uuid::nil());
+        assert_eq!(session_id, SessionId::default());
+        assert_eq!(session_id.to_string().parse::<SessionId>().unwrap(), session_id);
+        assert!(UserId::nil() <= UserId::new_v4());
+    }
+
+    #[test]
+    fn test_uuid_derive() {
+        #[derive(Branded)]
+        #[branded(uuid)]
+        pub struct UserId(uuid::Uuid);
+
+        let id = UserId::new_v4();
+        let str = id.to_string();
+        assert_eq!(str.len(), 36);
+
+        let nil = UserId::nil();
+        assert_eq!(nil.inner(), &uuid::Uuid::nil());
+    }
+
+    #[test]
+    fn test_uuid_v5() {
+        #[derive(Branded)]
+        #[branded(uuid(v5 = "dns"))]
+        pub struct HostId(uuid::Uuid);
+
+        #[derive(Branded)]
+        #[branded(uuid(v5 = "3f0a9c2e-5b1d-4e8a-9c7f-2d6b8e4a1f03"))]
+        pub struct TenantId(uuid::Uuid);
+
+        assert_eq!(HostId::NAMESPACE, uuid::Uuid::NAMESPACE_DNS);
+        assert_eq!(
+            HostId::derive_from("example.com").to_string(),
+            "cfbff0d1-9375-5685-968c-48ce8b15ae17"
+        );
+        assert_eq!(TenantId::NAMESPACE, uuid::uuid!("3f0a9c2e-5b1d-4e8a-9c7f-2d6b8e4a1f03"));
+        assert_eq!(TenantId::derive_from("acme"), TenantId::derive_from(b"acme"));
+        assert_eq!(
+            *TenantId::derive_from("acme").inner(),
+            uuid::Uuid::new_v5(&TenantId::NAMESPACE, b"acme")
+        );
+    }
+
+    #[test]
+    fn test_uuid_for_test() {
+        #[derive(Branded)]
+        #[branded(uuid)]
+        pub struct UserId(uuid::Uuid);
+
+        #[derive(Branded)]
+        #[branded(uuid)]
+        pub struct OrderId(uuid::Uuid);
+
+        let alice = UserId::for_test("alice");
+        assert_eq!(alice, UserId::for_test("alice"));
+        assert_ne!(alice, UserId::for_test("bob"));
+        assert_ne!(*alice.inner(), *OrderId::for_test("alice").inner());
+        assert_eq!(alice.inner().get_version_num(), 5);
+        assert_eq!(alice.to_string(), "5efddbe4-981c-519f-a184-32cf7a916513");
+    }
+
+    #[test]
+    fn test_uuid_typeid() {
+        #[derive(Branded)]
+        #[branded(uuid, external(prefix = "user", v1 = "typeid"))]
+        pub struct UserId(uuid::Uuid);
+
+        let user_id = UserId::new(uuid::uuid!("01890a5d-ac96-774b-bcce-b302099a8057"));
+        assert_eq!(user_id.to_string(), "user_01h455vb4pex5vsknk084sn02q");
+        assert_eq!("user_01h455vb4pex5vsknk084sn02q".parse::<UserId>().unwrap(), user_id);
+        assert!("user_7zzzzzzzzzzzzzzzzzzzzzzzzz".parse::<UserId>().is_ok());
+        assert!("user_8zzzzzzzzzzzzzzzzzzzzzzzzz".parse::<UserId>().is_err());
+    }
+
+    #[test]
+    fn test_uuid_options() {
+        #[derive(Branded)]
+        #[branded(uuid(v7, format = "simple"))]
+        pub struct EventId(uuid::Uuid);
+
+        #[derive(Branded)]
+        #[branded(uuid(format = "urn"))]
+        pub struct DocumentId(uuid::Uuid);
+
+        let id = EventId::new_v7();
+        assert_eq!(id.inner().get_version_num(), 7);
+        assert_eq!(id.to_string(), id.inner().simple().to_string());
+        assert_eq!(EventId::nil().to_string(), "0".repeat(32));
+        assert_eq!(format!("{:#}", EventId::nil()), format!("EventId:{}", "0".repeat(32)));
+        assert!(DocumentId::new_v4().to_string().starts_with("urn:uuid:"));
+    }
+
+    #[test]
+    fn test_uuid_brand_macro() {
+        use branded::brand;
+
+        #[derive(Branded)]
+        #[branded(uuid, const_new)]
+        pub struct TenantId(uuid::Uuid);
+
+        const ROOT_TENANT: TenantId = brand!(TenantId, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(ROOT_TENANT.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    }
+
+    #[test]
+    fn test_uuid_time_bucket() {
+        use branded::time_bucket::TimeBucket;
+        use std::time::Duration;
+
+        #[derive(Branded)]
+        #[branded(uuid(v7))]
+        pub struct EventId(uuid::Uuid);
+
+        let hour = Duration::from_secs(3600);
+        let timestamp = uuid::Timestamp::from_unix(uuid::NoContext, 1_700_000_000, 0);
+        let id = EventId::new(uuid::Uuid::new_v7(timestamp));
+        let bucket = id.bucket(hour);
+        assert_eq!(bucket, TimeBucket::containing(1_700_000_000_000, hour));
+        assert_eq!(bucket.start_millis(), 1_699_999_200_000);
+        assert_eq!(bucket.end_millis(), 1_700_002_800_000);
+
+        let range = EventId::range_for_bucket(bucket);
+        assert!(range.contains(&id));
+        assert_eq!(range.start().inner().get_version_num(), 7);
+        assert_eq!(range.end().inner().get_version_num(), 7);
+        assert!(!EventId::range_for_bucket(bucket.next()).contains(&id));
+        assert!(!EventId::range_for_bucket(bucket.previous().unwrap()).contains(&id));
+        assert_eq!(range.end().bucket(hour), bucket);
+        assert_eq!(bucket.next().min_uuid_bytes(), {
+            let end = uuid::Timestamp::from_unix(uuid::NoContext, 1_700_002_800, 0);
+            let mut bytes = *uuid::Uuid::new_v7(end).as_bytes();
+            bytes[6..].copy_from_slice(&[0x70, 0, 0x80, 0, 0, 0, 0, 0, 0, 0]);
+            bytes
+        });
+    }
+}
+
+#[test]
+fn test_assert_brand() {
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    fn generic<B: Branded>(a: B, b: B) -> (B, B) {
+        branded::assert_brand!(a, b);
+        (a, b)
+    }
+
+    let (a, b) = generic(UserId::new(1), UserId::new(2));
+    branded::assert_brand!(a => UserId);
+    branded::assert_brand!(a, b, UserId::new(3));
+    assert_eq!(a.into_inner(), 1);
+}
+
+#[test]
+fn test_parse() {
+    use branded::TryParseBranded;
+
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    let id: UserId = branded::parse("123").unwrap();
+    assert_eq!(id.into_inner(), 123);
+    let id = "456".parse_branded::<UserId>().unwrap();
+    assert_eq!(id.into_inner(), 456);
+    assert!(branded::parse::<UserId>("abc").is_err());
+}
+
+#[test]
+fn test_generic_heapless_inner() {
+    #[derive(Branded)]
+    pub struct DeviceId<const N: usize>(heapless::String<N>);
+
+    let id = DeviceId::<16>::new(heapless::String::try_from("sensor-1").unwrap());
+    assert_eq!(id.to_string(), "sensor-1");
+    assert_eq!(format!("{id:?}"), r#"DeviceId("sensor-1")"#);
+    assert_eq!(id.clone(), id);
+    assert_eq!(DeviceId::<16>::default().inner().len(), 0);
+}
+
+#[test]
+fn test_generic_lifetime_and_type_params() {
+    use std::collections::HashSet;
+
+    #[derive(Branded)]
+    #[branded(partial_eq_inner, from, inner_mut)]
+    pub struct Key<'a>(&'a str);
+
+    #[derive(Branded)]
+    #[branded(len = "1..=5")]
+    pub struct Label<'a>(&'a str);
+
+    #[derive(Branded)]
+    #[branded(from, partial_eq_inner)]
+    pub struct Wrapper<T>(T)
+    where
+        T: Copy;
+
+    let input = String::from("alpha,beta");
+    let keys = input.split(',').map(Key::new).collect::<Vec<_>>();
+    assert_eq!(keys[0], "alpha");
+    assert_eq!(keys[1].to_string(), "beta");
+    assert_eq!(format!("{:?}", keys[0]), r#"Key("alpha")"#);
+    assert!(keys[0] < keys[1]);
+    assert_eq!(keys.iter().copied().collect::<HashSet<_>>().len(), 2);
+    let mut key: Key<'_> = "gamma".into();
+    *key.inner_mut() = "delta";
+    assert_eq!(key.into_inner(), "delta");
+    assert!(Label::is_valid("alpha"));
+    assert!(!Label::is_valid("gamma,delta"));
+
+    let wrapper = Wrapper::new(42u8);
+    assert_eq!(wrapper, 42);
+    assert_eq!("7".parse::<Wrapper<u8>>(), Ok(Wrapper::new(7)));
+    assert_eq!(Wrapper::from(1u32), Wrapper::new(1));
+    assert_eq!(Wrapper::<u16>::default(), Wrapper::new(0));
+}
+
+#[cfg(feature = "defmt")]
+mod defmt {
+    use branded::Branded;
+
+    #[test]
+    fn test_defmt_derive() {
+        #[derive(Branded)]
+        #[branded(defmt)]
+        pub struct DeviceId<const N: usize>(heapless::String<N>);
+
+        fn needs_format<T: defmt::Format>() {}
+
+        needs_format::<DeviceId<16>>();
+    }
+}
+
+#[cfg(feature = "polars")]
+mod polars {
+    use branded::Branded;
+    use polars::prelude::{AnyValue, DataType};
+
+    #[test]
+    fn test_polars_derive() {
+        #[derive(Branded)]
+        #[branded(polars)]
+        pub struct OrderId(u64);
+
+        #[derive(Branded)]
+        #[branded(polars)]
+        pub struct Sku(String);
+
+        let ids = vec![OrderId::new(1), OrderId::new(2), OrderId::new(3)];
+        let series = OrderId::into_series("order_id", ids);
+        assert_eq!(series.dtype(), &DataType::UInt64);
+        let ids = OrderId::try_from_series(&series).unwrap();
+        assert_eq!(ids, vec![OrderId::new(1), OrderId::new(2), OrderId::new(3)]);
+        assert!(Sku::try_from_series(&series).is_err());
+
+        let skus = vec![Sku::new("a".to_owned()), Sku::new("b".to_owned())];
+        let series = Sku::into_series("sku", skus);
+        assert_eq!(series.dtype(), &DataType::String);
+        assert_eq!(Sku::try_from_series(&series).unwrap()[1], Sku::new("b".to_owned()));
+
+        let value = AnyValue::from(OrderId::new(42));
+        assert_eq!(value, AnyValue::UInt64(42));
+        assert_eq!(OrderId::try_from(value).unwrap(), OrderId::new(42));
+        assert!(OrderId::try_from(AnyValue::Int32(1)).is_err());
+    }
+
+    #[test]
+    fn test_polars_validated() {
+        use polars::prelude::{NamedFrom, Series};
+
+        #[derive(Branded)]
+        #[branded(polars, range = "1..")]
+        pub struct OrderId(u64);
+
+        let series = Series::new("order_id".into(), [1u64, 0]);
+        assert!(OrderId::try_from_series(&series).is_err());
+        assert!(OrderId::try_from(AnyValue::UInt64(0)).is_err());
+        assert_eq!(OrderId::try_from(AnyValue::UInt64(1)).unwrap().into_inner(), 1);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_polars_uuid() {
+        #[derive(Branded)]
+        #[branded(polars, uuid)]
+        pub struct UserId(uuid::Uuid);
+
+        let ids = vec![UserId::new_v4(), UserId::new_v4()];
+        let series = UserId::into_series("user_id", ids.clone());
+        assert_eq!(series.dtype(), &DataType::Binary);
+        assert_eq!(UserId::try_from_series(&series).unwrap(), ids);
+    }
+}
+
+#[cfg(feature = "influxdb")]
+mod influxdb {
+    use branded::Branded;
+    use influxdb::{InfluxDbWriteable, Query, Timestamp, Type};
+
+    #[test]
+    fn test_influxdb_derive() {
+        #[derive(Branded)]
+        #[branded(influxdb)]
+        pub struct DeviceId(String);
+
+        #[derive(Branded)]
+        #[branded(influxdb)]
+        pub struct ShardId(u32);
+
+        let device = Type::from(DeviceId::new("dev-1".to_owned()));
+        assert!(matches!(device, Type::Text(text) if text == "dev-1"));
+        assert!(matches!(Type::from(ShardId::new(7)), Type::UnsignedInteger(7)));
+
+        let query = Timestamp::Seconds(0)
+            .into_query("temperature")
+            .add_tag("device", DeviceId::new("dev-1".to_owned()))
+            .add_field("shard", ShardId::new(7));
+        assert_eq!(query.build().unwrap().get(), "temperature,device=dev-1 shard=7i 0");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_influxdb_uuid() {
+        #[derive(Branded)]
+        #[branded(influxdb, uuid)]
+        pub struct SensorId(uuid::Uuid);
+
+        let id = SensorId::nil();
+        assert!(
+            matches!(Type::from(id), Type::Text(text) if text == uuid::Uuid::nil().to_string())
+        );
+    }
+}
+
+#[cfg(feature = "tonic")]
+mod tonic {
+    use branded::tonic::MetadataInterceptor;
+    use branded::Branded;
+    use tonic::metadata::{Ascii, MetadataValue};
+    use tonic::service::Interceptor;
+    use tonic::{Code, Request};
+
+    #[test]
+    fn test_tonic_derive() {
+        #[derive(Branded)]
+        #[branded(tonic)]
+        pub struct TenantId(u64);
+
+        let value = MetadataValue::<Ascii>::try_from(&TenantId::new(42)).unwrap();
+        assert_eq!(value, "42");
+        assert_eq!(TenantId::try_from(&value).unwrap(), TenantId::new(42));
+
+        let invalid = MetadataValue::<Ascii>::from_static("abc");
+        let status = TenantId::try_from(&invalid).unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert!(status.message().contains("TenantId"));
+    }
+
+    #[test]
+    fn test_tonic_validated() {
+        #[derive(Branded)]
+        #[branded(tonic, range = "1..")]
+        pub struct TenantId(u64);
+
+        let status = TenantId::try_from(&MetadataValue::from_static("0")).unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert!(status.message().contains("TenantId"));
+        assert!(TenantId::try_from(&MetadataValue::from_static("1")).is_ok());
+    }
+
+    #[test]
+    fn test_tonic_interceptor() {
+        #[derive(Branded)]
+        #[branded(tonic)]
+        pub struct TenantId(u64);
+
+        let mut interceptor = MetadataInterceptor::<TenantId>::new("x-tenant-id");
+
+        let mut request = Request::new(());
+        request.metadata_mut().insert("x-tenant-id", MetadataValue::from_static("7"));
+        let request = interceptor.call(request).unwrap();
+        assert_eq!(request.extensions().get::<TenantId>(), Some(&TenantId::new(7)));
+
+        let status = interceptor.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+}
+
+#[test]
+#[deny(deprecated)]
+fn test_alias_of() {
+    #[derive(Branded)]
+    #[deprecated(note = "use UserId instead")]
+    pub struct LegacyUserId(u32);
+
+    #[derive(Branded)]
+    #[branded(alias_of = "LegacyUserId")]
+    pub struct UserId(u32);
+
+    #[allow(deprecated)]
+    let legacy = LegacyUserId::new(42);
+    let user_id = UserId::from(legacy);
+    assert_eq!(user_id, UserId::new(42));
+    #[allow(deprecated)]
+    let legacy = LegacyUserId::from(user_id);
+    assert_eq!(legacy.into_inner(), 42);
+
+    #[derive(Branded)]
+    #[branded(constructor = "from_raw")]
+    pub struct LegacyOrderId(u32);
+
+    #[derive(Branded)]
+    #[branded(alias_of = "LegacyOrderId")]
+    pub struct OrderId(u32);
+
+    let legacy = LegacyOrderId::from(OrderId::new(7));
+    assert_eq!(legacy, LegacyOrderId::from_raw(7));
+    assert_eq!(OrderId::from(legacy), OrderId::new(7));
+}
+
+#[test]
+fn test_convert_from() {
+    fn is_even(id: &u32) -> bool { id % 2 == 0 }
+
+    #[derive(Branded)]
+    pub struct LegacyUserId(u32);
+
+    #[derive(Branded)]
+    pub struct ImportedUserId(u32);
+
+    #[derive(Branded)]
+    #[branded(convert_from(LegacyUserId, ImportedUserId))]
+    pub struct UserId(u32);
+
+    #[derive(Branded)]
+    #[branded(validate = "is_even", convert_from(LegacyUserId))]
+    pub struct EvenUserId(u32);
+
+    assert_eq!(UserId::from(LegacyUserId::new(1)), UserId::new(1));
+    assert_eq!(UserId::from(ImportedUserId::new(2)), UserId::new(2));
+    assert_eq!(EvenUserId::try_from(LegacyUserId::new(2)).unwrap(), EvenUserId::new(2));
+    assert!(EvenUserId::try_from(LegacyUserId::new(1)).is_err());
+}
+
+#[test]
+fn test_constructor() {
+    mod brands {
+        use branded::Branded;
+
+        #[derive(Branded)]
+        #[branded(constructor = "from_raw", constructor_vis = "pub(super)")]
+        pub struct Email(&'static str);
+
+        #[derive(Branded)]
+        #[branded(no_constructor)]
+        pub struct Port(u16);
+
+        impl Port {
+            pub fn try_new(value: u16) -> Option<Self> { (value != 0).then_some(Self(value)) }
+        }
+    }
+
+    assert_eq!(brands::Email::from_raw("a@example.com").into_inner(), "a@example.com");
+    assert_eq!(brands::Port::try_new(8080).map(branded::Branded::into_inner), Some(8080));
+    assert!(brands::Port::try_new(0).is_none());
+}
+
+#[test]
+fn test_inner_mut() {
+    #[derive(Branded)]
+    #[branded(inner_mut)]
+    pub struct Buffer(heapless::Vec<u8, 4>);
+
+    let mut buffer = Buffer::new(heapless::Vec::new());
+    buffer.inner_mut().push(1).unwrap();
+    buffer.inner_mut().push(2).unwrap();
+    assert_eq!(buffer.inner(), &[1, 2]);
+}
+
+#[test]
+fn test_brand_macro() {
+    use branded::brand;
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct UserId(u64);
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct Offset(i8);
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct Role(&'static str);
+
+    const SYSTEM_USER: UserId = brand!(UserId, "18446744073709551615");
+    const MIN_OFFSET: Offset = brand!(Offset, "-128");
+    static ADMIN: Role = brand!(Role, "admin");
+
+    assert_eq!(SYSTEM_USER, UserId::new(u64::MAX));
+    assert_eq!(MIN_OFFSET, Offset::new(i8::MIN));
+    assert_eq!(brand!(Offset, "127"), Offset::new(127));
+    assert_eq!(*ADMIN.inner(), "admin");
+}
+
+#[test]
+fn test_const_new() {
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct UserId(u64);
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct Role(&'static str);
+
+    const SYSTEM_USER: UserId = UserId::new(0);
+    static ADMIN: Role = Role::new("admin");
+
+    assert_eq!(SYSTEM_USER.into_inner(), 0);
+    assert_eq!(*ADMIN.inner(), "admin");
+}
+
+#[test]
+fn test_declare() {
+    branded::declare! {
+        /// The ID of a user.
+        pub UserId: u64 [const_new, partial_eq_inner];
+        OrderId: u64;
+        pub(crate) Sku: String [skip(Display)];
+    }
+
+    const ROOT: UserId = UserId::new(0);
+    assert_eq!(ROOT, 0);
+    assert_eq!(OrderId::new(7).to_string(), "7");
+    assert_eq!(Sku::new("A-1".to_string()).inner(), "A-1");
+}
+
+#[test]
+fn test_structural() {
+    use std::collections::BTreeSet;
+
+    #[derive(Branded, PartialEq, Eq)]
+    #[branded(structural)]
+    pub struct Opcode(u8);
+
+    impl Opcode {
+        const PING: Self = Self::new(1);
+        const PONG: Self = branded::brand!(Opcode, "2");
+    }
+
+    struct Handler<const OPCODE: u8>;
+
+    impl<const OPCODE: u8> Handler<OPCODE> {
+        fn opcode(&self) -> Opcode { Opcode::new(OPCODE) }
+    }
+
+    fn dispatch(opcode: Opcode) -> &'static str {
+        match opcode {
+            Opcode::PING => "ping",
+            Opcode::PONG => "pong",
+            _ => "unknown",
+        }
+    }
+
+    assert_eq!(dispatch(Opcode::new(1)), "ping");
+    assert_eq!(dispatch(Opcode::new(2)), "pong");
+    assert_eq!(dispatch(Opcode::new(3)), "unknown");
+    assert_eq!(Handler::<{ Opcode::PONG.get() }>.opcode(), Opcode::PONG);
+    assert_eq!(BTreeSet::from([Opcode::PONG, Opcode::PING]).first(), Some(&Opcode::PING));
+}
+
+#[test]
+fn test_from() {
+    #[derive(Branded)]
+    #[branded(from)]
+    pub struct UserId(String);
+
+    fn takes_inner(inner: impl Into<String>) -> String { inner.into() }
+
+    let user_id: UserId = "abc".to_owned().into();
+    assert_eq!(user_id, UserId::new("abc".to_owned()));
+    assert_eq!(takes_inner(user_id), "abc");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_try_from() {
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    #[branded(try_from)]
+    pub struct OrderId(u64);
+
+    #[derive(Branded)]
+    #[branded(try_from, display(zero_pad = 6))]
+    pub struct InvoiceNumber(u32);
+
+    assert_eq!(OrderId::try_from("42"), Ok(OrderId::new(42)));
+    assert_eq!(OrderId::try_from("42".to_owned()), Ok(OrderId::new(42)));
+    assert_eq!(OrderId::try_from("abc").unwrap_err().kind(), ErrorKind::Parse);
+    assert_eq!(InvoiceNumber::try_from("42").unwrap_err().kind(), ErrorKind::Length);
+
+    // Brands of other inner types can convert from both.
+    #[derive(Branded)]
+    #[branded(from, try_from)]
+    pub struct LineNumber(u32);
+
+    assert_eq!(LineNumber::from(7), LineNumber::new(7));
+    assert_eq!(LineNumber::try_from("7"), Ok(LineNumber::new(7)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_builder() {
+    use branded::ErrorKind;
+
+    fn is_slug(slug: &str) -> bool { slug.bytes().all(|b| b.is_ascii_lowercase() || b == b'-') }
+
+    #[derive(Branded)]
+    #[branded(builder)]
+    pub struct OrderId(u64);
+
+    #[derive(Branded)]
+    #[branded(builder, validate = "is_slug")]
+    pub struct Slug(String);
+
+    #[derive(Branded)]
+    #[branded(builder(inner))]
+    pub struct Name(String);
+
+    #[derive(Default)]
+    struct OrderBuilder {
+        id: Option<OrderId>,
+        slug: Option<Slug>,
+        name: Option<Name>,
+    }
+
+    impl OrderBuilder {
+        fn id(mut self, id: impl Into<OrderId>) -> Self {
+            self.id = Some(id.into());
+            self
+        }
+
+        fn try_slug<T: TryInto<Slug>>(mut self, slug: T) -> Result<Self, T::Error> {
+            self.slug = Some(slug.try_into()?);
+            Ok(self)
+        }
+
+        fn name(mut self, name: impl Into<Name>) -> Self {
+            self.name = Some(name.into());
+            self
+        }
+    }
+
+    let builder = OrderBuilder::default()
+        .id(42)
+        .try_slug("summer-sale")
+        .unwrap()
+        .try_slug("winter-sale".to_owned())
+        .unwrap()
+        .name("Summer sale".to_owned());
+    assert_eq!(builder.id, Some(OrderId::new(42)));
+    assert_eq!(builder.slug, Some(Slug::new("winter-sale".to_owned())));
+    assert_eq!(builder.name, Some(Name::new("Summer sale".to_owned())));
+    assert_eq!(OrderId::try_from("42"), Ok(OrderId::new(42)));
+
+    let error = OrderBuilder::default().try_slug("Summer Sale").err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Validation);
+    let error = OrderBuilder::default().try_slug("Summer Sale".to_owned()).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Validation);
+
+    #[derive(Branded)]
+    #[branded(builder)]
+    pub struct Label(&'static str);
+
+    #[derive(Branded)]
+    #[branded(builder, validate = "is_slug")]
+    pub struct Tag(&'static str);
+
+    assert_eq!(Label::from("sale"), Label::new("sale"));
+    assert_eq!(Tag::try_from("sale"), Ok(Tag::new("sale")));
+    assert_eq!(Tag::try_from("Sale").unwrap_err().kind(), ErrorKind::Validation);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_context() {
+    #[derive(Branded)]
+    #[branded(context, display(zero_pad = 4))]
+    pub struct OrderId(u64);
+
+    let context = OrderId::new(42).context();
+    assert_eq!(context.brand(), "OrderId");
+    assert_eq!(context.value(), "0042");
+    assert_eq!(context.to_string(), "OrderId:0042");
+}
+
+#[test]
+fn test_same_brand() {
+    use branded::same_brand::{self, SameBrand};
+    use std::cmp::Ordering;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    fn max_of<A: SameBrand<B> + Ord + Copy, B: Branded>(a: &A, b: &B) -> A {
+        match same_brand::cmp(a, b) {
+            Ordering::Less => *A::from_same_ref(b),
+            _ => *a,
+        }
+    }
+
+    assert!(same_brand::eq(&UserId::new(1), &UserId::new(1)));
+    assert!(!same_brand::eq(&UserId::new(1), &UserId::new(2)));
+    assert_eq!(max_of(&UserId::new(1), &UserId::new(2)), UserId::new(2));
+}
+
+#[test]
+fn test_tagged_id() {
+    use branded::id::{Id, Tag};
+    use branded::same_brand;
+    use std::collections::HashSet;
+
+    pub enum UserTag {}
+    impl Tag for UserTag {
+        const NAME: &'static str = "UserId";
+        const COLUMN: &'static str = "user_id";
+    }
+    pub type UserId = Id<UserTag, u64>;
+
+    let user_id: UserId = "42".parse().unwrap();
+    assert_eq!(user_id, UserId::new(42));
+    assert_eq!(*user_id.inner(), 42);
+    assert_eq!(user_id.to_string(), "42");
+    assert_eq!(format!("{user_id:?}"), "UserId(42)");
+    assert_eq!(UserId::BRAND, "UserId");
+    assert!(same_brand::eq(&user_id, &UserId::new(42)));
+    assert_eq!(HashSet::from([user_id, user_id]).len(), 1);
+
+    let error = "x".parse::<UserId>().unwrap_err();
+    assert_eq!(error.brand(), "UserId");
+    assert_eq!(error.kind(), branded::ErrorKind::Parse);
+}
+
+#[test]
+fn test_cast() {
+    use branded::cast::TransparentBrand;
+
+    #[derive(Branded)]
+    #[branded(cast)]
+    #[repr(transparent)]
+    pub struct UserId(u64);
+
+    let ids = [UserId::new(1), UserId::new(2)];
+    assert_eq!(UserId::as_inner_slice(&ids), &[1, 2]);
+    assert_eq!(UserId::from_inner_slice(&[1, 2]), &ids);
+
+    let ids = [Some(UserId::new(1)), None];
+    assert_eq!(UserId::as_inner_option_slice(&ids), &[Some(1), None]);
+    assert_eq!(UserId::from_inner_option_slice(&[Some(1), None]), &ids);
+
+    #[cfg(feature = "alloc")]
+    {
+        let inner = vec![Some(1), None, Some(3)];
+        let pointer = inner.as_ptr() as usize;
+        let ids = UserId::from_inner_option_vec(inner);
+        assert_eq!(ids, [Some(UserId::new(1)), None, Some(UserId::new(3))]);
+        let inner = UserId::into_inner_option_vec(ids);
+        assert_eq!(inner.as_ptr() as usize, pointer);
+        assert_eq!(UserId::into_inner_vec(UserId::from_inner_vec(vec![4, 5])), [4, 5]);
+    }
+}
+
+#[test]
+fn test_composite_key() {
+    use std::collections::HashSet;
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct TenantId(u32);
+
+    #[derive(Branded)]
+    #[branded(const_new)]
+    pub struct UserId(u64);
+
+    #[derive(Branded)]
+    pub struct TenantUserId(TenantId, UserId);
+
+    #[derive(Branded)]
+    #[branded(const_new, skip(Default))]
+    pub struct Membership {
+        tenant: TenantId,
+        member: UserId,
+    }
+
+    let key = TenantUserId::new(TenantId::new(1), UserId::new(2));
+    assert_eq!(*key.tenant_id(), TenantId::new(1));
+    assert_eq!(*key.user_id(), UserId::new(2));
+    assert_eq!(format!("{key:?}"), "TenantUserId(TenantId(1), UserId(2))");
+    assert!(key < TenantUserId::new(TenantId::new(1), UserId::new(3)));
+    assert!(key > TenantUserId::new(TenantId::new(0), UserId::new(3)));
+    assert_eq!(HashSet::from([key, key]).len(), 1);
+    assert_eq!(key.into_parts(), (TenantId::new(1), UserId::new(2)));
+    assert_eq!(TenantUserId::default(), TenantUserId::new(TenantId::new(0), UserId::new(0)));
+
+    const OWNER: Membership = Membership::new(TenantId::new(1), UserId::new(1));
+    assert_eq!(*OWNER.member(), UserId::new(1));
+    assert_eq!(format!("{OWNER:?}"), "Membership { tenant: TenantId(1), member: UserId(1) }");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_url_path() {
+    #[derive(Branded)]
+    pub struct OrgId(String);
+
+    #[derive(Branded)]
+    pub struct ProjectId(u64);
+
+    #[derive(Branded)]
+    #[branded(secret)]
+    pub struct ApiToken(String);
+
+    let org_id = OrgId::new("a/b c?d=é".to_owned());
+    let path = branded::path!("/orgs/{}/projects/{}/", org_id, ProjectId::new(7));
+    assert_eq!(path, "/orgs/a%2Fb%20c%3Fd%3D%C3%A9/projects/7/");
+    assert_eq!(branded::path!("/health"), "/health");
+    assert_eq!(branded::url::encode("Az09-._~").to_string(), "Az09-._~");
+    assert_eq!(ApiToken::new("hunter2".to_owned()).to_string(), "hunter2");
+}
+
+#[test]
+fn test_shard() {
+    #[derive(Branded)]
+    #[branded(shard(modulo = 64))]
+    pub struct TenantId(u64);
+
+    assert_eq!(TenantId::SHARDS, 64);
+    assert_eq!(TenantId::new(130).shard(), 2);
+
+    #[cfg(feature = "alloc")]
+    {
+        #[derive(Branded)]
+        #[branded(shard(modulo = 10))]
+        pub struct Username(String);
+
+        // The FNV-1a hash of "alice" is 0x508b2abb65a03907.
+        let expected = (0x508b2abb65a03907_u64 % 10) as u16;
+        assert_eq!(Username::new("alice".to_owned()).shard(), expected);
+    }
+}
+
+#[test]
+fn test_partial_eq_inner() {
+    #[derive(Branded)]
+    #[branded(partial_eq_inner)]
+    pub struct OrderId(u64);
+
+    #[derive(Branded)]
+    #[branded(partial_eq_inner)]
+    pub struct Username(String);
+
+    assert_eq!(OrderId::new(42), 42);
+    assert_ne!(OrderId::new(42), 7);
+    let username = Username::new("alice".to_owned());
+    assert_eq!(username, "alice");
+    assert_eq!(username, *"alice");
+    assert_eq!(username, "alice".to_owned());
+    assert_ne!(username, "bob");
+}
+
+#[test]
+fn test_generator() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT: AtomicU64 = AtomicU64::new(100);
+
+    fn next_id() -> u64 { NEXT.fetch_add(1, Ordering::Relaxed) }
+
+    #[derive(Branded)]
+    #[branded(generator = "next_id")]
+    pub struct JobId(u64);
+
+    #[derive(Branded)]
+    #[branded(generator(function = "next_id", default))]
+    pub struct TaskId(u64);
+
+    assert!(JobId::generate().into_inner() >= 100);
+    assert_eq!(JobId::default(), JobId::new(0));
+    assert!(TaskId::default().into_inner() >= 100);
+
+    #[derive(Branded)]
+    #[branded(generator(function = "next_id", default), range = "100..")]
+    pub struct RunId(u64);
+
+    assert!(RunId::default().into_inner() >= 100);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_override_generator() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT: AtomicU64 = AtomicU64::new(100);
+
+    fn next_id() -> u64 { NEXT.fetch_add(1, Ordering::Relaxed) }
+
+    #[derive(Branded)]
+    #[branded(generator = "next_id")]
+    pub struct JobId(u64);
+
+    #[derive(Branded)]
+    #[branded(generator = "next_id")]
+    pub struct TaskId(u64);
+
+    JobId::override_generator(Some(|| 7));
+    assert_eq!(JobId::generate(), JobId::new(7));
+    assert_ne!(TaskId::generate(), TaskId::new(7));
+    JobId::override_generator(None);
+    assert_ne!(JobId::generate(), JobId::new(7));
+}
+
+#[test]
+fn test_on_generate() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static GENERATED: RefCell<Vec<(&'static str, u64)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record<B: Branded<Inner = u64>>(value: &B) {
+        GENERATED.with(|generated| generated.borrow_mut().push((B::BRAND, *value.inner())));
+    }
+
+    #[derive(Branded)]
+    #[branded(generator(function = "u64::default", default), on_generate = "record")]
+    pub struct JobId(u64);
+
+    JobId::generate();
+    JobId::default();
+    JobId::new(1);
+
+    let generated = GENERATED.with(|generated| generated.take());
+    assert_eq!(generated, [("JobId", 0), ("JobId", 0)]);
+}
+
+#[test]
+fn test_implements() {
+    pub trait EntityId: Branded {}
+    pub trait Auditable {}
+
+    #[derive(Branded)]
+    #[branded(implements(EntityId, Auditable))]
+    pub struct UserId(u32);
+
+    #[derive(Branded)]
+    #[branded(implements(EntityId))]
+    pub struct OrderId(u32);
+
+    fn needs_entity_id<T: EntityId>() {}
+    fn needs_auditable<T: Auditable>() {}
+
+    needs_entity_id::<UserId>();
+    needs_entity_id::<OrderId>();
+    needs_auditable::<UserId>();
+}
+
+#[test]
+fn test_from_str() {
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    assert_eq!("42".parse::<UserId>(), Ok(UserId::new(42)));
+    let error = "abc".parse::<UserId>().unwrap_err();
+    assert_eq!(error.brand(), "UserId");
+    assert_eq!(error.kind(), ErrorKind::Parse);
+}
+
+#[test]
+fn test_validate() {
+    use branded::ErrorKind;
+
+    fn is_port(port: &u16) -> bool { *port != 0 }
+
+    #[derive(Branded)]
+    #[branded(validate = "is_port")]
+    pub struct Port(u16);
+
+    #[derive(Branded)]
+    #[branded(checksum = "luhn", validate = "is_port")]
+    pub struct CheckedPort(u16);
+
+    assert_eq!(Port::try_new(8080), Ok(Port::new(8080)));
+    let error = Port::try_new(0).unwrap_err();
+    assert_eq!(error.brand(), "Port");
+    assert_eq!(error.kind(), ErrorKind::Validation);
+    assert_eq!("0".parse::<Port>().unwrap_err().kind(), ErrorKind::Validation);
+    assert_eq!("abc".parse::<Port>().unwrap_err().kind(), ErrorKind::Parse);
+    assert_eq!("00".parse::<CheckedPort>().unwrap_err().kind(), ErrorKind::Validation);
+}
+
+#[test]
+fn test_len_range() {
+    #[derive(Branded)]
+    #[branded(len = "1..=4")]
+    pub struct Code(&'static str);
+
+    #[derive(Branded)]
+    #[branded(range = "1..")]
+    pub struct Port(u16);
+
+    #[derive(Branded)]
+    #[branded(range = "-10..10")]
+    pub struct Offset(i8);
+
+    assert!(Code::try_new("").is_err());
+    assert!(Code::try_new("æøå").is_ok());
+    assert!(Code::try_new("abcde").is_err());
+    assert!(Port::try_new(1).is_ok());
+    assert!(Port::try_new(0).is_err());
+    assert!("8080".parse::<Port>().is_ok());
+    assert!("0".parse::<Port>().is_err());
+    assert!("-10".parse::<Offset>().is_ok());
+    assert!("10".parse::<Offset>().is_err());
+}
+
+#[test]
+fn test_format() {
+    use branded::format::{is_email, is_hostname, is_slug, is_url, is_uuid_string};
+
+    #[derive(Branded)]
+    #[branded(format = "email", len = "..=32")]
+    pub struct EmailAddress(&'static str);
+
+    assert!(EmailAddress::try_new("alice@example.com").is_ok());
+    assert!(EmailAddress::try_new("alice@localhost").is_err());
+    assert!(EmailAddress::try_new("a-very-long-name@subdomain.example.com").is_err());
+
+    assert!(is_email("first.last+tag@mail.example.co"));
+    assert!(!is_email("first..last@example.com"));
+    assert!(!is_email("@example.com"));
+    assert!(is_url("https://example.com/path?query#fragment"));
+    assert!(is_url("postgres://user@db:5432"));
+    assert!(!is_url("https:///path"));
+    assert!(!is_url("example.com"));
+    assert!(!is_url("https://exa mple.com"));
+    assert!(is_hostname("api-2.example.com"));
+    assert!(!is_hostname("-api.example.com"));
+    assert!(!is_hostname("example..com"));
+    assert!(is_slug("hello-world-2"));
+    assert!(!is_slug("Hello-world"));
+    assert!(!is_slug("hello--world"));
+    assert!(!is_slug(""));
+    assert!(is_uuid_string("67e55044-10b1-426f-9247-BB680E5FE0C8"));
+    assert!(!is_uuid_string("67e5504410b1426f9247bb680e5fe0c8"));
+    assert!(!is_uuid_string("67e55044-10b1-426f-9247-bb680e5fe0c8-0"));
+}
+
+#[test]
+fn test_as_ref_borrow() {
+    use std::collections::HashMap;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    fn takes_as_ref(id: impl AsRef<u64>) -> u64 { *id.as_ref() }
+
+    let mut names = HashMap::new();
+    names.insert(UserId::new(42), "alice");
+    assert_eq!(names.get(&42), Some(&"alice"));
+    assert_eq!(takes_as_ref(UserId::new(7)), 7);
+}
+
+#[test]
+fn test_borrow_str() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Branded)]
+    pub struct Username(String);
+
+    let mut ages = HashMap::new();
+    ages.insert(Username::new("alice".to_owned()), 30);
+    assert_eq!(ages.get("alice"), Some(&30));
+
+    let mut ranks = BTreeMap::new();
+    ranks.insert(Username::new("bob".to_owned()), 1);
+    assert_eq!(ranks.get("bob"), Some(&1));
+
+    let username = Username::new("carol".to_owned());
+    let name: &str = username.as_ref();
+    assert_eq!(name, "carol");
+}
+
+#[test]
+fn test_display_padding() {
+    #[derive(Branded)]
+    pub struct UserId(u32);
+
+    let user_id = UserId::new(42);
+    assert_eq!(format!("{user_id:>6}"), "    42");
+    assert_eq!(format!("{user_id:<6}|"), "42    |");
+    assert_eq!(format!("{user_id:06}"), "000042");
+    assert_eq!(format!("{user_id:#06}"), "UserId:000042");
+}
+
+#[test]
+fn test_render() {
+    mod render {
+        use std::fmt;
+
+        pub fn hex(bytes: &[u8; 4], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+        }
+    }
+
+    #[derive(Branded)]
+    #[branded(render = "render::hex")]
+    pub struct Checksum([u8; 4]);
+
+    let checksum = Checksum::new([0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(checksum.to_string(), "deadbeef");
+    assert_eq!(format!("{checksum:#}"), "Checksum:deadbeef");
+    assert_eq!(format!("{checksum:?}"), "Checksum(deadbeef)");
+}
+
+#[test]
+fn test_zero_pad() {
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    #[branded(display(zero_pad = 8))]
+    pub struct InvoiceNumber(u32);
+
+    let invoice = InvoiceNumber::new(1234);
+    assert_eq!(invoice.to_string(), "00001234");
+    assert_eq!(format!("{invoice:#}"), "InvoiceNumber:00001234");
+    assert_eq!(InvoiceNumber::new(123456789).to_string(), "123456789");
+    assert_eq!("00001234".parse::<InvoiceNumber>(), Ok(invoice));
+    assert_eq!("1234".parse::<InvoiceNumber>().unwrap_err().kind(), ErrorKind::Length);
+    assert_eq!("0000123x".parse::<InvoiceNumber>().unwrap_err().kind(), ErrorKind::Parse);
+}
+
+#[test]
+fn test_write_to() {
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    let mut buf = heapless::String::<16>::new();
+    UserId::new(42).write_to(&mut buf).unwrap();
+    assert_eq!(buf, "42");
+}
+
+#[test]
+fn test_id_list() {
+    use branded::fmt::IdList;
+
+    #[derive(Branded)]
+    pub struct UserId(&'static str);
+
+    let ids = [UserId::new("a1b2c3d4e5f6"), UserId::new("c3d4"), UserId::new("e5f6a7b8c9")];
+    assert_eq!(IdList::new(&ids).to_string(), "3 UserIds: [a1b2c3d4…, c3d4, e5f6a7b8…]");
+    assert_eq!(IdList::new(&ids).limit(2).width(4).to_string(), "3 UserIds: [a1b2…, c3d4, +1]");
+    assert_eq!(IdList::new(&ids).limit(0).to_string(), "3 UserIds: [+3]");
+    assert_eq!(format!("{:?}", IdList::new(&ids[..1])), "1 UserId: [a1b2c3d4…]");
+    assert_eq!(IdList::<UserId>::new(&[]).to_string(), "0 UserIds: []");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_notify() {
+    use branded::notify::{self, Payload};
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    #[derive(Branded)]
+    pub struct OrderId(u64);
+
+    #[derive(Branded)]
+    #[branded(range = "1..")]
+    pub struct Page(u32);
+
+    let payload = notify::encode(&UserId::new(42));
+    assert_eq!(payload, "UserId:42");
+    assert_eq!(Payload::new(&Page::new(3)).to_string(), "Page:3");
+    assert_eq!(notify::decode::<UserId>(&payload), Ok(UserId::new(42)));
+
+    let error = notify::decode::<OrderId>(&payload).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::WrongPrefix("OrderId"));
+    assert_eq!(error.input(), Some("UserId:42"));
+    assert_eq!(
+        notify::decode::<UserId>("UserId42").unwrap_err().kind(),
+        ErrorKind::WrongPrefix("UserId")
+    );
+    assert_eq!(notify::decode::<UserId>("UserId:abc").unwrap_err().kind(), ErrorKind::Parse);
+    assert_eq!(notify::decode::<Page>("Page:0").unwrap_err().kind(), ErrorKind::Validation);
+}
+
+#[test]
+fn test_envelope() {
+    use branded::envelope::Envelope;
+
+    #[derive(Branded)]
+    pub struct OrderId(u64);
+
+    let placed = Envelope::new(OrderId::new(7), 1, "placed");
+    let shipped = placed.next("shipped");
+    assert_eq!(shipped, Envelope::new(OrderId::new(7), 2, "shipped"));
+    assert_eq!(shipped.map(str::len), Envelope::new(OrderId::new(7), 2, 7));
+}
+
+#[test]
+fn test_diff() {
+    use branded::diff::Diff;
+
+    #[derive(Branded)]
+    pub struct UserId(&'static str);
+
+    let left = [UserId::new("a1b2c3d4e5f6"), UserId::new("c3d4"), UserId::new("e5f6")];
+    let right = [UserId::new("a1b2c3d4e5f6"), UserId::new("c3d5")];
+    assert!(Diff::new(&left, &left).is_empty());
+    assert_eq!(Diff::new(&left, &left).to_string(), "");
+    assert!(!Diff::new(&left, &right).is_empty());
+    assert_eq!(
+        format!("{:?}", Diff::new(&left, &right)),
+        "[1] UserId: c3d4 != c3d5\n[2] UserId: e5f6 != (missing)"
+    );
+    let right = [UserId::new("a1b2c3d4e5f7")];
+    assert_eq!(Diff::new(&left[..1], &right).to_string(), "[0] UserId: a1b2c3d4… != a1b2c3d4…");
+    assert_eq!(
+        Diff::new(&left[..1], &right).width(usize::MAX).to_string(),
+        "[0] UserId: a1b2c3d4e5f6 != a1b2c3d4e5f7"
+    );
+
+    branded::assert_brands_eq!(
+        left,
+        [UserId::new("a1b2c3d4e5f6"), UserId::new("c3d4"), UserId::new("e5f6")]
+    );
+    let panic =
+        std::panic::catch_unwind(|| branded::assert_brands_eq!(left[..1], right)).unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "brands are not equal:\n[0] UserId: a1b2c3d4… != a1b2c3d4…"
+    );
+}
+
+#[cfg(feature = "compact_str")]
+mod compact_str {
+    use branded::Branded;
+
+    #[test]
+    fn test_to_compact_string() {
+        #[derive(Branded)]
+        #[branded(compact_str)]
+        pub struct UserId(u64);
+
+        let id = UserId::new(1234).to_compact_string();
+        assert_eq!(id, "1234");
+        assert!(!id.is_heap_allocated());
+    }
+}
+
+#[cfg(feature = "regex")]
+mod regex {
+    use branded::{Branded, ErrorKind};
+
+    #[test]
+    fn test_pattern() {
+        fn is_not_reserved(username: &String) -> bool { username != "admin" }
+
+        #[derive(Branded)]
+        #[branded(pattern = "^[a-z0-9_-]{3,32}$", validate = "is_not_reserved")]
+        pub struct Username(String);
+
+        #[derive(Branded)]
+        #[branded(pattern = "^[a-z]+$")]
+        pub struct Slug(&'static str);
+
+        assert!(Username::try_new("alice".to_owned()).is_ok());
+        assert!(Username::try_new("al".to_owned()).is_err());
+        assert!(Username::try_new("admin".to_owned()).is_err());
+        let error = "Alice!".parse::<Username>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Validation);
+        assert_eq!(error.input(), Some("Alice!"));
+        assert!(Slug::try_new("hello").is_ok());
+        assert!(Slug::try_new("hello-world").is_err());
+    }
+
+    #[test]
+    fn test_pattern_over_size_limit() {
+        let pattern = branded::regex::Pattern::new(r"\w{1000}{1000}");
+        assert!(!pattern.is_match("hello"));
+        assert!(!branded::regex::Pattern::new("(").is_match("("));
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck {
+    use branded::Branded;
+    use bytemuck::TransparentWrapper;
+
+    #[test]
+    fn test_bytemuck() {
+        #[derive(Branded)]
+        #[branded(bytemuck)]
+        #[repr(transparent)]
+        pub struct EntityId(u64);
+
+        let index = [1u64, 2, 3];
+        let ids: &[EntityId] = bytemuck::cast_slice(&index);
+        assert_eq!(ids, [EntityId::new(1), EntityId::new(2), EntityId::new(3)]);
+        assert_eq!(EntityId::wrap_slice(&index), ids);
+        assert_eq!(bytemuck::bytes_of(&EntityId::new(1)), 1u64.to_ne_bytes());
+        assert_eq!(<EntityId as bytemuck::Zeroable>::zeroed(), EntityId::new(0));
+    }
+}
+
+#[cfg(feature = "verify")]
+mod verify {
+    use branded::Branded;
+
+    fn is_port(port: &u32) -> bool { (1..=65535).contains(port) }
+
+    #[test]
+    fn test_verify_contracts() {
+        #[derive(Branded)]
+        #[branded(validate = "is_port")]
+        pub struct Port(u32);
+
+        #[derive(Branded)]
+        #[branded(range = "1..=10")]
+        pub struct Level(u8);
+
+        assert_eq!(Port::try_new(80).unwrap(), Port::new(80));
+        assert!(Port::try_new(0).is_err());
+        assert!(Level::try_new(11).is_err());
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod tracing {
+    use branded::{record_ids, Branded};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// A subscriber that collects the recorded span fields.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().push(format!("{}={value:?}", field.name()));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id { Id::from_u64(1) }
+
+        fn record(&self, _: &Id, values: &Record<'_>) { values.record(&mut self.clone()) }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_record_ids() {
+        #[derive(Branded)]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        pub struct OrderId(&'static str);
+
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let user_id = UserId::new(1);
+            let order = (OrderId::new("ord_2"),);
+            let span = tracing::info_span!(
+                "checkout",
+                user_id = tracing::field::Empty,
+                order_id = tracing::field::Empty
+            );
+            record_ids!(span, user_id, order_id = order.0);
+        });
+        assert_eq!(*recorder.0.lock().unwrap(), ["user_id=1", "order_id=ord_2"]);
+    }
+}
+
+#[test]
+fn test_skip() {
+    #[derive(Branded)]
+    #[branded(skip(Default, Display, Debug, Ord))]
+    pub struct SessionToken(String);
+
+    impl std::fmt::Debug for SessionToken {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("SessionToken(<redacted>)")
+        }
+    }
+
+    let token = SessionToken::new("secret".to_owned());
+    assert_eq!(format!("{token:?}"), "SessionToken(<redacted>)");
+    assert_eq!(token.clone(), token);
+    assert!(token.partial_cmp(&token.clone()).is_some());
+}
+
+#[test]
+fn test_inner_traits() {
+    #[derive(Branded)]
+    pub struct Price(f64);
+
+    #[derive(Branded)]
+    pub struct Label(&'static str);
+
+    #[derive(Branded)]
+    pub struct Wrapper<T>(T);
+
+    pub struct Opaque;
+
+    assert!(Price::new(1.5) < Price::new(2.0));
+    assert_eq!(Price::default(), Price::new(0.0));
+    assert_eq!("2.5".parse::<Price>().unwrap(), Price::new(2.5));
+    assert_eq!(Label::default().to_string(), "");
+    assert_eq!(Label::new("a").clone(), Label::new("a"));
+    assert_eq!(Wrapper::new(1).clone(), Wrapper::new(1));
+    // The impls of a generic brand only apply where the type parameter implements the traits.
+    let _ = Wrapper::new(Opaque).into_inner();
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Branded)]
+    pub struct Origin(Point);
+
+    let origin = Origin::new(Point {
+        x: 0,
+        y: 0,
+    });
+    assert_eq!(origin.clone(), origin);
+    assert_eq!(format!("{origin:?}"), "Origin(Point { x: 0, y: 0 })");
+}
+
+#[test]
+fn test_path_derive() {
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    pub struct OrgId(String);
+
+    #[derive(Branded)]
+    pub struct ProjectId(u32);
+
+    #[derive(Branded)]
+    #[branded(path = "orgs/{OrgId}/projects/{ProjectId}")]
+    pub struct ProjectName((OrgId, ProjectId));
+
+    let name = ProjectName::new((OrgId::new("acme".to_owned()), ProjectId::new(7)));
+    assert_eq!(name.to_string(), "orgs/acme/projects/7");
+    assert_eq!(format!("{name:#}"), "ProjectName:orgs/acme/projects/7");
+    assert_eq!(name.org_id(), &OrgId::new("acme".to_owned()));
+    assert_eq!(name.project_id(), &ProjectId::new(7));
+
+    assert_eq!("orgs/acme/projects/7".parse::<ProjectName>().unwrap(), name);
+    let kind = |input: &str| input.parse::<ProjectName>().unwrap_err().kind();
+    assert_eq!(kind("users/acme"), ErrorKind::WrongPrefix("orgs/"));
+    assert_eq!(kind("orgs/acme"), ErrorKind::WrongPrefix("/projects/"));
+    assert_eq!(kind("orgs//projects/7"), ErrorKind::Length);
+    assert_eq!(kind("orgs/acme/projects/x"), ErrorKind::Parse);
+    assert_eq!(kind("orgs/acme/projects/7/x"), ErrorKind::Parse);
+
+    let error = "orgs/acme/projects/x".parse::<ProjectName>().unwrap_err();
+    assert_eq!(error.brand(), "ProjectName");
+    #[cfg(feature = "alloc")]
+    assert_eq!(error.input(), Some("orgs/acme/projects/x"));
+}
+
+#[test]
+fn test_checksum() {
+    use branded::ErrorKind;
+
+    #[derive(Branded)]
+    #[branded(checksum = "luhn")]
+    pub struct AccountNumber(u64);
+
+    #[derive(Branded)]
+    #[branded(checksum = "crc")]
+    pub struct TicketCode(String);
+
+    let account = AccountNumber::new(7992739871);
+    assert_eq!(account.to_string(), "79927398713");
+    assert_eq!(format!("{account:#}"), "AccountNumber:79927398713");
+    assert_eq!("79927398713".parse::<AccountNumber>(), Ok(account));
+    let kind = |input: &str| input.parse::<AccountNumber>().unwrap_err().kind();
+    assert_eq!(kind("79927398712"), ErrorKind::Checksum);
+    assert_eq!(kind("79927938713"), ErrorKind::Checksum);
+    assert_eq!(kind(""), ErrorKind::Length);
+    assert_eq!(kind("0"), ErrorKind::Parse);
+
+    let code = TicketCode::new("support-42".to_owned());
+    let rendered = code.to_string();
+    assert_eq!(rendered.parse::<TicketCode>().unwrap(), code);
+    assert_eq!(rendered.to_lowercase()[..10], *"support-42");
+    assert!("support-43".parse::<TicketCode>().is_err());
+    assert!(rendered.replace("42", "24").parse::<TicketCode>().is_err());
+}
+
+#[test]
+fn test_parser() {
+    use branded::checksum::Crc;
+    use branded::parser::Parser;
+    use branded::ErrorKind;
+
+    let mut parser = Parser::new("ProjectName", b"orgs/acme/projects/7");
+    parser.expect("orgs/").unwrap();
+    assert_eq!(parser.component(Some("/")).unwrap(), "acme");
+    parser.expect("/projects/").unwrap();
+    assert_eq!(parser.parse::<u32>(None).unwrap(), 7);
+    parser.finish().unwrap();
+
+    let mut parser = Parser::new("ProjectName", b"orgs/acme/projects/x");
+    parser.expect("orgs/").unwrap();
+    let error = parser.expect("acme/tasks/").unwrap_err();
+    assert_eq!((error.kind(), error.offset()), (ErrorKind::WrongPrefix("acme/tasks/"), 5));
+    parser.expect("acme/projects/").unwrap();
+    let error = parser.parse::<u32>(None).unwrap_err();
+    assert_eq!((error.kind(), error.offset()), (ErrorKind::Parse, 19));
+    assert_eq!(error.to_string(), "invalid ProjectName: malformed value at byte 19");
+
+    let mut parser = Parser::new("Ticket", b"tk-ab\xffc");
+    parser.expect("tk-").unwrap();
+    let error = parser.component(None).unwrap_err();
+    assert_eq!((error.kind(), error.offset()), (ErrorKind::Parse, 5));
+
+    let mut parser = Parser::new("Ticket", b"abc0");
+    let error = parser.split_check::<Crc>().unwrap_err();
+    assert_eq!((error.kind(), error.offset()), (ErrorKind::Checksum, 3));
+    assert_eq!(parser.remaining(), b"abc0");
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use branded::axum::IdPath;
+    use branded::Branded;
+    use tower::ServiceExt;
+
+    #[derive(Branded)]
+    pub struct UserId(u64);
+
+    async fn request(uri: &str) -> (StatusCode, String) {
+        async fn handler(IdPath(user_id): IdPath<UserId>) -> String { format!("user {user_id}") }
+
+        let app = Router::new().route("/users/{user_id}", get(handler));
+        let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_id_path() {
+        assert_eq!(request("/users/42").await, (StatusCode::OK, "user 42".to_owned()));
+
+        let (status, body) = request("/users/abc").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, r#"invalid UserId in path: invalid UserId "abc": malformed value"#);
+    }
+}
+
+#[cfg(feature = "okapi")]
+mod okapi {
+    use branded::Branded;
+    use okapi::schemars::schema_for;
+
+    #[test]
+    fn test_okapi_derive() {
+        #[derive(Branded)]
+        #[branded(okapi)]
+        pub struct UserId(String);
+
+        assert_eq!(schema_for!(UserId), schema_for!(String));
+    }
+
+    #[test]
+    fn test_okapi_example_and_format() {
+        #[derive(Branded)]
+        #[branded(okapi, example = "018f3a2b-7c1d-7e4f-9a0b-1c2d3e4f5a6b", schema_format = "uuid")]
+        pub struct UserId(String);
+
+        #[derive(Branded)]
+        #[branded(okapi, example = "42")]
+        pub struct ShardId(u16);
+
+        let schema = serde_json::to_value(schema_for!(UserId)).unwrap();
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["format"], "uuid");
+        assert_eq!(schema["examples"], serde_json::json!(["018f3a2b-7c1d-7e4f-9a0b-1c2d3e4f5a6b"]));
+
+        let schema = serde_json::to_value(schema_for!(ShardId)).unwrap();
+        assert_eq!(schema["examples"], serde_json::json!([42]));
+    }
+}
+
+#[cfg(feature = "aide")]
+mod aide {
+    use aide::axum::routing::get;
+    use aide::axum::ApiRouter;
+    use aide::openapi::{OpenApi, Parameter, ReferenceOr};
+    use branded::axum::IdPath;
+    use branded::Branded;
+
+    #[test]
+    fn test_aide_id_path() {
+        #[derive(Branded)]
+        #[branded(aide)]
+        pub struct UserId(u64);
+
+        async fn handler(IdPath(user_id): IdPath<UserId>) -> String { user_id.to_string() }
+
+        let mut api = OpenApi::default();
+        let _router: axum::Router =
+            ApiRouter::new().api_route("/users/{user_id}", get(handler)).finish_api(&mut api);
+
+        let operation = api.paths.unwrap().paths["/users/{user_id}"].clone();
+        let operation = operation.as_item().unwrap().get.clone().unwrap();
+        let Some(ReferenceOr::Item(Parameter::Path {
+            parameter_data,
+            ..
+        })) = operation.parameters.first()
+        else {
+            panic!("expected a path parameter");
+        };
+        assert_eq!(parameter_data.name, "user_id");
+        assert!(parameter_data.required);
+    }
+}
+
+#[cfg(feature = "cynic")]
+mod cynic {
+    use branded::Branded;
+    use cynic::QueryBuilder;
+
+    mod schema {
+        cynic::use_schema!("tests/cynic.graphql");
+    }
+
+    #[derive(Branded)]
+    #[branded(cynic = "schema::ID")]
+    pub struct UserId(String);
+
+    #[derive(cynic::QueryVariables)]
+    pub struct UserVariables {
+        id: UserId,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(schema_path = "tests/cynic.graphql")]
+    pub struct User {
+        id: UserId,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(
+        schema_path = "tests/cynic.graphql",
+        graphql_type = "Query",
+        variables = "UserVariables"
+    )]
+    pub struct UserQuery {
+        #[arguments(id: $id)]
+        user: Option<User>,
+    }
+
+    #[test]
+    fn test_cynic_scalar() {
+        let operation = UserQuery::build(UserVariables {
+            id: UserId::new("u1".to_owned()),
+        });
+        assert!(operation.query.contains("query UserQuery($id: ID!)"));
+        assert_eq!(
+            serde_json::to_value(&operation.variables).unwrap(),
+            serde_json::json!({ "id": "u1" })
+        );
+
+        let response: UserQuery =
+            serde_json::from_value(serde_json::json!({ "user": { "id": "u1" } })).unwrap();
+        assert_eq!(response.user.unwrap().id, UserId::new("u1".to_owned()));
+    }
+}
+
+#[cfg(feature = "prometheus")]
+mod prometheus {
+    use branded::Branded;
+    use prometheus_client::encoding::text::encode;
+    use prometheus_client::encoding::EncodeLabelSet;
+    use prometheus_client::metrics::counter::Counter;
+    use prometheus_client::metrics::family::Family;
+    use prometheus_client::registry::Registry;
+
+    #[test]
+    fn test_prometheus_derive() {
+        #[derive(Branded)]
+        #[branded(prometheus)]
+        pub struct Region(String);
+
+        #[derive(Branded)]
+        #[branded(prometheus)]
+        pub struct ShardId(u16);
+
+        #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+        struct Labels {
+            region: Region,
+            shard: ShardId,
+        }
+
+        let family = Family::<Labels, Counter>::default();
+        let mut registry = Registry::default();
+        registry.register("requests", "Requests", family.clone());
+        family
+            .get_or_create(&Labels {
+                region: Region::new("eu-north-1".to_owned()),
+                shard: ShardId::new(7),
+            })
+            .inc();
+
+        let mut output = String::new();
+        encode(&mut output, &registry).unwrap();
+        assert!(output.contains(r#"requests_total{region="eu-north-1",shard="7"} 1"#));
+    }
+}
+
+#[cfg(feature = "sentry")]
+mod sentry {
+    use branded::{sentry_context, sentry_tag, Branded};
+    use sentry_core::protocol::{Context, Value};
+    use sentry_core::test::with_captured_events;
+
+    #[test]
+    fn test_sentry_derive() {
+        #[derive(Branded)]
+        #[branded(sentry)]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        #[branded(sentry(pii))]
+        pub struct EmailAddress(String);
+
+        let user_id = UserId::new(42);
+        let email = EmailAddress::new("jane@example.com".to_owned());
+        let events = with_captured_events(|| {
+            sentry_tag!(user_id, email);
+            sentry_context!("account", user_id, email);
+            sentry_core::capture_message("failed", sentry_core::Level::Error);
+        });
+
+        let event = &events[0];
+        assert_eq!(event.tags["user_id"], "42");
+        assert_eq!(event.tags["email_address"], "[redacted]");
+        let Context::Other(account) = &event.contexts["account"] else {
+            panic!("expected a custom context");
+        };
+        assert_eq!(account["user_id"], Value::from("42"));
+        assert_eq!(account["email_address"], Value::from("[redacted]"));
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use branded::mysql::{FromValue, Value};
+    use branded::Branded;
+
+    #[test]
+    fn test_mysql_derive() {
+        #[derive(Branded)]
+        #[branded(mysql)]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        #[branded(mysql)]
+        pub struct UserName(String);
+
+        assert_eq!(Value::from(UserId::new(42)), Value::UInt(42));
+        assert_eq!(UserId::from_value(Value::Int(42)), UserId::new(42));
+        assert_eq!(UserId::from_value(Value::Bytes(b"42".to_vec())), UserId::new(42));
+        assert!(UserId::from_value_opt(Value::Int(-1)).is_err());
+
+        assert_eq!(Value::from(UserName::new("jane".to_owned())), Value::Bytes(b"jane".to_vec()));
+        assert_eq!(
+            UserName::from_value(Value::Bytes(b"jane".to_vec())),
+            UserName::new("jane".to_owned())
+        );
+        assert_eq!(Option::<UserId>::from_value(Value::NULL), None);
+    }
+
+    #[test]
+    fn test_mysql_validated() {
+        #[derive(Branded)]
+        #[branded(mysql, range = "1..")]
+        pub struct UserId(u64);
+
+        assert!(UserId::from_value_opt(Value::UInt(0)).is_err());
+        assert_eq!(UserId::from_value(Value::UInt(1)).into_inner(), 1);
+    }
+}
+
+#[cfg(feature = "gel")]
+mod gel {
+    use branded::gel::protocol::query_arg::ScalarArg;
+    use branded::gel::protocol::queryable::{Decoder, Queryable};
+    use branded::gel::protocol::value::Value;
+    use branded::Branded;
+
+    #[test]
+    fn test_gel_derive() {
+        #[derive(Branded)]
+        #[branded(gel)]
+        pub struct UserId(uuid::Uuid);
+
+        let uuid = uuid::Uuid::new_v4();
+        let user_id = UserId::decode(&Decoder::default(), &(), uuid.as_bytes()).unwrap();
+        assert_eq!(user_id, UserId::new(uuid));
+        assert!(UserId::decode(&Decoder::default(), &(), &[0; 4]).is_err());
+        assert!(
+            matches!(ScalarArg::to_value(&user_id).unwrap(), Value::Uuid(value) if value == uuid)
+        );
+    }
+
+    #[test]
+    fn test_gel_validated() {
+        #[derive(Branded)]
+        #[branded(gel, range = "1..")]
+        pub struct Quantity(i64);
+
+        let decoder = Decoder::default();
+        assert!(Quantity::decode(&decoder, &(), &0i64.to_be_bytes()).is_err());
+        assert!(Quantity::decode_optional(&decoder, &(), Some(&0i64.to_be_bytes())).is_err());
+        assert_eq!(Quantity::decode(&decoder, &(), &1i64.to_be_bytes()).unwrap().into_inner(), 1);
+    }
+}
+
+#[cfg(feature = "zbus")]
+mod zbus {
+    use branded::zbus::zvariant::serialized::{Context, Format};
+    use branded::zbus::zvariant::{to_bytes, Type, LE};
+    use branded::Branded;
+
+    #[test]
+    fn test_zbus_derive() {
+        #[derive(Branded)]
+        #[branded(zbus)]
+        pub struct SessionId(u64);
+
+        assert_eq!(SessionId::SIGNATURE, u64::SIGNATURE);
+        assert_eq!(<(SessionId, String)>::SIGNATURE.to_string(), "(ts)");
+
+        let context = Context::new(Format::DBus, LE, 0);
+        let encoded = to_bytes(context, &SessionId::new(42)).unwrap();
+        assert_eq!(encoded.bytes(), to_bytes(context, &42u64).unwrap().bytes());
+        let (decoded, _): (SessionId, _) = encoded.deserialize().unwrap();
+        assert_eq!(decoded, SessionId::new(42));
+    }
+}
+
+#[cfg(feature = "newtype_uuid")]
+mod newtype_uuid {
+    use branded::Branded;
+    use newtype_uuid::{GenericUuid, TypedUuid, TypedUuidKind, TypedUuidTag};
+
+    pub enum UserKind {}
+
+    impl TypedUuidKind for UserKind {
+        fn tag() -> TypedUuidTag {
+            const TAG: TypedUuidTag = TypedUuidTag::new("user");
+            TAG
+        }
+    }
+
+    #[test]
+    fn test_newtype_uuid_derive() {
+        #[derive(Branded)]
+        #[branded(uuid, newtype_uuid = "UserKind")]
+        pub struct UserId(uuid::Uuid);
+
+        let uuid = uuid::Uuid::new_v4();
+        let typed = TypedUuid::<UserKind>::from_untyped_uuid(uuid);
+        let user_id = UserId::from(typed);
+        assert_eq!(user_id.inner(), &uuid);
+        assert_eq!(user_id.as_untyped_uuid(), &uuid);
+        assert_eq!(TypedUuid::<UserKind>::from(user_id), typed);
+        assert_eq!(UserId::from_untyped_uuid(uuid).into_untyped_uuid(), uuid);
+    }
+}
+
+#[cfg(feature = "rustler")]
+mod rustler {
+    use branded::Branded;
+
+    #[test]
+    fn test_rustler_derive() {
+        #[derive(Branded)]
+        #[branded(rustler)]
+        pub struct UserId(u64);
+
+        fn needs_encoder<T: rustler::Encoder>() {}
+        fn needs_decoder<'a, T: rustler::Decoder<'a>>() {}
+
+        needs_encoder::<UserId>();
+        needs_decoder::<UserId>();
+    }
+
+    // Decoding needs a running BEAM, so this only checks that validated brands implement Decoder.
+    #[test]
+    fn test_rustler_validated() {
+        #[derive(Branded)]
+        #[branded(rustler, range = "1..")]
+        pub struct UserId(u64);
+
+        fn needs_decoder<'a, T: rustler::Decoder<'a>>() {}
+
+        needs_decoder::<UserId>();
+    }
+}
+
+#[cfg(feature = "pseudonymize")]
+mod pseudonymize {
+    use branded::pseudonymize::{pseudonymize, Pseudonymizer};
+    use branded::Branded;
+
+    #[test]
+    fn test_pseudonymize() {
+        #[derive(Branded)]
+        pub struct UserId(u64);
+
+        #[derive(Branded)]
+        pub struct OrderId(u64);
+
+        let pseudonym = pseudonymize(&UserId::new(42), b"key");
+        assert_eq!(pseudonym.to_string(), "219c0e27b57c88ee890c6db1d50061ff");
+        assert_eq!(pseudonym.base64().to_string(), "IZwOJ7V8iO6JDG2x1QBh_w");
+        assert_ne!(pseudonymize(&UserId::new(42), b"other key"), pseudonym);
+        assert_ne!(pseudonymize(&OrderId::new(42), b"key"), pseudonym);
+
+        let pseudonymizer = Pseudonymizer::new(b"key");
+        let ids = [UserId::new(42), UserId::new(7), UserId::new(42)];
+        let pseudonyms = pseudonymizer.pseudonymize_all(&ids).collect::<Vec<_>>();
+        assert_eq!(pseudonyms[0], pseudonym);
+        assert_eq!(pseudonyms[1], pseudonymize(&UserId::new(7), b"key"));
+        assert_eq!(pseudonyms[2], pseudonym);
+    }
+}
+
+#[cfg(feature = "bitset")]
+mod bitset {
+    use branded::bitset::BrandedBitSet;
+    use branded::Branded;
+
+    #[derive(Branded)]
+    #[branded(bitset)]
+    pub struct OrderId(u32);
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BrandedBitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(OrderId::new(3)));
+        assert!(set.insert(OrderId::new(130)));
+        assert!(!set.insert(OrderId::new(3)));
+        assert!(set.contains(&OrderId::new(130)));
+        assert!(!set.contains(&OrderId::new(4)));
+        assert_eq!(set.len(), 2);
+        assert!(set.remove(&OrderId::new(130)));
+        assert!(!set.remove(&OrderId::new(130)));
+        assert_eq!(set, [OrderId::new(3)].into_iter().collect());
+    }
+
+    #[test]
+    fn test_bitset_set_operations() {
+        let a = [1, 2, 64, 200].map(OrderId::new).into_iter().collect::<BrandedBitSet<_>>();
+        let b = [2, 64, 65].map(OrderId::new).into_iter().collect::<BrandedBitSet<_>>();
+        let union = a.union(&b).iter().map(OrderId::into_inner).collect::<Vec<_>>();
+        assert_eq!(union, [1, 2, 64, 65, 200]);
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection.iter().map(OrderId::into_inner).collect::<Vec<_>>(), [2, 64]);
+        assert_eq!(intersection, b.intersection(&a));
+        assert_eq!(format!("{intersection:?}"), "{OrderId(2), OrderId(64)}");
     }
 }